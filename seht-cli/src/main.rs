@@ -0,0 +1,129 @@
+use std::process::ExitCode;
+use std::str::FromStr;
+use seht_core::build::BuildSpec;
+use seht_core::entities::level::CraftingLevel;
+use seht_core::entities::materials::MaterialBill;
+use seht_core::piece::{self, SetPiece};
+
+const USAGE: &str = "Uso: seht-cli [--spec <FICHERO>] [--piece <PIEZA>...] [--level <NIVEL>] [--research] [--format text|json|csv]
+
+  --spec      Especificación de build en TOML o JSON, se suma a las piezas de --piece
+  --piece     Pieza:Peso:Rasgo:Calidad[:Glifo][:Nivel] para armaduras, sin peso para armas y joyas
+              p. ej. \"Cabeza:Pesada:Divinidad:Amarilla:Glifo de salud\"
+  --level     Nivel de las piezas que no indican uno (por defecto CP160)
+  --research  Muestra los materiales para investigar los rasgos en lugar de craftear
+  --format    Formato de salida: text (por defecto), json o csv";
+
+enum Format {
+    Text,
+    Json,
+    Csv
+}
+
+struct Args {
+    pieces: Vec<String>,
+    spec: Option<String>,
+    level: CraftingLevel,
+    research: bool,
+    format: Format
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS
+        }
+        Err(why) => {
+            eprintln!("{}\n\n{}", why, USAGE);
+            return ExitCode::from(2)
+        }
+    };
+
+    let mut pieces = match &args.spec {
+        Some(path) => match std::fs::read_to_string(path).map_err(|e| e.to_string()).and_then(|text| BuildSpec::parse(&text)) {
+            Ok(spec) => spec.pieces,
+            Err(why) => {
+                eprintln!("{}: {}", path, why);
+                return ExitCode::from(2)
+            }
+        },
+        None => vec![]
+    };
+    match args.pieces.iter()
+        .map(|spec| piece::parse_piece(spec, args.level))
+        .collect::<Result<Vec<SetPiece>, String>>() {
+        Ok(parsed) => pieces.extend(parsed),
+        Err(why) => {
+            eprintln!("{}", why);
+            return ExitCode::from(2)
+        }
+    };
+
+    // Same aggregation the bot uses for its material embeds
+    let bill = if args.research { piece::research_cost(&pieces) } else { piece::material_cost(&pieces) };
+    print!("{}", match args.format {
+        Format::Text => to_text(&bill),
+        Format::Json => to_json(&bill),
+        Format::Csv => to_csv(&bill)
+    });
+    ExitCode::SUCCESS
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Option<Args>, String> {
+    let mut parsed = Args { pieces: vec![], spec: None, level: CraftingLevel::CP160, research: false, format: Format::Text };
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "--research" => parsed.research = true,
+            "--piece" => parsed.pieces.push(value(&mut args, &arg)?),
+            "--spec" => parsed.spec = Some(value(&mut args, &arg)?),
+            "--level" => {
+                let level = value(&mut args, &arg)?;
+                parsed.level = CraftingLevel::from_str(&level)
+                    .map_err(|_| format!("`{}` no es un nivel válido", level))?;
+            }
+            "--format" => parsed.format = match value(&mut args, &arg)?.as_str() {
+                "text" => Format::Text,
+                "json" => Format::Json,
+                "csv" => Format::Csv,
+                format => return Err(format!("Formato `{}` desconocido", format))
+            },
+            _ => return Err(format!("Argumento `{}` desconocido", arg))
+        }
+    }
+
+    if parsed.pieces.is_empty() && parsed.spec.is_none() {
+        return Err("Indica al menos una pieza con --piece o una build con --spec".to_string());
+    }
+    Ok(Some(parsed))
+}
+
+fn value(args: &mut impl Iterator<Item = String>, flag: &str) -> Result<String, String> {
+    args.next().ok_or_else(|| format!("Falta el valor de {}", flag))
+}
+
+fn to_text(bill: &MaterialBill) -> String {
+    if bill.is_empty() {
+        return "Sin materiales\n".to_string();
+    }
+    bill.iter()
+        .map(|(material, amount)| format!("{}: {}\n", material, amount))
+        .collect()
+}
+
+fn to_json(bill: &MaterialBill) -> String {
+    let materials: Vec<serde_json::Value> = bill.iter()
+        .map(|(material, amount)| serde_json::json!({ "material": material.to_string(), "amount": amount }))
+        .collect();
+    format!("{}\n", serde_json::Value::Array(materials))
+}
+
+fn to_csv(bill: &MaterialBill) -> String {
+    let mut csv = "material,amount\n".to_string();
+    for (material, amount) in bill.iter() {
+        csv.push_str(&format!("\"{}\",{}\n", material.to_string().replace('"', "\"\""), amount));
+    }
+    csv
+}
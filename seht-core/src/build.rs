@@ -0,0 +1,158 @@
+//! Build specifications describe a whole gear order outside of the Discord conversation: the set
+//! and every piece with the same values offered in the bot menus. They can be written as TOML or
+//! JSON, both parsed into the same [`BuildSpec`].
+//!
+//! Each piece has a `tipo` (`arma`, `armadura` or `joya`), the `pieza`, its `rasgo` and `calidad`,
+//! an optional `glifo` and an optional `nivel` (CP160 when missing). Armour also needs a `peso`.
+//!
+//! ```toml
+//! set = "Cólera de la orden"
+//!
+//! [[piezas]]
+//! tipo = "armadura"
+//! pieza = "Cabeza"
+//! peso = "Pesada"
+//! rasgo = "Divinidad"
+//! calidad = "Amarilla"
+//! glifo = "Glifo de salud"
+//!
+//! [[piezas]]
+//! tipo = "arma"
+//! pieza = "Bastón infernal"
+//! rasgo = "Precisión"
+//! calidad = "Morada"
+//! nivel = "CP150"
+//! ```
+//!
+//! ```json
+//! {
+//!   "set": "Cólera de la orden",
+//!   "piezas": [
+//!     { "tipo": "joya", "pieza": "Anillo", "rasgo": "Arcanidad", "calidad": "Amarilla" }
+//!   ]
+//! }
+//! ```
+//!
+//! For quick pastes there is also a plain text form, one piece per line written as
+//! `Pieza[:Peso]:Rasgo:Calidad[:Glifo][:Nivel]` and an optional `Set:` line:
+//!
+//! ```text
+//! Set: Cólera de la orden
+//! Cabeza:Pesada:Divinidad:Amarilla:Glifo de salud
+//! Bastón infernal:Precisión:Morada:CP150
+//! ```
+
+use serde::{Deserialize, Serialize};
+use crate::entities::level::CraftingLevel;
+use crate::piece::{parse_piece, SetPiece};
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct BuildSpec {
+    #[serde(rename = "set")]
+    pub set_name: String,
+    #[serde(rename = "piezas", default)]
+    pub pieces: Vec<SetPiece>
+}
+
+impl BuildSpec {
+    pub fn new(set_name: &str, pieces: Vec<SetPiece>) -> Self {
+        BuildSpec { set_name: set_name.to_string(), pieces }
+    }
+
+    /// Parses a JSON spec when the text starts with `{`, TOML when it has a `set =` or `piezas =` key
+    /// or a table, and the plain text form otherwise. Text that looks like TOML but does not parse
+    /// is still tried as plain text, reporting the TOML error if that fails too
+    pub fn parse(text: &str) -> Result<Self, String> {
+        if text.trim_start().starts_with('{') {
+            return Self::from_json(text)
+        }
+        if !looks_like_toml(text) {
+            return Self::from_lines(text)
+        }
+        Self::from_toml(text).or_else(|toml_error| Self::from_lines(text).map_err(|_| toml_error))
+    }
+
+    pub fn from_lines(text: &str) -> Result<Self, String> {
+        let mut spec = BuildSpec::new("", vec![]);
+        for (idx, line) in text.lines().enumerate() {
+            let line = line.trim().trim_start_matches(['-', '*']).trim();
+            if line.is_empty() {
+                continue;
+            }
+            match line.split_once(':') {
+                Some((key, set)) if key.trim().eq_ignore_ascii_case("set") => spec.set_name = set.trim().to_string(),
+                _ => spec.pieces.push(parse_piece(line, CraftingLevel::default())
+                    .map_err(|why| format!("Línea {}: {}", idx + 1, why))?)
+            }
+        }
+        Ok(spec)
+    }
+
+    pub fn from_toml(text: &str) -> Result<Self, String> {
+        toml::from_str(text).map_err(|e| e.to_string())
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        serde_json::from_str(text).map_err(|e| e.to_string())
+    }
+
+    pub fn to_toml(&self) -> String {
+        toml::to_string(self).expect("build specs always serialize")
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("build specs always serialize")
+    }
+}
+
+fn looks_like_toml(text: &str) -> bool {
+    text.lines().map(str::trim).any(|line| {
+        line.starts_with('[') || line.split_once('=')
+            .is_some_and(|(key, _)| matches!(key.trim().trim_matches('"'), "set" | "piezas"))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_toml_with_inline_pieces_and_spaced_tables() {
+        let inline = r#"set = "Cólera de la orden"
+piezas = [ { tipo = "joya", pieza = "Anillo", rasgo = "Arcanidad", calidad = "Amarilla" } ]"#;
+        let spaced = "set = \"Cólera de la orden\"\n[[ piezas ]]\ntipo = \"joya\"\npieza = \"Anillo\"\nrasgo = \"Arcanidad\"\ncalidad = \"Amarilla\"";
+        for text in [inline, spaced] {
+            let spec = BuildSpec::parse(text).unwrap();
+            assert_eq!(spec.set_name, "Cólera de la orden");
+            assert_eq!(spec.pieces.len(), 1);
+        }
+    }
+
+    #[test]
+    fn reports_the_toml_error_for_broken_toml() {
+        let why = BuildSpec::parse("set = \"Cólera de la orden\"\n[[piezas]]\ntipo = \"joya\"\npieza = \"Anillo\"").err().unwrap();
+        assert!(!why.starts_with("Línea"), "{}", why);
+    }
+
+    #[test]
+    fn parses_the_plain_text_form() {
+        let spec = BuildSpec::parse("Set: Cólera de la orden\n- Cabeza:Pesada:Divinidad:Amarilla:Glifo de salud\n\n* Bastón infernal:Precisión:Morada:CP150").unwrap();
+        assert_eq!(spec.set_name, "Cólera de la orden");
+        assert_eq!(spec.pieces.len(), 2);
+        assert!(spec.pieces[0].level() == CraftingLevel::default());
+        assert!(spec.pieces[1].level() == CraftingLevel::ChampionPoints(150));
+    }
+
+    #[test]
+    fn points_to_the_line_of_a_wrong_piece() {
+        let why = BuildSpec::parse("Set: Cólera de la orden\nCabeza:Divinidad:Amarilla").err().unwrap();
+        assert!(why.starts_with("Línea 2: "), "{}", why);
+    }
+
+    #[test]
+    fn round_trips_through_json_and_toml() {
+        let spec = BuildSpec::parse("Set: Cólera de la orden\nCabeza:Pesada:Divinidad:Amarilla:Glifo de salud:CP150\nAnillo:Arcanidad:Azul").unwrap();
+        assert_eq!(BuildSpec::parse(&spec.to_json()).unwrap().to_json(), spec.to_json());
+        assert_eq!(BuildSpec::parse(&spec.to_toml()).unwrap().to_json(), spec.to_json());
+    }
+}
@@ -0,0 +1,202 @@
+use std::fmt::{Display, Formatter};
+use strum::IntoEnumIterator;
+use strum_macros::{Display, EnumIter, EnumString};
+use crate::entities::materials::{MaterialBill, Reagents, Solvents};
+
+#[derive(EnumIter, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, EnumString, Display)]
+pub enum Effect {
+    #[strum(serialize = "Restaurar salud")] RestoreHealth,
+    #[strum(serialize = "Dañar salud")] RavageHealth,
+    #[strum(serialize = "Restaurar magia")] RestoreMagicka,
+    #[strum(serialize = "Dañar magia")] RavageMagicka,
+    #[strum(serialize = "Restaurar aguante")] RestoreStamina,
+    #[strum(serialize = "Dañar aguante")] RavageStamina,
+    #[strum(serialize = "Aumentar poder de hechizo")] SpellPower,
+    #[strum(serialize = "Reducir poder de hechizo")] Cowardice,
+    #[strum(serialize = "Aumentar poder de arma")] WeaponPower,
+    #[strum(serialize = "Reducir poder de arma")] Maim,
+    #[strum(serialize = "Crítico de hechizo")] SpellCritical,
+    #[strum(serialize = "Incertidumbre")] Uncertainty,
+    #[strum(serialize = "Crítico de arma")] WeaponCritical,
+    #[strum(serialize = "Enervación")] Enervation,
+    #[strum(serialize = "Aumentar resistencia a hechizos")] SpellResist,
+    #[strum(serialize = "Reducir resistencia a hechizos")] Breach,
+    #[strum(serialize = "Aumentar armadura")] Armor,
+    #[strum(serialize = "Reducir armadura")] Fracture,
+    #[strum(serialize = "Protección")] Protection,
+    #[strum(serialize = "Vulnerabilidad")] Vulnerability,
+    #[strum(serialize = "Velocidad")] Speed,
+    #[strum(serialize = "Entorpecimiento")] Hindrance,
+    #[strum(serialize = "Invisibilidad")] Invisible,
+    #[strum(serialize = "Detección")] Detection,
+    #[strum(serialize = "Imparable")] Unstoppable,
+    #[strum(serialize = "Atrapamiento")] Entrapment,
+    #[strum(serialize = "Salud persistente")] LingeringHealth,
+    #[strum(serialize = "Daño de salud gradual")] GradualRavageHealth,
+    #[strum(serialize = "Vitalidad")] Vitality,
+    #[strum(serialize = "Profanación")] Defile
+}
+
+impl Effect {
+    /// Effect cancelled out when both end up in the same mix
+    pub fn opposite(&self) -> Effect {
+        match self {
+            Effect::RestoreHealth => Effect::RavageHealth,
+            Effect::RavageHealth => Effect::RestoreHealth,
+            Effect::RestoreMagicka => Effect::RavageMagicka,
+            Effect::RavageMagicka => Effect::RestoreMagicka,
+            Effect::RestoreStamina => Effect::RavageStamina,
+            Effect::RavageStamina => Effect::RestoreStamina,
+            Effect::SpellPower => Effect::Cowardice,
+            Effect::Cowardice => Effect::SpellPower,
+            Effect::WeaponPower => Effect::Maim,
+            Effect::Maim => Effect::WeaponPower,
+            Effect::SpellCritical => Effect::Uncertainty,
+            Effect::Uncertainty => Effect::SpellCritical,
+            Effect::WeaponCritical => Effect::Enervation,
+            Effect::Enervation => Effect::WeaponCritical,
+            Effect::SpellResist => Effect::Breach,
+            Effect::Breach => Effect::SpellResist,
+            Effect::Armor => Effect::Fracture,
+            Effect::Fracture => Effect::Armor,
+            Effect::Protection => Effect::Vulnerability,
+            Effect::Vulnerability => Effect::Protection,
+            Effect::Speed => Effect::Hindrance,
+            Effect::Hindrance => Effect::Speed,
+            Effect::Invisible => Effect::Detection,
+            Effect::Detection => Effect::Invisible,
+            Effect::Unstoppable => Effect::Entrapment,
+            Effect::Entrapment => Effect::Unstoppable,
+            Effect::LingeringHealth => Effect::GradualRavageHealth,
+            Effect::GradualRavageHealth => Effect::LingeringHealth,
+            Effect::Vitality => Effect::Defile,
+            Effect::Defile => Effect::Vitality
+        }
+    }
+}
+
+impl Reagents {
+    /// Effects of each reagent in the order the game reveals them, as listed in
+    /// https://en.uesp.net/wiki/Online:Alchemy#Reagents
+    pub fn effects(&self) -> [Effect; 4] {
+        match self {
+            Reagents::Cornflower => [Effect::RestoreMagicka, Effect::RavageHealth, Effect::SpellPower, Effect::Detection],
+            Reagents::LadysSmock => [Effect::SpellPower, Effect::RestoreMagicka, Effect::Breach, Effect::SpellCritical],
+            Reagents::WaterHyacinth => [Effect::RestoreHealth, Effect::SpellCritical, Effect::WeaponCritical, Effect::Entrapment],
+            Reagents::Bugloss => [Effect::SpellResist, Effect::RestoreHealth, Effect::Cowardice, Effect::RestoreMagicka],
+            Reagents::Columbine => [Effect::RestoreHealth, Effect::RestoreMagicka, Effect::RestoreStamina, Effect::Unstoppable],
+            Reagents::MountainFlower => [Effect::Armor, Effect::RestoreHealth, Effect::Maim, Effect::RestoreStamina],
+            Reagents::BlessedThistle => [Effect::RestoreStamina, Effect::WeaponPower, Effect::RavageHealth, Effect::Speed],
+            Reagents::Dragonthorn => [Effect::WeaponPower, Effect::RestoreStamina, Effect::Fracture, Effect::WeaponCritical],
+            Reagents::Wormwood => [Effect::WeaponCritical, Effect::Hindrance, Effect::Detection, Effect::Unstoppable],
+            Reagents::Nightshade => [Effect::RavageHealth, Effect::Protection, Effect::GradualRavageHealth, Effect::Defile],
+            Reagents::Nirnroot => [Effect::RavageHealth, Effect::Uncertainty, Effect::Enervation, Effect::Invisible],
+            Reagents::NamirasRot => [Effect::SpellCritical, Effect::Speed, Effect::Invisible, Effect::Unstoppable],
+            Reagents::BlueEntoloma => [Effect::RavageMagicka, Effect::RestoreHealth, Effect::Cowardice, Effect::Invisible],
+            Reagents::EmeticRussula => [Effect::RavageHealth, Effect::RavageMagicka, Effect::RavageStamina, Effect::Entrapment],
+            Reagents::Stinkhorn => [Effect::Fracture, Effect::RavageHealth, Effect::WeaponPower, Effect::RavageStamina],
+            Reagents::WhiteCap => [Effect::Cowardice, Effect::RavageMagicka, Effect::SpellResist, Effect::Detection],
+            Reagents::ImpStool => [Effect::Maim, Effect::RavageStamina, Effect::Armor, Effect::Enervation]
+        }
+    }
+}
+
+/// Effects a mix of reagents ends up with: those shared by at least two reagents,
+/// minus any pair of opposite effects that cancel each other
+pub fn mix_effects(reagents: &[Reagents]) -> Vec<Effect> {
+    let shared: Vec<Effect> = Effect::iter()
+        .filter(|e| reagents.iter().filter(|r| r.effects().contains(e)).count() >= 2)
+        .collect();
+    shared.iter()
+        .filter(|e| !shared.contains(&e.opposite()))
+        .copied()
+        .collect()
+}
+
+/// Reagents, solvent and resulting effects of one alchemy craft
+pub struct AlchemyRecipe {
+    pub reagents: Vec<Reagents>,
+    pub solvent: Solvents,
+    pub effects: Vec<Effect>
+}
+
+impl AlchemyRecipe {
+    /// Reagents and solvent for the given number of crafts, each using one of every ingredient
+    pub fn bill(&self, crafts: i32) -> MaterialBill {
+        self.reagents.iter()
+            .fold(MaterialBill::new().with(crafts, self.solvent), |bill, reagent| bill.with(crafts, *reagent))
+    }
+}
+
+impl Display for AlchemyRecipe {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let reagents = self.reagents.iter().map(|r| r.to_string()).collect::<Vec<_>>().join(" + ");
+        write!(f, "{} + {}", reagents, self.solvent)
+    }
+}
+
+/// Mixes of two or three reagents giving every wanted effect, best first: fewer reagents and then
+/// fewer unwanted effects. Three reagent mixes where one of them adds nothing are left out
+pub fn solve(wanted: &[Effect], solvent: Solvents) -> Vec<AlchemyRecipe> {
+    let reagents: Vec<Reagents> = Reagents::iter().collect();
+    let mut mixes = vec![];
+    for (i, first) in reagents.iter().enumerate() {
+        for (j, second) in reagents.iter().enumerate().skip(i + 1) {
+            mixes.push(vec![*first, *second]);
+            for third in reagents.iter().skip(j + 1) {
+                mixes.push(vec![*first, *second, *third]);
+            }
+        }
+    }
+
+    let mut recipes: Vec<AlchemyRecipe> = mixes.into_iter()
+        .map(|mix| AlchemyRecipe { effects: mix_effects(&mix), reagents: mix, solvent })
+        .filter(|recipe| wanted.iter().all(|e| recipe.effects.contains(e)))
+        .filter(|recipe| recipe.reagents.iter().all(|r| r.effects().iter().any(|e| recipe.effects.contains(e))))
+        .collect();
+    recipes.sort_by_key(|recipe| (recipe.reagents.len(), recipe.effects.len().saturating_sub(wanted.len())));
+    recipes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::consumables::{Recipe, CATALOG};
+
+    #[test]
+    fn blessed_thistle_ravages_health() {
+        assert!(Reagents::BlessedThistle.effects().contains(&Effect::RavageHealth));
+        assert!(!Reagents::BlessedThistle.effects().contains(&Effect::RestoreHealth));
+        let effects = mix_effects(&[Reagents::BlessedThistle, Reagents::Nirnroot]);
+        assert!(effects == [Effect::RavageHealth]);
+    }
+
+    #[test]
+    fn solves_the_spell_power_essence_with_the_magicka_flowers() {
+        let wanted = [Effect::RestoreMagicka, Effect::SpellPower, Effect::SpellCritical];
+        let recipe = solve(&wanted, Solvents::LorkhansTears).into_iter().next().unwrap();
+        assert!(recipe.reagents == [Reagents::Cornflower, Reagents::LadysSmock, Reagents::WaterHyacinth]);
+        assert!(recipe.solvent == Solvents::LorkhansTears);
+        assert!(recipe.effects == wanted);
+        let recipe = solve(&[Effect::SpellPower], Solvents::LorkhansTears).into_iter().next().unwrap();
+        assert!(recipe.reagents == [Reagents::Cornflower, Reagents::LadysSmock]);
+    }
+
+    #[test]
+    fn opposite_effects_cancel_each_other() {
+        let effects = mix_effects(&[Reagents::Cornflower, Reagents::LadysSmock, Reagents::Bugloss]);
+        assert!(effects.contains(&Effect::SpellPower) && effects.contains(&Effect::RestoreMagicka));
+        let effects = mix_effects(&[Reagents::Columbine, Reagents::Bugloss, Reagents::Nirnroot, Reagents::Nightshade]);
+        assert!(!effects.contains(&Effect::RestoreHealth) && !effects.contains(&Effect::RavageHealth));
+        assert!(solve(&[Effect::SpellPower, Effect::Cowardice], Solvents::LorkhansTears).is_empty());
+    }
+
+    #[test]
+    fn every_potion_and_poison_in_the_catalog_has_a_recipe() {
+        for item in CATALOG.iter().filter(|item| matches!(item.recipe, Recipe::Alchemy(_))) {
+            let recipe = item.alchemy().unwrap_or_else(|| panic!("{} has no recipe", item.name_en));
+            let Recipe::Alchemy(wanted) = item.recipe else { unreachable!() };
+            assert!(wanted.iter().all(|e| recipe.effects.contains(e)), "{}", item.name_en);
+        }
+    }
+}
@@ -0,0 +1,173 @@
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumIter, EnumMessage, EnumProperty, EnumString};
+use crate::entities::{GearQuality, get_blacksmith_quality_cost, get_tailoring_quality_cost, get_woodworking_quality_cost, MaterialCost};
+use crate::entities::level::CraftingLevel;
+use crate::entities::glyph::{Enchantment, Glyph, Potency};
+use crate::entities::materials::{ArmourTraitMaterials, EssenceRunes, MaterialBill};
+
+#[derive(Clone, EnumIter, Ord, PartialOrd, Eq, PartialEq, Display, EnumString, EnumMessage)]
+pub enum ArmourParts {
+    #[strum(serialize = "Cabeza")]
+    Head,
+    #[strum(serialize = "Hombros")]
+    Shoulder,
+    #[strum(serialize = "Cuerpo")]
+    Body,
+    #[strum(serialize = "Manos")]
+    Hands,
+    #[strum(serialize = "Cintura")]
+    Waist,
+    #[strum(serialize = "Piernas")]
+    Legs,
+    #[strum(serialize = "Pies")]
+    Feet,
+    #[strum(serialize = "Escudo")]
+    Shield
+}
+
+#[derive(Clone, EnumIter, Ord, PartialOrd, Eq, PartialEq, Display, EnumString, EnumMessage)]
+pub enum ArmourWeights {
+    #[strum(serialize = "Ligera")]
+    Light,
+    #[strum(serialize = "Media")]
+    Medium,
+    #[strum(serialize = "Pesada")]
+    Heavy
+}
+
+#[derive(Clone, EnumIter, Ord, PartialOrd, Eq, PartialEq, Display, EnumString, EnumMessage)]
+pub enum ArmourTraits {
+    /// Aumenta los efectos de las piedras de Mundus
+    #[strum(serialize = "Divinidad")]
+    Divines,
+    /// Aumenta la recuperación de salud, magia y aguante
+    #[strum(serialize = "Vigorización")]
+    Invigorating,
+    /// Aumenta la resistencia a críticos y la durabilidad
+    #[strum(serialize = "Impenetrabilidad")]
+    Impenetrable,
+    /// Aumenta el efecto del encantamiento de la armadura
+    #[strum(serialize = "Imbuición")]
+    Infused,
+    /// Aumenta la resistencia física y a hechizos
+    #[strum(serialize = "Temple de Nirn")]
+    Nirnhoned,
+    /// Aumenta el valor de armadura de este objeto
+    #[strum(serialize = "Refuerzo")]
+    Reinforced,
+    /// Reduce el coste de bloquear
+    #[strum(serialize = "Solidez")]
+    Sturdy,
+    /// Aumenta la experiencia ganada con cada muerte
+    #[strum(serialize = "Entrenamiento")]
+    Training,
+    /// Reduce el coste de esquivar rodando y esprintar
+    #[strum(serialize = "Buen ajuste")]
+    WellFitted
+}
+
+#[derive(Clone, EnumIter, Ord, PartialOrd, Eq, PartialEq, Display, EnumString, EnumMessage, EnumProperty)]
+pub enum ArmourEnchantments {
+    /// Aumenta la salud máxima
+    #[strum(serialize = "Glifo de salud")]
+    #[strum(props(English = "Glyph of Health"))]
+    Health,
+    /// Aumenta la magia máxima
+    #[strum(serialize = "Glifo de magia")]
+    #[strum(props(English = "Glyph of Magicka"))]
+    Magicka,
+    /// Aumenta el aguante máximo
+    #[strum(serialize = "Glifo de aguante")]
+    #[strum(props(English = "Glyph of Stamina"))]
+    Stamina,
+    /// Aumente la magia, salud y aguante máximos
+    #[strum(serialize = "Glifo de defensa prismática")]
+    #[strum(props(English = "Glyph of Prismatic Defense"))]
+    PrismaticDefense
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Armour {
+    #[serde(rename = "pieza")]
+    pub kind: ArmourParts,
+    #[serde(rename = "peso")]
+    pub weight: ArmourWeights,
+    #[serde(rename = "rasgo")]
+    pub armour_trait: ArmourTraits,
+    #[serde(rename = "glifo", default, skip_serializing_if = "Option::is_none")]
+    pub enchantment: Option<ArmourEnchantments>,
+    #[serde(rename = "calidad")]
+    pub quality: GearQuality,
+    #[serde(rename = "nivel", default)]
+    pub level: CraftingLevel
+}
+
+impl MaterialCost for ArmourTraits {
+    fn cost(&self) -> MaterialBill {
+        match *self {
+            ArmourTraits::Divines => MaterialBill::new().with(1, ArmourTraitMaterials::Sapphire),
+            ArmourTraits::Invigorating => MaterialBill::new().with(1, ArmourTraitMaterials::Garnet),
+            ArmourTraits::Impenetrable => MaterialBill::new().with(1, ArmourTraitMaterials::Diamond),
+            ArmourTraits::Infused => MaterialBill::new().with(1, ArmourTraitMaterials::Bloodstone),
+            ArmourTraits::Nirnhoned => MaterialBill::new().with(1, ArmourTraitMaterials::FortifiedNirncrux),
+            ArmourTraits::Reinforced => MaterialBill::new().with(1, ArmourTraitMaterials::Sardonyx),
+            ArmourTraits::Sturdy => MaterialBill::new().with(1, ArmourTraitMaterials::Quartz),
+            ArmourTraits::Training => MaterialBill::new().with(1, ArmourTraitMaterials::Emerald),
+            ArmourTraits::WellFitted => MaterialBill::new().with(1, ArmourTraitMaterials::Almandine),
+        }
+    }
+}
+
+impl ArmourEnchantments {
+    /// Kind of potency rune and essence rune of the glyph, the exact potency rune depending on
+    /// its level and the aspect rune on its quality
+    pub fn runes(&self) -> (Potency, EssenceRunes) {
+        match *self {
+            ArmourEnchantments::Health => (Potency::Additive, EssenceRunes::Oko),
+            ArmourEnchantments::Magicka => (Potency::Additive, EssenceRunes::Makko),
+            ArmourEnchantments::Stamina => (Potency::Additive, EssenceRunes::Deni),
+            ArmourEnchantments::PrismaticDefense => (Potency::Additive, EssenceRunes::Hakeijo),
+        }
+    }
+}
+
+fn get_quality_mats(weight: &ArmourWeights, quality: &GearQuality) -> MaterialBill {
+    match weight {
+        ArmourWeights::Heavy => get_blacksmith_quality_cost(quality),
+        _ => get_tailoring_quality_cost(quality)
+    }
+}
+
+fn get_part_mats(part: &ArmourParts, weight: &ArmourWeights, level: &CraftingLevel) -> MaterialBill {
+    let tier = level.tier();
+    let size = match part {
+        ArmourParts::Body => 2,
+        ArmourParts::Legs | ArmourParts::Shield => 1,
+        _ => 0
+    };
+    let material = match (part, weight) {
+        (ArmourParts::Shield, _) => tier.wood(),
+        (_, ArmourWeights::Heavy) => tier.ingot(),
+        (_, ArmourWeights::Light) => tier.cloth(),
+        (_, ArmourWeights::Medium) => tier.leather()
+    };
+
+    MaterialBill::new().with(level.part_amount(size), material)
+}
+
+impl MaterialCost for Armour {
+    fn cost(&self) -> MaterialBill {
+        let mut bill = MaterialBill::new();
+        bill.merge(&get_part_mats(&self.kind, &self.weight, &self.level));
+        bill.merge(&self.armour_trait.cost());
+        if let Some(e) = &self.enchantment {
+            bill.merge(&Glyph::new(&Enchantment::Armour(e.clone()), &self.quality, self.level).cost());
+        }
+        if self.kind == ArmourParts::Shield {
+            bill.merge(&get_woodworking_quality_cost(&self.quality));
+        } else {
+            bill.merge(&get_quality_mats(&self.weight, &self.quality));
+        }
+        bill
+    }
+}
\ No newline at end of file
@@ -0,0 +1,240 @@
+use std::fmt::{Display, Formatter};
+use strum_macros::{Display, EnumIter, EnumMessage, EnumProperty, EnumString};
+use strum::EnumProperty as _;
+use crate::entities::alchemy::{self, AlchemyRecipe, Effect};
+use crate::entities::materials::{Ingredients, MaterialBill, Solvents};
+use crate::entities::{split_quantity, GearQuality, MaterialCost};
+use crate::entities::sets::normalize;
+
+#[derive(EnumIter, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, EnumString, Display, EnumProperty, EnumMessage)]
+pub enum ConsumableKind {
+    /// Platos de cocina que aumentan los atributos máximos
+    #[strum(serialize = "Comida")]
+    #[strum(props(Emoji = "🍖"))]
+    Food,
+    /// Bebidas de cocina que aumentan la recuperación
+    #[strum(serialize = "Bebida")]
+    #[strum(props(Emoji = "🍺"))]
+    Drink,
+    /// Pociones de alquimia
+    #[strum(serialize = "Poción")]
+    #[strum(props(Emoji = "🧪"))]
+    Potion,
+    /// Venenos de alquimia para aplicar al arma
+    #[strum(serialize = "Veneno")]
+    #[strum(props(Emoji = "☠️"))]
+    Poison
+}
+
+impl ConsumableKind {
+    /// Units obtained from one craft, counting the crafter has the Chef, Brewer and Chemistry passives maxed
+    pub fn batch(&self) -> i32 {
+        match self {
+            ConsumableKind::Food | ConsumableKind::Drink | ConsumableKind::Potion => 4,
+            ConsumableKind::Poison => 16
+        }
+    }
+
+    /// Solvent for the alchemy kinds, the champion point 150 ones
+    pub fn solvent(&self) -> Option<Solvents> {
+        match self {
+            ConsumableKind::Potion => Some(Solvents::LorkhansTears),
+            ConsumableKind::Poison => Some(Solvents::Alkahest),
+            ConsumableKind::Food | ConsumableKind::Drink => None
+        }
+    }
+}
+
+/// How a consumable is made: provisioning uses one of each ingredient per craft,
+/// while alchemy items are defined by their effects and the reagents worked out from them
+pub enum Recipe {
+    Provisioning { quality: GearQuality, ingredients: &'static [Ingredients] },
+    Alchemy(&'static [Effect])
+}
+
+pub struct Consumable {
+    pub name_es: &'static str,
+    pub name_en: &'static str,
+    pub kind: ConsumableKind,
+    /// Buff of food and drinks, effects of potions and poisons
+    pub effects: &'static str,
+    pub recipe: Recipe
+}
+
+impl Consumable {
+    /// Best reagent mix for the effects of a potion or poison
+    pub fn alchemy(&self) -> Option<AlchemyRecipe> {
+        match (&self.recipe, self.kind.solvent()) {
+            (Recipe::Alchemy(wanted), Some(solvent)) => alchemy::solve(wanted, solvent).into_iter().next(),
+            _ => None
+        }
+    }
+}
+
+impl Display for Consumable {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.name_es, self.name_en)
+    }
+}
+
+// The picker sends positions in this list, so new entries go at the end
+pub const CATALOG: &[Consumable] = &[
+    Consumable { name_es: "Calaveras de azúcar embrujadas", name_en: "Bewitched Sugar Skulls", kind: ConsumableKind::Food,
+        effects: "Salud, magia y aguante máximos, recuperación de salud",
+        recipe: Recipe::Provisioning { quality: GearQuality::Yellow, ingredients: &[Ingredients::Pumpkin, Ingredients::Potato, Ingredients::BervezJuice, Ingredients::FrostMirriam] } },
+    Consumable { name_es: "Filete cítrico mecánico", name_en: "Clockwork Citrus Filet", kind: ConsumableKind::Food,
+        effects: "Salud máxima, recuperación de salud y de magia",
+        recipe: Recipe::Provisioning { quality: GearQuality::Yellow, ingredients: &[Ingredients::WhiteMeat, Ingredients::Lemon, Ingredients::Greens, Ingredients::FrostMirriam] } },
+    Consumable { name_es: "Sopa de pie de lava con arroz salado", name_en: "Lava Foot Soup-and-Saltrice", kind: ConsumableKind::Food,
+        effects: "Aguante máximo y recuperación de aguante",
+        recipe: Recipe::Provisioning { quality: GearQuality::Yellow, ingredients: &[Ingredients::RedMeat, Ingredients::Radish, Ingredients::Carrots, Ingredients::BervezJuice] } },
+    Consumable { name_es: "Cuenco de pescado en salmuera de Artaeum", name_en: "Artaeum Pickled Fish Bowl", kind: ConsumableKind::Food,
+        effects: "Salud y magia máximas, recuperación de magia",
+        recipe: Recipe::Provisioning { quality: GearQuality::Yellow, ingredients: &[Ingredients::Fish, Ingredients::Beets, Ingredients::SmallGame, Ingredients::PerfectRoe] } },
+    Consumable { name_es: "Brebaje potente de la madre bruja", name_en: "Witchmother's Potent Brew", kind: ConsumableKind::Drink,
+        effects: "Salud máxima, recuperación de magia y de aguante",
+        recipe: Recipe::Provisioning { quality: GearQuality::Yellow, ingredients: &[Ingredients::JazbayGrapes, Ingredients::Rye, Ingredients::Jasmine, Ingredients::FrostMirriam] } },
+    Consumable { name_es: "Frothgar rojo de Orzorga", name_en: "Orzorga's Red Frothgar", kind: ConsumableKind::Drink,
+        effects: "Magia máxima y recuperación de magia",
+        recipe: Recipe::Provisioning { quality: GearQuality::Yellow, ingredients: &[Ingredients::Barley, Ingredients::Honey, Ingredients::Mint, Ingredients::BervezJuice] } },
+    Consumable { name_es: "Cuenco de ojos espantosos", name_en: "Ghastly Eye Bowl", kind: ConsumableKind::Drink,
+        effects: "Recuperación de salud, magia y aguante",
+        recipe: Recipe::Provisioning { quality: GearQuality::Yellow, ingredients: &[Ingredients::Rice, Ingredients::Lotus, Ingredients::Ginkgo, Ingredients::FrostMirriam] } },
+    Consumable { name_es: "Infusión de resorte", name_en: "Spring-Loaded Infusion", kind: ConsumableKind::Drink,
+        effects: "Recuperación de magia y de aguante",
+        recipe: Recipe::Provisioning { quality: GearQuality::Yellow, ingredients: &[Ingredients::Millet, Ingredients::Ginseng, Ingredients::Honey, Ingredients::BervezJuice] } },
+    Consumable { name_es: "Poción de poder de hechizo", name_en: "Essence of Spell Power", kind: ConsumableKind::Potion,
+        effects: "Restaura magia, aumenta el daño y el crítico de hechizo",
+        recipe: Recipe::Alchemy(&[Effect::RestoreMagicka, Effect::SpellPower, Effect::SpellCritical]) },
+    Consumable { name_es: "Poción de poder de arma", name_en: "Essence of Weapon Power", kind: ConsumableKind::Potion,
+        effects: "Restaura aguante, aumenta el daño y el crítico de arma",
+        recipe: Recipe::Alchemy(&[Effect::RestoreStamina, Effect::WeaponPower, Effect::WeaponCritical]) },
+    Consumable { name_es: "Poción de restauración triple", name_en: "Tri-Restoration Essence", kind: ConsumableKind::Potion,
+        effects: "Restaura salud, magia y aguante",
+        recipe: Recipe::Alchemy(&[Effect::RestoreHealth, Effect::RestoreMagicka, Effect::RestoreStamina]) },
+    Consumable { name_es: "Veneno de daño de salud", name_en: "Damage Health Poison", kind: ConsumableKind::Poison,
+        effects: "Daña la salud del objetivo con el tiempo",
+        recipe: Recipe::Alchemy(&[Effect::RavageHealth]) },
+    Consumable { name_es: "Sopa de zanahoria", name_en: "Carrot Soup", kind: ConsumableKind::Food,
+        effects: "Aguante máximo",
+        recipe: Recipe::Provisioning { quality: GearQuality::Green, ingredients: &[Ingredients::Carrots, Ingredients::Greens] } },
+    Consumable { name_es: "Pastel de hocico de mamut", name_en: "Mammoth Snout Pie", kind: ConsumableKind::Food,
+        effects: "Salud máxima",
+        recipe: Recipe::Provisioning { quality: GearQuality::Green, ingredients: &[Ingredients::RedMeat, Ingredients::SmallGame] } },
+    Consumable { name_es: "Calabaza asada con uvas", name_en: "Roast Pumpkin with Grapes", kind: ConsumableKind::Food,
+        effects: "Magia máxima",
+        recipe: Recipe::Provisioning { quality: GearQuality::Green, ingredients: &[Ingredients::Pumpkin, Ingredients::JazbayGrapes] } },
+    Consumable { name_es: "Estofado de carne y patatas", name_en: "Meat and Potato Stew", kind: ConsumableKind::Food,
+        effects: "Salud y aguante máximos",
+        recipe: Recipe::Provisioning { quality: GearQuality::Blue, ingredients: &[Ingredients::RedMeat, Ingredients::Potato] } },
+    Consumable { name_es: "Tarta de zanahoria de la posada Jerall View", name_en: "Jerall View Inn Carrot Cake", kind: ConsumableKind::Food,
+        effects: "Salud, magia y aguante máximos",
+        recipe: Recipe::Provisioning { quality: GearQuality::Purple, ingredients: &[Ingredients::WhiteMeat, Ingredients::Pumpkin, Ingredients::Carrots] } },
+    Consumable { name_es: "Cerveza de cebada", name_en: "Barley Beer", kind: ConsumableKind::Drink,
+        effects: "Recuperación de salud",
+        recipe: Recipe::Provisioning { quality: GearQuality::Green, ingredients: &[Ingredients::Barley, Ingredients::Honey] } },
+    Consumable { name_es: "Té de menta", name_en: "Mint Tea", kind: ConsumableKind::Drink,
+        effects: "Recuperación de magia",
+        recipe: Recipe::Provisioning { quality: GearQuality::Green, ingredients: &[Ingredients::Mint, Ingredients::Honey] } },
+    Consumable { name_es: "Infusión de loto y ginseng", name_en: "Lotus and Ginseng Infusion", kind: ConsumableKind::Drink,
+        effects: "Recuperación de magia y de aguante",
+        recipe: Recipe::Provisioning { quality: GearQuality::Blue, ingredients: &[Ingredients::Lotus, Ingredients::Ginseng] } },
+    Consumable { name_es: "Vino de jazbay especiado", name_en: "Spiced Jazbay Wine", kind: ConsumableKind::Drink,
+        effects: "Recuperación de salud, magia y aguante",
+        recipe: Recipe::Provisioning { quality: GearQuality::Purple, ingredients: &[Ingredients::JazbayGrapes, Ingredients::Jasmine, Ingredients::Ginseng] } },
+];
+
+/// Catalog entries of a kind, with their position in the catalog so they can be picked by it
+pub fn consumables_of(kind: ConsumableKind) -> impl Iterator<Item = (usize, &'static Consumable)> {
+    CATALOG.iter().enumerate().filter(move |(_, c)| c.kind == kind)
+}
+
+pub fn find_consumable(name: &str) -> Option<&'static Consumable> {
+    let name = normalize(name);
+    CATALOG.iter()
+        .find(|c| normalize(c.name_es) == name || normalize(c.name_en) == name)
+}
+
+/// Parses a line written as `Nombre (xN)`, with or without a leading dash, e.g.
+/// `- Poción de poder de hechizo (x20)`. Lines without a quantity ask for one unit
+pub fn parse_consumable(line: &str) -> Result<ConsumableOrder, String> {
+    let (name, quantity) = split_quantity(line)?;
+    let item = find_consumable(name).ok_or_else(|| format!("`{}` no está en el catálogo", name))?;
+    Ok(ConsumableOrder { item, quantity })
+}
+
+/// Catalog items found in a free text order, and the lines that could not be read as one
+pub fn parse_consumables(text: &str) -> (Vec<ConsumableOrder>, Vec<String>) {
+    let mut items = vec![];
+    let mut unknown = vec![];
+    for line in text.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        match parse_consumable(line) {
+            Ok(item) => items.push(item),
+            Err(why) => unknown.push(format!("{}: {}", line, why))
+        }
+    }
+    (items, unknown)
+}
+
+/// A catalog item and how many units of it were asked for
+pub struct ConsumableOrder {
+    pub item: &'static Consumable,
+    pub quantity: i32
+}
+
+impl ConsumableOrder {
+    /// Crafts needed to get at least the requested units
+    pub fn crafts(&self) -> i32 {
+        let batch = self.item.kind.batch().unsigned_abs();
+        self.quantity.max(0).unsigned_abs().div_ceil(batch) as i32
+    }
+}
+
+impl Display for ConsumableOrder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {} (x{})", self.item.kind.get_str("Emoji").unwrap_or_default(), self.item.name_es, self.quantity)
+    }
+}
+
+impl MaterialCost for ConsumableOrder {
+    fn cost(&self) -> MaterialBill {
+        let crafts = self.crafts();
+        match self.item.recipe {
+            Recipe::Provisioning { ingredients, .. } => ingredients.iter()
+                .fold(MaterialBill::new(), |bill, ingredient| bill.with(crafts, *ingredient)),
+            Recipe::Alchemy(_) => self.item.alchemy()
+                .map(|recipe| recipe.bill(crafts))
+                .unwrap_or_default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_quantities_and_rounds_crafts_up_to_whole_batches() {
+        let order = parse_consumable("- Poción de poder de hechizo (x20)").unwrap();
+        assert_eq!((order.quantity, order.crafts()), (20, 5));
+        let order = parse_consumable("• Veneno de daño de salud (x17)").unwrap();
+        assert_eq!(order.crafts(), 2);
+        assert_eq!(parse_consumable("Poción de poder de hechizo").unwrap().quantity, 1);
+    }
+
+    #[test]
+    fn refuses_quantities_over_the_limit() {
+        assert!(parse_consumable("Poción de poder de hechizo (x9999)").is_ok());
+        for quantity in ["0", "10000", "2147483647", "99999999999"] {
+            assert!(parse_consumable(&format!("Poción de poder de hechizo (x{})", quantity)).is_err(), "{}", quantity);
+        }
+    }
+
+    #[test]
+    fn flags_lines_not_in_the_catalog() {
+        let (items, unknown) = parse_consumables("Sopa de zanahoria (x4)\nTarta de nada\n\n");
+        assert_eq!(items.len(), 1);
+        assert_eq!(unknown.len(), 1);
+        assert!(unknown[0].starts_with("Tarta de nada: "));
+    }
+}
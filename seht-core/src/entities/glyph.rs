@@ -0,0 +1,337 @@
+use std::fmt::{Display, Formatter};
+use strum::{EnumMessage as _, EnumProperty as _, IntoEnumIterator};
+use strum_macros::{Display, EnumIter, EnumMessage, EnumProperty, EnumString};
+use crate::entities::armour::ArmourEnchantments;
+use crate::entities::jewelry::JewelryEnchantments;
+use crate::entities::level::CraftingLevel;
+use crate::entities::materials::{EssenceRunes, MaterialBill, PotencyRunes, RuneQualityMaterials};
+use crate::entities::weapon::WeaponEnchantments;
+use crate::entities::sets::normalize;
+use crate::entities::{split_quantity, GearQuality, MaterialCost};
+
+#[derive(EnumIter, Clone, Copy, Eq, PartialEq, EnumString, Display, EnumProperty, EnumMessage)]
+pub enum EnchantmentKind {
+    /// Glifos de armadura, aumentan salud, magia o aguante
+    #[strum(serialize = "Armadura")]
+    #[strum(props(Emoji = "🛡️"))]
+    Armour,
+    /// Glifos de arma, se activan al golpear
+    #[strum(serialize = "Arma")]
+    #[strum(props(Emoji = "⚔️"))]
+    Weapon,
+    /// Glifos de joyería
+    #[strum(serialize = "Joyería")]
+    #[strum(props(Emoji = "💍"))]
+    Jewelry
+}
+
+#[derive(Clone, Eq, PartialEq)]
+pub enum Enchantment {
+    Armour(ArmourEnchantments),
+    Weapon(WeaponEnchantments),
+    Jewelry(JewelryEnchantments)
+}
+
+impl Enchantment {
+    /// Every enchantment, armour first, then weapons and jewelry
+    pub fn all() -> Vec<Enchantment> {
+        ArmourEnchantments::iter().map(Enchantment::Armour)
+            .chain(WeaponEnchantments::iter().map(Enchantment::Weapon))
+            .chain(JewelryEnchantments::iter().map(Enchantment::Jewelry))
+            .collect()
+    }
+
+    pub fn kind(&self) -> EnchantmentKind {
+        match self {
+            Enchantment::Armour(_) => EnchantmentKind::Armour,
+            Enchantment::Weapon(_) => EnchantmentKind::Weapon,
+            Enchantment::Jewelry(_) => EnchantmentKind::Jewelry
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Enchantment::Armour(e) => e.get_documentation(),
+            Enchantment::Weapon(e) => e.get_documentation(),
+            Enchantment::Jewelry(e) => e.get_documentation()
+        }.unwrap_or_default()
+    }
+
+    /// Name in the English client, e.g. `Glyph of Health`
+    pub fn name_en(&self) -> &'static str {
+        match self {
+            Enchantment::Armour(e) => e.get_str("English"),
+            Enchantment::Weapon(e) => e.get_str("English"),
+            Enchantment::Jewelry(e) => e.get_str("English")
+        }.unwrap_or_default()
+    }
+
+    pub fn runes(&self) -> (Potency, EssenceRunes) {
+        match self {
+            Enchantment::Armour(e) => e.runes(),
+            Enchantment::Weapon(e) => e.runes(),
+            Enchantment::Jewelry(e) => e.runes()
+        }
+    }
+}
+
+impl Display for Enchantment {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Enchantment::Armour(e) => write!(f, "{}", e),
+            Enchantment::Weapon(e) => write!(f, "{}", e),
+            Enchantment::Jewelry(e) => write!(f, "{}", e)
+        }
+    }
+}
+
+/// Whether a potency rune makes glyphs that add to the wearer or take away from the target
+#[derive(Clone, Copy, Eq, PartialEq)]
+pub enum Potency {
+    Additive,
+    Subtractive
+}
+
+/// Pair of potency runes for a range of levels, and the grade they give to the glyph name
+pub struct PotencyLevel {
+    pub additive: PotencyRunes,
+    pub subtractive: PotencyRunes,
+    pub min: CraftingLevel,
+    pub max: CraftingLevel,
+    pub name_es: &'static str,
+    pub name_en: &'static str
+}
+
+impl PotencyLevel {
+    pub fn rune(&self, potency: Potency) -> PotencyRunes {
+        match potency {
+            Potency::Additive => self.additive,
+            Potency::Subtractive => self.subtractive
+        }
+    }
+}
+
+// Ranges overlap, from lowest to highest so the last one reached is the best for a level
+pub const POTENCY_LEVELS: &[PotencyLevel] = &[
+    PotencyLevel { additive: PotencyRunes::Jora, subtractive: PotencyRunes::Jode, min: CraftingLevel::Level(1), max: CraftingLevel::Level(10), name_es: "Insignificante", name_en: "Trifling" },
+    PotencyLevel { additive: PotencyRunes::Porade, subtractive: PotencyRunes::Notade, min: CraftingLevel::Level(5), max: CraftingLevel::Level(15), name_es: "Inferior", name_en: "Inferior" },
+    PotencyLevel { additive: PotencyRunes::Jera, subtractive: PotencyRunes::Ode, min: CraftingLevel::Level(10), max: CraftingLevel::Level(20), name_es: "Nimio", name_en: "Petty" },
+    PotencyLevel { additive: PotencyRunes::Jejora, subtractive: PotencyRunes::Tade, min: CraftingLevel::Level(15), max: CraftingLevel::Level(25), name_es: "Leve", name_en: "Slight" },
+    PotencyLevel { additive: PotencyRunes::Odra, subtractive: PotencyRunes::Jayde, min: CraftingLevel::Level(20), max: CraftingLevel::Level(30), name_es: "Menor", name_en: "Minor" },
+    PotencyLevel { additive: PotencyRunes::Pojora, subtractive: PotencyRunes::Edode, min: CraftingLevel::Level(25), max: CraftingLevel::Level(35), name_es: "Pequeño", name_en: "Lesser" },
+    PotencyLevel { additive: PotencyRunes::Edora, subtractive: PotencyRunes::Pojode, min: CraftingLevel::Level(30), max: CraftingLevel::Level(40), name_es: "Moderado", name_en: "Moderate" },
+    PotencyLevel { additive: PotencyRunes::Jaera, subtractive: PotencyRunes::Rekude, min: CraftingLevel::Level(35), max: CraftingLevel::Level(45), name_es: "Medio", name_en: "Average" },
+    PotencyLevel { additive: PotencyRunes::Pora, subtractive: PotencyRunes::Hade, min: CraftingLevel::Level(40), max: CraftingLevel::Level(50), name_es: "Fuerte", name_en: "Strong" },
+    PotencyLevel { additive: PotencyRunes::Denara, subtractive: PotencyRunes::Idode, min: CraftingLevel::ChampionPoints(10), max: CraftingLevel::ChampionPoints(30), name_es: "Mayor", name_en: "Major" },
+    PotencyLevel { additive: PotencyRunes::Rera, subtractive: PotencyRunes::Pode, min: CraftingLevel::ChampionPoints(30), max: CraftingLevel::ChampionPoints(50), name_es: "Superior", name_en: "Greater" },
+    PotencyLevel { additive: PotencyRunes::Derado, subtractive: PotencyRunes::Kedeko, min: CraftingLevel::ChampionPoints(50), max: CraftingLevel::ChampionPoints(70), name_es: "Grandioso", name_en: "Grand" },
+    PotencyLevel { additive: PotencyRunes::Rekura, subtractive: PotencyRunes::Rede, min: CraftingLevel::ChampionPoints(70), max: CraftingLevel::ChampionPoints(100), name_es: "Espléndido", name_en: "Splendid" },
+    PotencyLevel { additive: PotencyRunes::Kura, subtractive: PotencyRunes::Kude, min: CraftingLevel::ChampionPoints(100), max: CraftingLevel::ChampionPoints(150), name_es: "Monumental", name_en: "Monumental" },
+    PotencyLevel { additive: PotencyRunes::Rejera, subtractive: PotencyRunes::Jehade, min: CraftingLevel::ChampionPoints(150), max: CraftingLevel::ChampionPoints(150), name_es: "Soberbio", name_en: "Superb" },
+    PotencyLevel { additive: PotencyRunes::Repora, subtractive: PotencyRunes::Itade, min: CraftingLevel::ChampionPoints(160), max: CraftingLevel::ChampionPoints(160), name_es: "Realmente Soberbio", name_en: "Truly Superb" },
+];
+
+/// Highest potency runes usable on an item of the given level
+pub fn potency_level(level: CraftingLevel) -> &'static PotencyLevel {
+    POTENCY_LEVELS.iter()
+        .rev()
+        .find(|p| p.min <= level)
+        .unwrap_or(&POTENCY_LEVELS[0])
+}
+
+impl PotencyRunes {
+    pub fn level(&self) -> &'static PotencyLevel {
+        POTENCY_LEVELS.iter()
+            .find(|p| p.additive == *self || p.subtractive == *self)
+            .expect("every potency rune is in the table")
+    }
+
+    pub fn potency(&self) -> Potency {
+        if self.level().additive == *self { Potency::Additive } else { Potency::Subtractive }
+    }
+}
+
+/// A glyph as the three runes it is made of: the potency rune sets its level and whether it
+/// adds or takes away, the essence rune its effect and the aspect rune its quality
+#[derive(Clone, Copy, Eq, PartialEq)]
+pub struct Glyph {
+    pub potency: PotencyRunes,
+    pub essence: EssenceRunes,
+    pub aspect: RuneQualityMaterials
+}
+
+impl Glyph {
+    pub fn new(enchantment: &Enchantment, quality: &GearQuality, level: CraftingLevel) -> Self {
+        let (potency, essence) = enchantment.runes();
+        Glyph { potency: potency_level(level).rune(potency), essence, aspect: quality.aspect_rune() }
+    }
+
+    /// Enchantment the potency and essence runes make, if any
+    pub fn enchantment(&self) -> Option<Enchantment> {
+        Enchantment::all().into_iter()
+            .find(|e| e.runes() == (self.potency.potency(), self.essence))
+    }
+
+    pub fn quality(&self) -> GearQuality {
+        GearQuality::iter()
+            .find(|q| q.aspect_rune() == self.aspect)
+            .unwrap_or(GearQuality::White)
+    }
+
+    /// Name with the grade of its potency rune, e.g. `Glifo Realmente Soberbio de salud`
+    pub fn name(&self) -> Option<String> {
+        let enchantment = self.enchantment()?.to_string();
+        let effect = enchantment.strip_prefix("Glifo ").unwrap_or(&enchantment);
+        Some(format!("Glifo {} {}", self.potency.level().name_es, effect))
+    }
+}
+
+impl Display for Glyph {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.name() {
+            Some(name) => write!(f, "{} ({})", name, self.quality()),
+            None => write!(f, "{} + {} + {}", self.potency, self.essence, self.aspect)
+        }
+    }
+}
+
+impl MaterialCost for Glyph {
+    fn cost(&self) -> MaterialBill {
+        MaterialBill::new()
+            .with(1, self.potency)
+            .with(1, self.essence)
+            .with(1, self.aspect)
+    }
+}
+
+/// A glyph and how many of them were asked for
+pub struct GlyphOrder {
+    pub glyph: Glyph,
+    pub quantity: i32
+}
+
+impl Display for GlyphOrder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (x{})", self.glyph, self.quantity)
+    }
+}
+
+impl MaterialCost for GlyphOrder {
+    fn cost(&self) -> MaterialBill {
+        self.glyph.cost().scale(self.quantity)
+    }
+}
+
+/// Quality written after a glyph name, in Spanish as the bot shows it or by its English colour
+fn parse_quality(word: &str) -> Option<GearQuality> {
+    let word = normalize(word);
+    GearQuality::iter()
+        .find(|q| normalize(&q.to_string()) == word)
+        .or(match word.as_str() {
+            "white" | "normal" => Some(GearQuality::White),
+            "green" | "fine" => Some(GearQuality::Green),
+            "blue" => Some(GearQuality::Blue),
+            "purple" | "epic" => Some(GearQuality::Purple),
+            "yellow" | "gold" | "legendary" => Some(GearQuality::Yellow),
+            _ => None
+        })
+}
+
+/// Enchantment and potency grade of a glyph name in Spanish, `Glifo [grado] de X`, or English,
+/// `[grade] Glyph of X`. Names without a grade give no potency level
+fn parse_glyph_name(name: &str) -> Option<(Enchantment, Option<&'static PotencyLevel>)> {
+    let name = normalize(name);
+    let name = name.split_whitespace().collect::<Vec<_>>().join(" ");
+    if let Some(rest) = name.strip_prefix("glifo ") {
+        let (level, effect) = POTENCY_LEVELS.iter()
+            .find_map(|p| rest.strip_prefix(&format!("{} ", normalize(p.name_es))).map(|effect| (Some(p), effect)))
+            .unwrap_or((None, rest));
+        let effect = format!("glifo {}", effect);
+        let enchantment = Enchantment::all().into_iter().find(|e| normalize(&e.to_string()) == effect)?;
+        return Some((enchantment, level))
+    }
+
+    let at = name.find("glyph of ")?;
+    let (grade, effect) = (name[..at].trim(), &name[at..]);
+    let level = match grade {
+        "" => None,
+        grade => Some(POTENCY_LEVELS.iter().find(|p| normalize(p.name_en) == grade)?)
+    };
+    let enchantment = Enchantment::all().into_iter().find(|e| normalize(e.name_en()) == effect)?;
+    Some((enchantment, level))
+}
+
+/// Parses a line written as `Nombre [(calidad)] [(xN)]`, with or without a leading dash, e.g.
+/// `- Glifo Realmente Soberbio de magia (Amarilla) (x2)` or `Truly Superb Glyph of Magicka`.
+/// Glyphs without a grade are made for CP160 and those without a quality are yellow, as most
+/// requests are for endgame gear
+pub fn parse_glyph(line: &str) -> Result<GlyphOrder, String> {
+    let (name, quantity) = split_quantity(line)?;
+    let (name, quality) = match name.rsplit_once('(') {
+        Some((rest, quality)) if quality.ends_with(')') => {
+            let quality = quality.trim_end_matches(')');
+            (rest.trim(), parse_quality(quality).ok_or_else(|| format!("`{}` no es una calidad", quality.trim()))?)
+        }
+        _ => (name, GearQuality::Yellow)
+    };
+    let (enchantment, level) = parse_glyph_name(name).ok_or_else(|| format!("`{}` no es un glifo conocido", name))?;
+    let (potency, essence) = enchantment.runes();
+    let level = level.unwrap_or_else(|| potency_level(CraftingLevel::default()));
+    let glyph = Glyph { potency: level.rune(potency), essence, aspect: quality.aspect_rune() };
+    Ok(GlyphOrder { glyph, quantity })
+}
+
+/// Glyphs found in a free text order, one per line, and the lines that could not be read with the reason
+pub fn parse_glyphs(text: &str) -> (Vec<GlyphOrder>, Vec<String>) {
+    let mut glyphs = vec![];
+    let mut unknown = vec![];
+    for line in text.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        match parse_glyph(line) {
+            Ok(glyph) => glyphs.push(glyph),
+            Err(why) => unknown.push(format!("{}: {}", line, why))
+        }
+    }
+    (glyphs, unknown)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scales_the_runes_by_the_quantity_up_to_the_limit() {
+        let order = parse_glyph("Truly Superb Glyph of Magicka (x9999)").unwrap();
+        assert!(order.cost().iter().all(|(_, amount)| *amount == 9999));
+        assert_eq!(order.cost().iter().count(), 3);
+        assert!(parse_glyph("Truly Superb Glyph of Magicka (x2147483647)").is_err());
+    }
+
+    #[test]
+    fn picks_the_best_potency_rune_for_a_level() {
+        assert!(potency_level(CraftingLevel::Level(30)).additive == PotencyRunes::Edora);
+        assert!(potency_level(CraftingLevel::Level(1)).additive == PotencyRunes::Jora);
+        assert!(potency_level(CraftingLevel::ChampionPoints(150)).additive == PotencyRunes::Rejera);
+        assert!(potency_level(CraftingLevel::CP160).additive == PotencyRunes::Repora);
+    }
+
+    #[test]
+    fn reads_glyph_names_in_english_and_spanish() {
+        let magicka = Glyph { potency: PotencyRunes::Repora, essence: EssenceRunes::Makko, aspect: RuneQualityMaterials::Kuta };
+        for line in ["Truly Superb Glyph of Magicka", "- Glifo Realmente Soberbio de magia (Amarilla)", "Glifo de magia"] {
+            let order = parse_glyph(line).unwrap();
+            assert!(order.glyph == magicka, "{}", line);
+            assert_eq!(order.quantity, 1);
+        }
+        let order = parse_glyph("Moderate Glyph of Magicka (green) (x3)").unwrap();
+        assert!(order.glyph.potency == PotencyRunes::Edora);
+        assert!(order.glyph.aspect == RuneQualityMaterials::Jejota);
+        assert_eq!(order.quantity, 3);
+    }
+
+    #[test]
+    fn lists_the_lines_it_cannot_read() {
+        let (glyphs, unknown) = parse_glyphs("Glyph of Nothing\nTruly Superb Glyph of Magicka (rosa)\nGlifo de magia");
+        assert_eq!(glyphs.len(), 1);
+        assert_eq!(unknown, ["Glyph of Nothing: `Glyph of Nothing` no es un glifo conocido", "Truly Superb Glyph of Magicka (rosa): `rosa` no es una calidad"]);
+    }
+}
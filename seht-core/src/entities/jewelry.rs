@@ -0,0 +1,229 @@
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumIter, EnumMessage, EnumProperty, EnumString};
+use crate::entities::{GearQuality, MaterialCost};
+use crate::entities::level::CraftingLevel;
+use crate::entities::glyph::{Enchantment, Glyph, Potency};
+use crate::entities::materials::{EssenceRunes, JewelryQualityMaterials, JewelryTraitMaterials, MaterialBill};
+
+#[derive(Clone, EnumIter, Ord, PartialOrd, Eq, PartialEq, EnumString, Display, EnumMessage)]
+pub enum Jewelries {
+    /// Solo uno
+    #[strum(serialize = "Collar")]
+    Necklace,
+    /// Se asumen dos anillos
+    #[strum(serialize = "Anillo")]
+    Ring
+}
+
+#[derive(Clone, EnumIter, Ord, PartialOrd, Eq, PartialEq, EnumString, Display, EnumMessage)]
+pub enum JewelryTraits {
+    /// Aumenta la magia máxima
+    #[strum(serialize = "Arcanidad")]
+    Arcane,
+    /// Aumenta el daño en enemigos por debajo del 90%
+    #[strum(serialize = "Sed de sangre")]
+    Bloodthirsty,
+    /// Al activar una sinergia, restaura salud, magia y aguante
+    #[strum(serialize = "Armonía")]
+    Harmony,
+    /// Aumenta la salud máxima
+    #[strum(serialize = "Saludable")]
+    Healthy,
+    /// Aumenta la eficacia del encantamiento de la joyería
+    #[strum(serialize = "Imbuido")]
+    Infused,
+    /// Aumenta la resistencia a hechizos y física
+    #[strum(serialize = "Protección")]
+    Protective,
+    /// Aumenta el aguante máximo
+    #[strum(serialize = "Robustez")]
+    Robust,
+    /// Aumenta la velocidad de movimiento
+    #[strum(serialize = "Agilidad")]
+    Swift,
+    /// Aumenta la magia, aguante y salud máximas
+    #[strum(serialize = "Trinidad")]
+    Triune
+}
+
+#[derive(Clone, EnumIter, Ord, PartialOrd, Eq, PartialEq, EnumString, Display, EnumMessage, EnumProperty)]
+pub enum JewelryEnchantments {
+    /// Añade daño de arma y hechizo, y recuperación de aguante
+    #[strum(serialize = "Glifo de aumento de daño físico")]
+    #[strum(props(English = "Glyph of Increase Physical Harm"))]
+    IncreasePhysicalHarm,
+    /// Añade daño de arma y hechizo, y recuperación de magia
+    #[strum(serialize = "Glifo de aumento de daño mágico")]
+    #[strum(props(English = "Glyph of Increase Magical Harm"))]
+    IncreaseMagicalHarm,
+    /// Añade recuperación de salud
+    #[strum(serialize = "Glifo de regeneración de salud")]
+    #[strum(props(English = "Glyph of Health Recovery"))]
+    HealthRecovery,
+    /// Añade recuperación de magia
+    #[strum(serialize = "Glifo de regeneración de magia")]
+    #[strum(props(English = "Glyph of Magicka Recovery"))]
+    MagickaRecovery,
+    /// Añade recuperación de aguante
+    #[strum(serialize = "Glifo de regeneración de aguante")]
+    #[strum(props(English = "Glyph of Stamina Recovery"))]
+    StaminaRecovery,
+    /// Reduce el coste de magia de las habilidades
+    #[strum(serialize = "Glifo de reducción de coste de magia")]
+    #[strum(props(English = "Glyph of Reduce Spell Cost"))]
+    ReduceSpellCost,
+    /// Reduce el coste de aguante de las habilidades
+    #[strum(serialize = "Glifo de reducción de coste de aguante")]
+    #[strum(props(English = "Glyph of Reduce Feat Cost"))]
+    ReduceFeatCost,
+    /// Reduce el coste de bloquear
+    #[strum(serialize = "Glifo de bloqueo")]
+    #[strum(props(English = "Glyph of Shielding"))]
+    Shielding,
+    /// Añade daño a tus ataques de aporreo
+    #[strum(serialize = "Glifo de percusión")]
+    #[strum(props(English = "Glyph of Bashing"))]
+    Bashing,
+    /// Añade resistencia física
+    #[strum(serialize = "Glifo de resistencia al daño físico")]
+    #[strum(props(English = "Glyph of Decrease Physical Harm"))]
+    DecreasePhysicalHarm,
+    /// Añade resistencia a los hechizos
+    #[strum(serialize = "Glifo de resistencia al daño mágico")]
+    #[strum(props(English = "Glyph of Decrease Spell Harm"))]
+    DecreaseSpellHarm,
+    /// Añade resistencia a las llamas
+    #[strum(serialize = "Glifo de resistencia al fuego")]
+    #[strum(props(English = "Glyph of Flame Resist"))]
+    FlameResist,
+    /// Añade resistencia a la escarcha
+    #[strum(serialize = "Glifo de resistencia a la congelación")]
+    #[strum(props(English = "Glyph of Frost Resist"))]
+    FrostResist,
+    /// Añade resistencia a descargas eléctricas
+    #[strum(serialize = "Glifo de resistencia a las descargas")]
+    #[strum(props(English = "Glyph of Shock Resist"))]
+    ShockResist,
+    /// Añade resistencia a venenos
+    #[strum(serialize = "Glifo de resistencia al veneno")]
+    #[strum(props(English = "Glyph of Poison Resist"))]
+    PoisonResist,
+    /// Añade resistencia a enfermedades
+    #[strum(serialize = "Glifo de resistencia a las enfermedades")]
+    #[strum(props(English = "Glyph of Disease Resist"))]
+    DiseaseResist,
+    /// Aumenta la duración de los efectos de las pociones
+    #[strum(serialize = "Glifo de amplificación alquímica")]
+    #[strum(props(English = "Glyph of Potion Speed"))]
+    PotionResist,
+    /// Reduce la reutilización de las pociones
+    #[strum(serialize = "Glifo de aceleración alquímica")]
+    #[strum(props(English = "Glyph of Potion Boost"))]
+    PotionBoost,
+    /// Reduce el coste de salud, magia y aguante de las habilidades
+    #[strum(serialize = "Glifo de reducción de coste de habilidades")]
+    #[strum(props(English = "Glyph of Reduce Skill Cost"))]
+    ReduceSkillCost,
+    /// Añade recuperación de magia, salud y aguante
+    #[strum(serialize = "Glifo de regeneración prismática")]
+    #[strum(props(English = "Glyph of Prismatic Recovery"))]
+    PrismaticRecovery
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Jewelry {
+    #[serde(rename = "pieza")]
+    pub kind: Jewelries,
+    #[serde(rename = "rasgo")]
+    pub jewelry_trait: JewelryTraits,
+    #[serde(rename = "glifo", default, skip_serializing_if = "Option::is_none")]
+    pub enchantment: Option<JewelryEnchantments>,
+    #[serde(rename = "calidad")]
+    pub quality: GearQuality,
+    #[serde(rename = "nivel", default)]
+    pub level: CraftingLevel
+}
+
+impl Jewelries {
+    fn part_cost(&self, level: &CraftingLevel) -> MaterialBill {
+        let amount = match *self {
+            Jewelries::Necklace => level.ring_amount() * 3 / 2,
+            Jewelries::Ring => level.ring_amount()
+        };
+
+        MaterialBill::new().with(amount, level.jewelry_ounces())
+    }
+}
+
+impl MaterialCost for JewelryTraits {
+    fn cost(&self) -> MaterialBill {
+        match *self {
+            JewelryTraits::Arcane => MaterialBill::new().with(1, JewelryTraitMaterials::Cobalt),
+            JewelryTraits::Bloodthirsty => MaterialBill::new().with(1, JewelryTraitMaterials::Slaughterstone),
+            JewelryTraits::Harmony => MaterialBill::new().with(1, JewelryTraitMaterials::Dibellium),
+            JewelryTraits::Healthy => MaterialBill::new().with(1, JewelryTraitMaterials::Antimony),
+            JewelryTraits::Infused => MaterialBill::new().with(1, JewelryTraitMaterials::AurbicAmber),
+            JewelryTraits::Protective => MaterialBill::new().with(1, JewelryTraitMaterials::Titanium),
+            JewelryTraits::Robust => MaterialBill::new().with(1, JewelryTraitMaterials::Zinc),
+            JewelryTraits::Swift => MaterialBill::new().with(1, JewelryTraitMaterials::GildingWax),
+            JewelryTraits::Triune => MaterialBill::new().with(1, JewelryTraitMaterials::DawnPrism),
+        }
+    }
+}
+
+impl JewelryEnchantments {
+    /// Kind of potency rune and essence rune of the glyph, the exact potency rune depending on
+    /// its level and the aspect rune on its quality
+    pub fn runes(&self) -> (Potency, EssenceRunes) {
+        match *self {
+            JewelryEnchantments::IncreasePhysicalHarm => (Potency::Additive, EssenceRunes::Taderi),
+            JewelryEnchantments::IncreaseMagicalHarm => (Potency::Additive, EssenceRunes::Makderi),
+            JewelryEnchantments::HealthRecovery => (Potency::Additive, EssenceRunes::Okoma),
+            JewelryEnchantments::MagickaRecovery => (Potency::Additive, EssenceRunes::Makkoma),
+            JewelryEnchantments::StaminaRecovery => (Potency::Additive, EssenceRunes::Denima),
+            JewelryEnchantments::ReduceSpellCost => (Potency::Subtractive, EssenceRunes::Makkoma),
+            JewelryEnchantments::ReduceFeatCost => (Potency::Subtractive, EssenceRunes::Denima),
+            JewelryEnchantments::Shielding => (Potency::Subtractive, EssenceRunes::Kaderi),
+            JewelryEnchantments::Bashing => (Potency::Additive, EssenceRunes::Kaderi),
+            JewelryEnchantments::DecreasePhysicalHarm => (Potency::Subtractive, EssenceRunes::Taderi),
+            JewelryEnchantments::DecreaseSpellHarm => (Potency::Subtractive, EssenceRunes::Makderi),
+            JewelryEnchantments::FlameResist => (Potency::Subtractive, EssenceRunes::Rakeipa),
+            JewelryEnchantments::FrostResist => (Potency::Subtractive, EssenceRunes::Dekeipa),
+            JewelryEnchantments::ShockResist => (Potency::Subtractive, EssenceRunes::Meip),
+            JewelryEnchantments::PoisonResist => (Potency::Subtractive, EssenceRunes::Kuoko),
+            JewelryEnchantments::DiseaseResist => (Potency::Subtractive, EssenceRunes::Haoko),
+            JewelryEnchantments::PotionResist => (Potency::Subtractive, EssenceRunes::Oru),
+            JewelryEnchantments::PotionBoost => (Potency::Additive, EssenceRunes::Oru),
+            JewelryEnchantments::ReduceSkillCost => (Potency::Subtractive, EssenceRunes::Indeko),
+            JewelryEnchantments::PrismaticRecovery => (Potency::Additive, EssenceRunes::Indeko),
+        }
+    }
+}
+
+impl MaterialCost for Jewelry {
+    fn cost(&self) -> MaterialBill {
+        let mut bill = MaterialBill::new();
+        bill.merge(&self.kind.part_cost(&self.level));
+        bill.merge(&self.jewelry_trait.cost());
+        if let Some(e) = &self.enchantment {
+            bill.merge(&Glyph::new(&Enchantment::Jewelry(e.clone()), &self.quality, self.level).cost());
+        }
+        bill.merge(&match self.quality {
+            GearQuality::White => MaterialBill::new(),
+            GearQuality::Green => MaterialBill::new().with(1, JewelryQualityMaterials::TernePlating),
+            GearQuality::Blue => MaterialBill::new()
+                .with(1, JewelryQualityMaterials::TernePlating)
+                .with(2, JewelryQualityMaterials::IridiumPlating),
+            GearQuality::Purple => MaterialBill::new()
+                .with(1, JewelryQualityMaterials::TernePlating)
+                .with(2, JewelryQualityMaterials::IridiumPlating)
+                .with(3, JewelryQualityMaterials::ZirconPlating),
+            GearQuality::Yellow => MaterialBill::new()
+                .with(1, JewelryQualityMaterials::TernePlating)
+                .with(2, JewelryQualityMaterials::IridiumPlating)
+                .with(3, JewelryQualityMaterials::ZirconPlating)
+                .with(4, JewelryQualityMaterials::ChromiumPlating),
+        });
+        bill
+    }
+}
\ No newline at end of file
@@ -0,0 +1,227 @@
+use std::fmt::{Display, Formatter};
+use strum_macros::{Display, EnumIter, EnumMessage, EnumString};
+use crate::entities::materials::PartMaterials;
+
+#[derive(Clone, Copy, EnumIter, Ord, PartialOrd, Eq, PartialEq, Display, EnumString, EnumMessage)]
+pub enum MaterialTier {
+    /// Hierro, yute, cuero sin curtir y arce
+    #[strum(serialize = "Nivel 1-14")]
+    Iron,
+    /// Acero, lino, piel y roble
+    #[strum(serialize = "Nivel 16-24")]
+    Steel,
+    /// Oricalco, algodón, cuero y haya
+    #[strum(serialize = "Nivel 26-34")]
+    Orichalcum,
+    /// Enano, seda de araña, cuero grueso y nogal
+    #[strum(serialize = "Nivel 36-44")]
+    Dwarven,
+    /// Ébano, hilo de ébano, piel de bestia y tejo
+    #[strum(serialize = "Nivel 46-50")]
+    Ebony,
+    /// Calcinio, fibra de kresh, piel curtida y abedul
+    #[strum(serialize = "CP10-CP30")]
+    Calcinium,
+    /// Galatita, hilo de hierro, piel de hierro y fresno
+    #[strum(serialize = "CP40-CP60")]
+    Galatite,
+    /// Mercurio, tejido plateado, piel soberbia y caoba
+    #[strum(serialize = "CP70-CP80")]
+    Quicksilver,
+    /// Piedra del vacío, tela del vacío, piel de sombra y madera nocturna
+    #[strum(serialize = "CP90-CP140")]
+    Voidstone,
+    /// Rubedita, seda ancestral, cuero rubedo y fresno rubí
+    #[strum(serialize = "CP150-CP160")]
+    Rubedite
+}
+
+#[derive(Clone, Copy, Ord, PartialOrd, Eq, PartialEq)]
+pub enum CraftingLevel {
+    Level(u8),
+    ChampionPoints(u16)
+}
+
+impl MaterialTier {
+    pub fn levels(&self) -> Vec<CraftingLevel> {
+        match self {
+            MaterialTier::Iron => [1, 4, 6, 8, 10, 12, 14].into_iter().map(CraftingLevel::Level).collect(),
+            MaterialTier::Steel => (16..=24).step_by(2).map(CraftingLevel::Level).collect(),
+            MaterialTier::Orichalcum => (26..=34).step_by(2).map(CraftingLevel::Level).collect(),
+            MaterialTier::Dwarven => (36..=44).step_by(2).map(CraftingLevel::Level).collect(),
+            MaterialTier::Ebony => (46..=50).step_by(2).map(CraftingLevel::Level).collect(),
+            MaterialTier::Calcinium => (10..=30).step_by(10).map(CraftingLevel::ChampionPoints).collect(),
+            MaterialTier::Galatite => (40..=60).step_by(10).map(CraftingLevel::ChampionPoints).collect(),
+            MaterialTier::Quicksilver => (70..=80).step_by(10).map(CraftingLevel::ChampionPoints).collect(),
+            MaterialTier::Voidstone => (90..=140).step_by(10).map(CraftingLevel::ChampionPoints).collect(),
+            MaterialTier::Rubedite => (150..=160).step_by(10).map(CraftingLevel::ChampionPoints).collect()
+        }
+    }
+
+    // Amount needed for a small armour piece at the first level of the tier
+    fn base_amount(&self) -> i32 {
+        match self {
+            MaterialTier::Iron => 5,
+            MaterialTier::Steel => 6,
+            MaterialTier::Orichalcum => 7,
+            MaterialTier::Dwarven => 8,
+            MaterialTier::Ebony => 9,
+            MaterialTier::Calcinium => 10,
+            MaterialTier::Galatite => 11,
+            MaterialTier::Quicksilver => 12,
+            MaterialTier::Voidstone => 13,
+            MaterialTier::Rubedite => 13
+        }
+    }
+
+    pub fn ingot(&self) -> PartMaterials {
+        match self {
+            MaterialTier::Iron => PartMaterials::IronIngots,
+            MaterialTier::Steel => PartMaterials::SteelIngots,
+            MaterialTier::Orichalcum => PartMaterials::OrichalcumIngots,
+            MaterialTier::Dwarven => PartMaterials::DwarvenIngots,
+            MaterialTier::Ebony => PartMaterials::EbonyIngots,
+            MaterialTier::Calcinium => PartMaterials::CalciniumIngots,
+            MaterialTier::Galatite => PartMaterials::GalatiteIngots,
+            MaterialTier::Quicksilver => PartMaterials::QuicksilverIngots,
+            MaterialTier::Voidstone => PartMaterials::VoidstoneIngots,
+            MaterialTier::Rubedite => PartMaterials::RubediteIngots
+        }
+    }
+
+    pub fn cloth(&self) -> PartMaterials {
+        match self {
+            MaterialTier::Iron => PartMaterials::Jute,
+            MaterialTier::Steel => PartMaterials::Flax,
+            MaterialTier::Orichalcum => PartMaterials::Cotton,
+            MaterialTier::Dwarven => PartMaterials::Spidersilk,
+            MaterialTier::Ebony => PartMaterials::Ebonthread,
+            MaterialTier::Calcinium => PartMaterials::KreshFiber,
+            MaterialTier::Galatite => PartMaterials::Ironthread,
+            MaterialTier::Quicksilver => PartMaterials::Silverweave,
+            MaterialTier::Voidstone => PartMaterials::VoidCloth,
+            MaterialTier::Rubedite => PartMaterials::AncestorSilk
+        }
+    }
+
+    pub fn leather(&self) -> PartMaterials {
+        match self {
+            MaterialTier::Iron => PartMaterials::Rawhide,
+            MaterialTier::Steel => PartMaterials::Hide,
+            MaterialTier::Orichalcum => PartMaterials::Leather,
+            MaterialTier::Dwarven => PartMaterials::ThickLeather,
+            MaterialTier::Ebony => PartMaterials::FellHide,
+            MaterialTier::Calcinium => PartMaterials::TopgrainHide,
+            MaterialTier::Galatite => PartMaterials::IronHide,
+            MaterialTier::Quicksilver => PartMaterials::SuperbHide,
+            MaterialTier::Voidstone => PartMaterials::Shadowhide,
+            MaterialTier::Rubedite => PartMaterials::RubedoLeather
+        }
+    }
+
+    pub fn wood(&self) -> PartMaterials {
+        match self {
+            MaterialTier::Iron => PartMaterials::SandedMaple,
+            MaterialTier::Steel => PartMaterials::SandedOak,
+            MaterialTier::Orichalcum => PartMaterials::SandedBeech,
+            MaterialTier::Dwarven => PartMaterials::SandedHickory,
+            MaterialTier::Ebony => PartMaterials::SandedYew,
+            MaterialTier::Calcinium => PartMaterials::SandedBirch,
+            MaterialTier::Galatite => PartMaterials::SandedAsh,
+            MaterialTier::Quicksilver => PartMaterials::SandedMahogany,
+            MaterialTier::Voidstone => PartMaterials::SandedNightwood,
+            MaterialTier::Rubedite => PartMaterials::SandedRubyAsh
+        }
+    }
+}
+
+impl CraftingLevel {
+    pub const CP160: CraftingLevel = CraftingLevel::ChampionPoints(160);
+
+    pub fn tier(&self) -> MaterialTier {
+        match *self {
+            CraftingLevel::Level(l) if l < 16 => MaterialTier::Iron,
+            CraftingLevel::Level(l) if l < 26 => MaterialTier::Steel,
+            CraftingLevel::Level(l) if l < 36 => MaterialTier::Orichalcum,
+            CraftingLevel::Level(l) if l < 46 => MaterialTier::Dwarven,
+            CraftingLevel::Level(_) => MaterialTier::Ebony,
+            CraftingLevel::ChampionPoints(cp) if cp < 40 => MaterialTier::Calcinium,
+            CraftingLevel::ChampionPoints(cp) if cp < 70 => MaterialTier::Galatite,
+            CraftingLevel::ChampionPoints(cp) if cp < 90 => MaterialTier::Quicksilver,
+            CraftingLevel::ChampionPoints(cp) if cp < 150 => MaterialTier::Voidstone,
+            CraftingLevel::ChampionPoints(_) => MaterialTier::Rubedite
+        }
+    }
+
+    /// Base material needed for a piece, `size` being how many more units it takes than a small
+    /// armour piece (body +2, legs +1, dagger -3...)
+    pub fn part_amount(&self, size: i32) -> i32 {
+        match self {
+            // CP160 gear costs ten times the CP150 amount
+            CraftingLevel::ChampionPoints(160) => CraftingLevel::ChampionPoints(150).part_amount(size) * 10,
+            _ => {
+                let step = self.tier().levels().iter()
+                    .position(|l| l == self)
+                    .unwrap_or_default() as i32;
+                self.tier().base_amount() + step + size
+            }
+        }
+    }
+
+    pub fn jewelry_ounces(&self) -> PartMaterials {
+        match *self {
+            CraftingLevel::Level(l) if l < 26 => PartMaterials::PewterOunces,
+            CraftingLevel::Level(_) => PartMaterials::CopperOunces,
+            CraftingLevel::ChampionPoints(cp) if cp < 80 => PartMaterials::SilverOunces,
+            CraftingLevel::ChampionPoints(cp) if cp < 150 => PartMaterials::ElectrumOunces,
+            CraftingLevel::ChampionPoints(_) => PartMaterials::PlatinumOunces
+        }
+    }
+
+    /// Ounces needed for a ring, necklaces take half as much again
+    pub fn ring_amount(&self) -> i32 {
+        match self.jewelry_ounces() {
+            PartMaterials::PewterOunces => 2,
+            PartMaterials::CopperOunces => 4,
+            PartMaterials::SilverOunces => 6,
+            PartMaterials::ElectrumOunces => 8,
+            _ if *self == CraftingLevel::CP160 => 100,
+            _ => 10
+        }
+    }
+}
+
+/// Level assumed when a build does not say otherwise
+impl Default for CraftingLevel {
+    fn default() -> Self {
+        CraftingLevel::CP160
+    }
+}
+
+impl Display for CraftingLevel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CraftingLevel::Level(l) => write!(f, "Nivel {}", l),
+            CraftingLevel::ChampionPoints(cp) => write!(f, "CP{}", cp),
+        }
+    }
+}
+
+impl std::str::FromStr for CraftingLevel {
+    type Err = strum::ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let level = if let Some(cp) = s.strip_prefix("CP").or_else(|| s.strip_prefix("cp")) {
+            cp.trim().parse().map(CraftingLevel::ChampionPoints)
+        } else {
+            s.trim_start_matches("Nivel").trim().parse().map(CraftingLevel::Level)
+        }.map_err(|_| strum::ParseError::VariantNotFound)?;
+
+        if level.tier().levels().contains(&level) {
+            Ok(level)
+        } else {
+            Err(strum::ParseError::VariantNotFound)
+        }
+    }
+}
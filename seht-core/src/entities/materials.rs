@@ -0,0 +1,388 @@
+use std::collections::BTreeMap;
+use std::fmt::Formatter;
+use strum_macros::{Display, EnumIter, EnumString};
+
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, EnumString, Display)]
+pub enum PartMaterials {
+    #[strum(serialize = "Lingote de hierro (Iron Ingot)")] IronIngots,
+    #[strum(serialize = "Lingote de acero (Steel Ingot)")] SteelIngots,
+    #[strum(serialize = "Lingote de oricalco (Orichalcum Ingot)")] OrichalcumIngots,
+    #[strum(serialize = "Lingote enano (Dwarven Ingot)")] DwarvenIngots,
+    #[strum(serialize = "Lingote de ébano (Ebony Ingot)")] EbonyIngots,
+    #[strum(serialize = "Lingote de calcinio (Calcinium Ingot)")] CalciniumIngots,
+    #[strum(serialize = "Lingote de galatita (Galatite Ingot)")] GalatiteIngots,
+    #[strum(serialize = "Lingote de mercurio (Quicksilver Ingot)")] QuicksilverIngots,
+    #[strum(serialize = "Lingote de piedra del vacío (Voidstone Ingot)")] VoidstoneIngots,
+    #[strum(serialize = "Lingote de rubedita (Rubedite Ingots)")] RubediteIngots,
+    #[strum(serialize = "Yute (Jute)")] Jute,
+    #[strum(serialize = "Lino (Flax)")] Flax,
+    #[strum(serialize = "Algodón (Cotton)")] Cotton,
+    #[strum(serialize = "Seda de araña (Spidersilk)")] Spidersilk,
+    #[strum(serialize = "Hilo de ébano (Ebonthread)")] Ebonthread,
+    #[strum(serialize = "Fibra de kresh (Kresh Fiber)")] KreshFiber,
+    #[strum(serialize = "Hilo de hierro (Ironthread)")] Ironthread,
+    #[strum(serialize = "Tejido plateado (Silverweave)")] Silverweave,
+    #[strum(serialize = "Tela del vacío (Void Cloth)")] VoidCloth,
+    #[strum(serialize = "Seda ancestral (Ancestor Silk)")] AncestorSilk,
+    #[strum(serialize = "Cuero sin curtir (Rawhide)")] Rawhide,
+    #[strum(serialize = "Piel (Hide)")] Hide,
+    #[strum(serialize = "Cuero (Leather)")] Leather,
+    #[strum(serialize = "Cuero grueso (Thick Leather)")] ThickLeather,
+    #[strum(serialize = "Piel de bestia (Fell Hide)")] FellHide,
+    #[strum(serialize = "Piel curtida (Topgrain Hide)")] TopgrainHide,
+    #[strum(serialize = "Piel de hierro (Iron Hide)")] IronHide,
+    #[strum(serialize = "Piel soberbia (Superb Hide)")] SuperbHide,
+    #[strum(serialize = "Piel de sombra (Shadowhide)")] Shadowhide,
+    #[strum(serialize = "Cuero rubedo (Rubedo Leather)")] RubedoLeather,
+    #[strum(serialize = "Madera de arce lijado (Sanded Maple)")] SandedMaple,
+    #[strum(serialize = "Madera de roble lijado (Sanded Oak)")] SandedOak,
+    #[strum(serialize = "Madera de haya lijada (Sanded Beech)")] SandedBeech,
+    #[strum(serialize = "Madera de nogal lijado (Sanded Hickory)")] SandedHickory,
+    #[strum(serialize = "Madera de tejo lijado (Sanded Yew)")] SandedYew,
+    #[strum(serialize = "Madera de abedul lijado (Sanded Birch)")] SandedBirch,
+    #[strum(serialize = "Madera de fresno lijado (Sanded Ash)")] SandedAsh,
+    #[strum(serialize = "Madera de caoba lijada (Sanded Mahogany)")] SandedMahogany,
+    #[strum(serialize = "Madera nocturna lijada (Sanded Nightwood)")] SandedNightwood,
+    #[strum(serialize = "Madera de fresno rubí lijado (Sanded Ruby Ash)")] SandedRubyAsh,
+    #[strum(serialize = "Onza de peltre (Pewter Ounces)")] PewterOunces,
+    #[strum(serialize = "Onza de cobre (Copper Ounces)")] CopperOunces,
+    #[strum(serialize = "Onza de plata (Silver Ounces)")] SilverOunces,
+    #[strum(serialize = "Onza de electro (Electrum Ounces)")] ElectrumOunces,
+    #[strum(serialize = "Onza de platino (Platinum Ounces)")] PlatinumOunces
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, EnumString, Display)]
+pub enum RuneQualityMaterials {
+    Ta, Jejota, Denata, Rekuta, Kuta
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, EnumString, Display)]
+pub enum TailoringQualityMaterials {
+    #[strum(serialize = "Hilo de coser (Hemming)")] Hemming,
+    #[strum(serialize = "Bordado (Embroidery)")] Embroidery,
+    #[strum(serialize = "Revestimiento elegante (Elegant Lining)")] ElegantLining,
+    #[strum(serialize = "Cera de dreugh (Dreugh Wax)")] DreughWax
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, EnumString, Display)]
+pub enum BlacksmithQualityMaterials {
+    #[strum(serialize = "Piedra de esmeril (Honing Stone)")] HoningStone,
+    #[strum(serialize = "Aceite enano (Dwarven Oil)")] DwarvenOil,
+    #[strum(serialize = "Disolvente granulado (Grain Solvent)")] GrainSolvent,
+    #[strum(serialize = "Aleación de temple (Tempering Alloy)")] TemperingAlloy
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, EnumString, Display)]
+pub enum WoodworkingQualityMaterials {
+    #[strum(serialize = "Brea (Pitch)")] Pitch,
+    #[strum(serialize = "Turpen")] Turpen,
+    #[strum(serialize = "Masilla (Mastic)")] Mastic,
+    #[strum(serialize = "Colofonia (Rosin)")] Rosin
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, EnumString, Display)]
+#[allow(clippy::enum_variant_names)]
+pub enum JewelryQualityMaterials {
+    #[strum(serialize = "Chapado de terne (Terne Plating)")] TernePlating,
+    #[strum(serialize = "Chapado de iridio (Iridium Plating)")] IridiumPlating,
+    #[strum(serialize = "Chapado de circón (Zircon Plating)")] ZirconPlating,
+    #[strum(serialize = "Chapado de cromo (Chromium Plating)")] ChromiumPlating
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, EnumString, Display)]
+pub enum ArmourTraitMaterials {
+    #[strum(serialize = "Zafiro (Sapphire)")] Sapphire,
+    #[strum(serialize = "Diamante (Diamond)")] Diamond,
+    #[strum(serialize = "Piedra de sangre (Bloodstone)")] Bloodstone,
+    #[strum(serialize = "Granate (Garnet)")] Garnet,
+    #[strum(serialize = "Nirncrux fortificado (Fortified Nirncrux)")] FortifiedNirncrux,
+    #[strum(serialize = "Sardónice (Sardonyx)")] Sardonyx,
+    #[strum(serialize = "Cuarzo (Quartz)")] Quartz,
+    #[strum(serialize = "Esmeralda (Emerald)")] Emerald,
+    #[strum(serialize = "Almandino (Almandine)")] Almandine
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, EnumString, Display)]
+pub enum WeaponTraitMaterials {
+    #[strum(serialize = "Amatista (Amethyst)")] Amethyst,
+    #[strum(serialize = "Citrina (Citrine)")] Citrine,
+    #[strum(serialize = "Turquesa (Turquoise)")] Turquoise,
+    Jade,
+    #[strum(serialize = "Temple de Nirn (Potent Nirncrux)")] PotentNirncrux,
+    #[strum(serialize = "Crisolita (Chysolite)")] Chysolite,
+    #[strum(serialize = "Rubí (Ruby)")] Ruby,
+    #[strum(serialize = "Ópalo de fuego (Fire Opal)")] FireOpal,
+    #[strum(serialize = "Cornalina (Carnelian)")] Carnelian
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, EnumString, Display)]
+pub enum JewelryTraitMaterials {
+    #[strum(serialize = "Cobalto (Cobalt)")] Cobalt,
+    #[strum(serialize = "Piedra masacre (Slaughterstone)")] Slaughterstone,
+    #[strum(serialize = "Dibelio (Dibellium)")] Dibellium,
+    #[strum(serialize = "Antimonio (Antimony)")] Antimony,
+    #[strum(serialize = "Ámbar aúrbico (AurbicAmber)")] AurbicAmber,
+    #[strum(serialize = "Titanio (Titanium)")] Titanium,
+    #[strum(serialize = "Cinc (Zinc)")] Zinc,
+    #[strum(serialize = "Cera dorada (Gilding Wax)")] GildingWax,
+    #[strum(serialize = "Prisma del alba (Dawn Prism)")] DawnPrism
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, EnumString, Display)]
+pub enum PotencyRunes {
+    Jora, Porade, Jera, Jejora, Odra, Pojora, Edora, Jaera, Pora, Denara, Rera, Derado, Rekura, Kura, Rejera, Repora,
+    Jode, Notade, Ode, Tade, Jayde, Edode, Pojode, Rekude, Hade, Idode, Pode, Kedeko, Rede, Kude, Jehade, Itade
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, EnumString, Display)]
+pub enum EssenceRunes {
+    Dekeipa, Deni, Denima, Deteri, Hakeijo, Haoko, Indeko, Kaderi, Kuoko,
+    Makderi, Makko, Makkoma, Meip, Oko, Okoma, Okori, Oru, Rakeipa, Taderi
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, EnumString, Display)]
+pub enum Ingredients {
+    #[strum(serialize = "Carne roja (Red Meat)")] RedMeat,
+    #[strum(serialize = "Carne blanca (White Meat)")] WhiteMeat,
+    #[strum(serialize = "Caza menor (Small Game)")] SmallGame,
+    #[strum(serialize = "Pescado (Fish)")] Fish,
+    #[strum(serialize = "Limón (Lemon)")] Lemon,
+    #[strum(serialize = "Calabaza (Pumpkin)")] Pumpkin,
+    #[strum(serialize = "Uvas jazbay (Jazbay Grapes)")] JazbayGrapes,
+    #[strum(serialize = "Remolacha (Beets)")] Beets,
+    #[strum(serialize = "Zanahorias (Carrots)")] Carrots,
+    #[strum(serialize = "Verduras (Greens)")] Greens,
+    #[strum(serialize = "Patata (Potato)")] Potato,
+    #[strum(serialize = "Rábano (Radish)")] Radish,
+    #[strum(serialize = "Cebada (Barley)")] Barley,
+    #[strum(serialize = "Mijo (Millet)")] Millet,
+    #[strum(serialize = "Arroz (Rice)")] Rice,
+    #[strum(serialize = "Centeno (Rye)")] Rye,
+    #[strum(serialize = "Miel (Honey)")] Honey,
+    #[strum(serialize = "Ginkgo")] Ginkgo,
+    #[strum(serialize = "Ginseng")] Ginseng,
+    #[strum(serialize = "Jazmín (Jasmine)")] Jasmine,
+    #[strum(serialize = "Loto (Lotus)")] Lotus,
+    #[strum(serialize = "Menta (Mint)")] Mint,
+    #[strum(serialize = "Zumo de bervez (Bervez Juice)")] BervezJuice,
+    #[strum(serialize = "Mirriam escarchado (Frost Mirriam)")] FrostMirriam,
+    #[strum(serialize = "Hueva perfecta (Perfect Roe)")] PerfectRoe
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, EnumIter, EnumString, Display)]
+pub enum Reagents {
+    #[strum(serialize = "Aciano (Cornflower)")] Cornflower,
+    #[strum(serialize = "Cardamina (Lady's Smock)")] LadysSmock,
+    #[strum(serialize = "Jacinto de agua (Water Hyacinth)")] WaterHyacinth,
+    #[strum(serialize = "Buglosa (Bugloss)")] Bugloss,
+    #[strum(serialize = "Aguileña (Columbine)")] Columbine,
+    #[strum(serialize = "Flor de montaña (Mountain Flower)")] MountainFlower,
+    #[strum(serialize = "Cardo bendito (Blessed Thistle)")] BlessedThistle,
+    #[strum(serialize = "Espina de dragón (Dragonthorn)")] Dragonthorn,
+    #[strum(serialize = "Ajenjo (Wormwood)")] Wormwood,
+    #[strum(serialize = "Belladona (Nightshade)")] Nightshade,
+    #[strum(serialize = "Raíz de Nirn (Nirnroot)")] Nirnroot,
+    #[strum(serialize = "Podredumbre de Namira (Namira's Rot)")] NamirasRot,
+    #[strum(serialize = "Entoloma azul (Blue Entoloma)")] BlueEntoloma,
+    #[strum(serialize = "Rúsula emética (Emetic Russula)")] EmeticRussula,
+    #[strum(serialize = "Falo hediondo (Stinkhorn)")] Stinkhorn,
+    #[strum(serialize = "Sombrero blanco (White Cap)")] WhiteCap,
+    #[strum(serialize = "Seta de diablillo (Imp Stool)")] ImpStool
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, EnumString, Display)]
+pub enum Solvents {
+    #[strum(serialize = "Lágrimas de Lorkhan (Lorkhan's Tears)")] LorkhansTears,
+    #[strum(serialize = "Alcahesto (Alkahest)")] Alkahest
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Material {
+    Part(PartMaterials),
+    RuneQuality(RuneQualityMaterials),
+    TailoringQuality(TailoringQualityMaterials),
+    BlacksmithQuality(BlacksmithQualityMaterials),
+    WoodworkingQuality(WoodworkingQualityMaterials),
+    JewelryQuality(JewelryQualityMaterials),
+    ArmourTrait(ArmourTraitMaterials),
+    WeaponTrait(WeaponTraitMaterials),
+    JewelryTrait(JewelryTraitMaterials),
+    PotencyRune(PotencyRunes),
+    EssenceRune(EssenceRunes),
+    Ingredient(Ingredients),
+    Reagent(Reagents),
+    Solvent(Solvents)
+}
+
+impl std::fmt::Display for Material {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Material::Part(m) => write!(f, "{}", m),
+            Material::RuneQuality(m) => write!(f, "{}", m),
+            Material::TailoringQuality(m) => write!(f, "{}", m),
+            Material::BlacksmithQuality(m) => write!(f, "{}", m),
+            Material::WoodworkingQuality(m) => write!(f, "{}", m),
+            Material::JewelryQuality(m) => write!(f, "{}", m),
+            Material::ArmourTrait(m) => write!(f, "{}", m),
+            Material::WeaponTrait(m) => write!(f, "{}", m),
+            Material::JewelryTrait(m) => write!(f, "{}", m),
+            Material::PotencyRune(m) => write!(f, "{}", m),
+            Material::EssenceRune(m) => write!(f, "{}", m),
+            Material::Ingredient(m) => write!(f, "{}", m),
+            Material::Reagent(m) => write!(f, "{}", m),
+            Material::Solvent(m) => write!(f, "{}", m),
+        }
+    }
+}
+
+impl From<PartMaterials> for Material {
+    fn from(material: PartMaterials) -> Self {
+        Material::Part(material)
+    }
+}
+
+impl From<RuneQualityMaterials> for Material {
+    fn from(material: RuneQualityMaterials) -> Self {
+        Material::RuneQuality(material)
+    }
+}
+
+impl From<TailoringQualityMaterials> for Material {
+    fn from(material: TailoringQualityMaterials) -> Self {
+        Material::TailoringQuality(material)
+    }
+}
+
+impl From<BlacksmithQualityMaterials> for Material {
+    fn from(material: BlacksmithQualityMaterials) -> Self {
+        Material::BlacksmithQuality(material)
+    }
+}
+
+impl From<WoodworkingQualityMaterials> for Material {
+    fn from(material: WoodworkingQualityMaterials) -> Self {
+        Material::WoodworkingQuality(material)
+    }
+}
+
+impl From<JewelryQualityMaterials> for Material {
+    fn from(material: JewelryQualityMaterials) -> Self {
+        Material::JewelryQuality(material)
+    }
+}
+
+impl From<ArmourTraitMaterials> for Material {
+    fn from(material: ArmourTraitMaterials) -> Self {
+        Material::ArmourTrait(material)
+    }
+}
+
+impl From<WeaponTraitMaterials> for Material {
+    fn from(material: WeaponTraitMaterials) -> Self {
+        Material::WeaponTrait(material)
+    }
+}
+
+impl From<JewelryTraitMaterials> for Material {
+    fn from(material: JewelryTraitMaterials) -> Self {
+        Material::JewelryTrait(material)
+    }
+}
+
+impl From<PotencyRunes> for Material {
+    fn from(material: PotencyRunes) -> Self {
+        Material::PotencyRune(material)
+    }
+}
+
+impl From<EssenceRunes> for Material {
+    fn from(material: EssenceRunes) -> Self {
+        Material::EssenceRune(material)
+    }
+}
+
+impl From<Ingredients> for Material {
+    fn from(material: Ingredients) -> Self {
+        Material::Ingredient(material)
+    }
+}
+
+impl From<Reagents> for Material {
+    fn from(material: Reagents) -> Self {
+        Material::Reagent(material)
+    }
+}
+
+impl From<Solvents> for Material {
+    fn from(material: Solvents) -> Self {
+        Material::Solvent(material)
+    }
+}
+
+/// Amount of each material needed for a craft, keyed by the material itself so
+/// aggregation never depends on how it is displayed
+#[derive(Clone, Default, Eq, PartialEq)]
+pub struct MaterialBill {
+    materials: BTreeMap<Material, i32>
+}
+
+impl MaterialBill {
+    pub fn new() -> Self {
+        MaterialBill::default()
+    }
+
+    pub fn with<M: Into<Material>>(mut self, amount: i32, material: M) -> Self {
+        self.add(amount, material);
+        self
+    }
+
+    pub fn add<M: Into<Material>>(&mut self, amount: i32, material: M) {
+        let total = self.materials.entry(material.into()).or_insert(0);
+        *total = total.saturating_add(amount);
+    }
+
+    pub fn merge(&mut self, other: &MaterialBill) {
+        for (material, amount) in &other.materials {
+            self.add(*amount, *material);
+        }
+    }
+
+    /// Removes the materials in `other`, dropping any that are no longer needed
+    pub fn subtract(&mut self, other: &MaterialBill) {
+        for (material, amount) in &other.materials {
+            if let Some(current) = self.materials.get_mut(material) {
+                *current -= amount;
+                if *current <= 0 {
+                    self.materials.remove(material);
+                }
+            }
+        }
+    }
+
+    pub fn scale(&self, factor: i32) -> MaterialBill {
+        MaterialBill {
+            materials: self.materials.iter()
+                .map(|(material, amount)| (*material, amount.saturating_mul(factor)))
+                .filter(|(_, amount)| *amount > 0)
+                .collect()
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.materials.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&Material, &i32)> {
+        self.materials.iter()
+    }
+}
+
+impl FromIterator<MaterialBill> for MaterialBill {
+    fn from_iter<T: IntoIterator<Item = MaterialBill>>(iter: T) -> Self {
+        let mut bill = MaterialBill::new();
+        for other in iter {
+            bill.merge(&other);
+        }
+        bill
+    }
+}
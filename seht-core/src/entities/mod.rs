@@ -0,0 +1,210 @@
+use std::fmt::{Display, Formatter};
+use strum::IntoEnumIterator;
+use strum_macros::{Display, EnumIter, EnumProperty, EnumString};
+use crate::entities::armour::{ArmourEnchantments, ArmourParts, ArmourTraits, ArmourWeights};
+use crate::entities::jewelry::{Jewelries, JewelryEnchantments, JewelryTraits};
+use crate::entities::level::{CraftingLevel, MaterialTier};
+use crate::entities::materials::{BlacksmithQualityMaterials, MaterialBill, RuneQualityMaterials, TailoringQualityMaterials, WoodworkingQualityMaterials};
+use crate::entities::weapon::{OneHandedWeapons, TwoHandedWeapons, WeaponEnchantments, WeaponKind, WeaponTraits};
+
+pub mod alchemy;
+pub mod armour;
+pub mod consumables;
+pub mod glyph;
+pub mod weapon;
+pub mod jewelry;
+pub mod level;
+pub mod materials;
+pub mod research;
+pub mod sets;
+
+#[derive(Clone, Ord, PartialOrd, Eq, PartialEq)]
+pub enum Gear {
+    Weapon(WeaponKind),
+    Armour(ArmourParts),
+    Jewelry(Jewelries)
+}
+
+#[derive(EnumIter, Clone, Ord, PartialOrd, Eq, PartialEq, EnumString, Display, EnumProperty)]
+pub enum GearQuality {
+    #[strum(serialize = "Blanco")]
+    #[strum(props(Emoji = "⚪"))]
+    White,
+    #[strum(serialize = "Verde")]
+    #[strum(props(Emoji = "🟢"))]
+    Green,
+    #[strum(serialize = "Azul")]
+    #[strum(props(Emoji = "🔵"))]
+    Blue,
+    #[strum(serialize = "Morada")]
+    #[strum(props(Emoji = "🟣"))]
+    Purple,
+    #[strum(serialize = "Amarilla")]
+    #[strum(props(Emoji = "🟡"))]
+    Yellow
+}
+
+/// Serializes enums through the same Spanish names shown in Discord, so build specs read like the bot.
+/// Each type is given with the valid values listed when deserializing fails
+macro_rules! serde_as_display {
+    ($($t:ty => $valid:expr),*) => {
+        $(
+            impl serde::Serialize for $t {
+                fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    serializer.collect_str(self)
+                }
+            }
+
+            impl<'de> serde::Deserialize<'de> for $t {
+                fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    let value = String::deserialize(deserializer)?;
+                    <$t as std::str::FromStr>::from_str(&value)
+                        .map_err(|_| serde::de::Error::custom(format!("`{}` no es un valor válido, usa: {}", value, $valid)))
+                }
+            }
+        )*
+    };
+}
+
+serde_as_display!(
+    Gear => research::gear_items().iter().map(|i| i.to_string()).collect::<Vec<_>>().join(", "),
+    GearQuality => choices::<GearQuality>(),
+    CraftingLevel => "Nivel 1-50 o CP10-CP160",
+    MaterialTier => choices::<MaterialTier>(),
+    ArmourParts => choices::<ArmourParts>(),
+    ArmourWeights => choices::<ArmourWeights>(),
+    ArmourTraits => choices::<ArmourTraits>(),
+    ArmourEnchantments => choices::<ArmourEnchantments>(),
+    WeaponKind => format!("{}, {}", choices::<OneHandedWeapons>(), choices::<TwoHandedWeapons>()),
+    WeaponTraits => choices::<WeaponTraits>(),
+    WeaponEnchantments => choices::<WeaponEnchantments>(),
+    Jewelries => choices::<Jewelries>(),
+    JewelryTraits => choices::<JewelryTraits>(),
+    JewelryEnchantments => choices::<JewelryEnchantments>()
+);
+
+/// Every value of an enum as shown in Discord, to tell users what they can write
+pub fn choices<T: IntoEnumIterator + Display>() -> String {
+    T::iter().map(|v| v.to_string()).collect::<Vec<_>>().join(", ")
+}
+
+/// Most units a single order line can ask for, keeping material bills far from overflowing
+pub const MAX_QUANTITY: i32 = 9999;
+
+/// Splits a free text order line written as `Nombre (xN)`, with or without a leading dash, into
+/// the name and the quantity, which is one when not given
+pub(crate) fn split_quantity(line: &str) -> Result<(&str, i32), String> {
+    let line = line.trim().trim_start_matches(['-', '*', '•']).trim();
+    match line.rsplit_once('(') {
+        Some((name, quantity)) if quantity.ends_with(')') && quantity.trim_start().starts_with(['x', 'X']) => {
+            let quantity = quantity.trim_end_matches(')').trim().trim_start_matches(['x', 'X']).trim();
+            let quantity = quantity.parse::<i32>().ok().filter(|q| (1..=MAX_QUANTITY).contains(q))
+                .ok_or_else(|| format!("`{}` no es una cantidad válida, escribe un número entre 1 y {}", quantity, MAX_QUANTITY))?;
+            Ok((name.trim(), quantity))
+        }
+        _ => Ok((line, 1))
+    }
+}
+
+pub trait MaterialCost {
+    fn cost(&self) -> MaterialBill;
+}
+
+impl GearQuality {
+    /// Aspect rune giving a glyph this quality
+    pub fn aspect_rune(&self) -> RuneQualityMaterials {
+        match self {
+            GearQuality::White => RuneQualityMaterials::Ta,
+            GearQuality::Green => RuneQualityMaterials::Jejota,
+            GearQuality::Blue => RuneQualityMaterials::Denata,
+            GearQuality::Purple => RuneQualityMaterials::Rekuta,
+            GearQuality::Yellow => RuneQualityMaterials::Kuta,
+        }
+    }
+}
+
+fn get_blacksmith_quality_cost(quality: &GearQuality) -> MaterialBill {
+    match quality {
+        GearQuality::White => MaterialBill::new(),
+        GearQuality::Green => MaterialBill::new()
+            .with(2, BlacksmithQualityMaterials::HoningStone),
+        GearQuality::Blue => MaterialBill::new()
+            .with(2, BlacksmithQualityMaterials::HoningStone)
+            .with(3, BlacksmithQualityMaterials::DwarvenOil),
+        GearQuality::Purple => MaterialBill::new()
+            .with(2, BlacksmithQualityMaterials::HoningStone)
+            .with(3, BlacksmithQualityMaterials::DwarvenOil)
+            .with(4, BlacksmithQualityMaterials::GrainSolvent),
+        GearQuality::Yellow => MaterialBill::new()
+            .with(2, BlacksmithQualityMaterials::HoningStone)
+            .with(3, BlacksmithQualityMaterials::DwarvenOil)
+            .with(4, BlacksmithQualityMaterials::GrainSolvent)
+            .with(8, BlacksmithQualityMaterials::TemperingAlloy),
+    }
+}
+
+fn get_tailoring_quality_cost(quality: &GearQuality) -> MaterialBill {
+    match quality {
+        GearQuality::White => MaterialBill::new(),
+        GearQuality::Green => MaterialBill::new()
+            .with(2, TailoringQualityMaterials::Hemming),
+        GearQuality::Blue => MaterialBill::new()
+            .with(2, TailoringQualityMaterials::Hemming)
+            .with(3, TailoringQualityMaterials::Embroidery),
+        GearQuality::Purple => MaterialBill::new()
+            .with(2, TailoringQualityMaterials::Hemming)
+            .with(3, TailoringQualityMaterials::Embroidery)
+            .with(4, TailoringQualityMaterials::ElegantLining),
+        GearQuality::Yellow => MaterialBill::new()
+            .with(2, TailoringQualityMaterials::Hemming)
+            .with(3, TailoringQualityMaterials::Embroidery)
+            .with(4, TailoringQualityMaterials::ElegantLining)
+            .with(8, TailoringQualityMaterials::DreughWax)
+    }
+}
+
+fn get_woodworking_quality_cost(quality: &GearQuality) -> MaterialBill {
+    match quality {
+        GearQuality::White => MaterialBill::new(),
+        GearQuality::Green => MaterialBill::new()
+            .with(2, WoodworkingQualityMaterials::Pitch),
+        GearQuality::Blue => MaterialBill::new()
+            .with(2, WoodworkingQualityMaterials::Pitch)
+            .with(3, WoodworkingQualityMaterials::Turpen),
+        GearQuality::Purple => MaterialBill::new()
+            .with(2, WoodworkingQualityMaterials::Pitch)
+            .with(3, WoodworkingQualityMaterials::Turpen)
+            .with(4, WoodworkingQualityMaterials::Mastic),
+        GearQuality::Yellow => MaterialBill::new()
+            .with(2, WoodworkingQualityMaterials::Pitch)
+            .with(3, WoodworkingQualityMaterials::Turpen)
+            .with(4, WoodworkingQualityMaterials::Mastic)
+            .with(8, WoodworkingQualityMaterials::Rosin),
+    }
+}
+
+impl Display for Gear {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Gear::Weapon(w) => write!(f, "{}", w),
+            Gear::Armour(a) => write!(f, "{}", a),
+            Gear::Jewelry(j) => write!(f, "{}", j),
+        }
+    }
+}
+
+impl std::str::FromStr for Gear {
+    type Err = strum::ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(a) = ArmourParts::from_str(s) {
+            Ok(Gear::Armour(a))
+        } else if let Ok(j) = Jewelries::from_str(s) {
+            Ok(Gear::Jewelry(j))
+        } else if let Ok(w) = WeaponKind::from_str(s) {
+            Ok(Gear::Weapon(w))
+        } else {
+            Err(strum::ParseError::VariantNotFound)
+        }
+    }
+}
\ No newline at end of file
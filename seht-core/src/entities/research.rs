@@ -0,0 +1,226 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use strum::IntoEnumIterator;
+use crate::entities::Gear;
+use crate::entities::armour::{ArmourParts, ArmourTraits, ArmourWeights};
+use crate::entities::jewelry::{Jewelries, JewelryTraits};
+use crate::entities::weapon::{OneHandedWeapons, TwoHandedWeapons, WeaponKind, WeaponTraits};
+
+#[derive(Clone, Ord, PartialOrd, Eq, PartialEq)]
+pub enum GearTrait {
+    Weapon(WeaponTraits),
+    Armour(ArmourTraits),
+    Jewelry(JewelryTraits)
+}
+
+/// A kind of item traits are researched on. Armour is researched apart for each weight, except
+/// shields which are made at the woodworking station and have none
+#[derive(Clone, Ord, PartialOrd, Eq, PartialEq)]
+pub enum ResearchItem {
+    Weapon(WeaponKind),
+    Armour(ArmourParts, Option<ArmourWeights>),
+    Jewelry(Jewelries)
+}
+
+impl ResearchItem {
+    pub fn armour(part: ArmourParts, weight: ArmourWeights) -> Self {
+        match part {
+            ArmourParts::Shield => ResearchItem::Armour(part, None),
+            part => ResearchItem::Armour(part, Some(weight))
+        }
+    }
+
+    pub fn gear(&self) -> Gear {
+        match self {
+            ResearchItem::Weapon(w) => Gear::Weapon(w.clone()),
+            ResearchItem::Armour(a, _) => Gear::Armour(a.clone()),
+            ResearchItem::Jewelry(j) => Gear::Jewelry(j.clone())
+        }
+    }
+
+    pub fn weight(&self) -> Option<&ArmourWeights> {
+        match self {
+            ResearchItem::Armour(_, weight) => weight.as_ref(),
+            _ => None
+        }
+    }
+}
+
+impl Display for ResearchItem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.weight() {
+            Some(weight) => write!(f, "{} {}", self.gear(), weight),
+            None => write!(f, "{}", self.gear())
+        }
+    }
+}
+
+/// Parses an item as shown by the bot, armour with its weight after the part: `Cabeza Pesada`
+impl FromStr for ResearchItem {
+    type Err = strum::ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match Gear::from_str(s) {
+            Ok(Gear::Weapon(w)) => return Ok(ResearchItem::Weapon(w)),
+            Ok(Gear::Jewelry(j)) => return Ok(ResearchItem::Jewelry(j)),
+            Ok(Gear::Armour(ArmourParts::Shield)) => return Ok(ResearchItem::Armour(ArmourParts::Shield, None)),
+            _ => {}
+        }
+        let (part, weight) = s.rsplit_once(' ').ok_or(strum::ParseError::VariantNotFound)?;
+        match ArmourParts::from_str(part.trim())? {
+            ArmourParts::Shield => Err(strum::ParseError::VariantNotFound),
+            part => Ok(ResearchItem::Armour(part, Some(ArmourWeights::from_str(weight)?)))
+        }
+    }
+}
+
+/// A trait a crafter has researched on a given kind of item
+#[derive(Clone, Ord, PartialOrd, Eq, PartialEq)]
+pub struct KnownTrait {
+    pub item: ResearchItem,
+    pub gear_trait: GearTrait
+}
+
+impl KnownTrait {
+    /// Parses an item kind and a trait, checking the trait can be researched on that item
+    pub fn parse(item: &str, gear_trait: &str) -> Result<Self, strum::ParseError> {
+        let item = ResearchItem::from_str(item.trim())?;
+        let gear_trait = match item {
+            ResearchItem::Weapon(_) => GearTrait::Weapon(WeaponTraits::from_str(gear_trait.trim())?),
+            ResearchItem::Armour(..) => GearTrait::Armour(ArmourTraits::from_str(gear_trait.trim())?),
+            ResearchItem::Jewelry(_) => GearTrait::Jewelry(JewelryTraits::from_str(gear_trait.trim())?)
+        };
+        Ok(KnownTrait { item, gear_trait })
+    }
+}
+
+impl Display for GearTrait {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GearTrait::Weapon(t) => write!(f, "{}", t),
+            GearTrait::Armour(t) => write!(f, "{}", t),
+            GearTrait::Jewelry(t) => write!(f, "{}", t),
+        }
+    }
+}
+
+/// Every kind of piece that can be ordered
+pub fn gear_items() -> Vec<Gear> {
+    ArmourParts::iter().map(Gear::Armour)
+        .chain(OneHandedWeapons::iter().map(|w| Gear::Weapon(WeaponKind::OneHanded(w))))
+        .chain(TwoHandedWeapons::iter().map(|w| Gear::Weapon(WeaponKind::TwoHanded(w))))
+        .chain(Jewelries::iter().map(Gear::Jewelry))
+        .collect()
+}
+
+/// Every kind of item traits are researched on, armour once per weight
+pub fn research_items() -> Vec<ResearchItem> {
+    ArmourWeights::iter()
+        .flat_map(|weight| ArmourParts::iter()
+            .filter(|part| *part != ArmourParts::Shield)
+            .map(move |part| ResearchItem::Armour(part, Some(weight.clone()))))
+        .chain([ResearchItem::Armour(ArmourParts::Shield, None)])
+        .chain(OneHandedWeapons::iter().map(|w| ResearchItem::Weapon(WeaponKind::OneHanded(w))))
+        .chain(TwoHandedWeapons::iter().map(|w| ResearchItem::Weapon(WeaponKind::TwoHanded(w))))
+        .chain(Jewelries::iter().map(ResearchItem::Jewelry))
+        .collect()
+}
+
+pub fn research_traits(item: &ResearchItem) -> Vec<GearTrait> {
+    match item {
+        ResearchItem::Weapon(_) => WeaponTraits::iter().map(GearTrait::Weapon).collect(),
+        ResearchItem::Armour(..) => ArmourTraits::iter().map(GearTrait::Armour).collect(),
+        ResearchItem::Jewelry(_) => JewelryTraits::iter().map(GearTrait::Jewelry).collect()
+    }
+}
+
+/// Parses a bulk import, one item per line (or separated by `;`) followed by its known traits:
+/// `Cabeza Pesada: Divinidad, Imbuición`. Errors point to the offending line
+pub fn parse_research(text: &str) -> Result<Vec<KnownTrait>, String> {
+    let mut known = Vec::new();
+    for (idx, line) in text.split(['\n', ';']).enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let Some((item, traits)) = line.split_once(':') else {
+            return Err(format!("Línea {}: falta ':' entre la pieza y los rasgos en `{}`", idx + 1, line));
+        };
+        if matches!(Gear::from_str(item.trim()), Ok(Gear::Armour(part)) if part != ArmourParts::Shield) {
+            return Err(format!("Línea {}: falta el peso de `{}`, p. ej. `{} Pesada`", idx + 1, item.trim(), item.trim()));
+        }
+        for gear_trait in traits.split(',').filter(|t| !t.trim().is_empty()) {
+            known.push(KnownTrait::parse(item, gear_trait)
+                .map_err(|_| format!("Línea {}: no reconozco `{}` con el rasgo `{}`", idx + 1, item.trim(), gear_trait.trim()))?);
+        }
+    }
+    Ok(known)
+}
+
+/// Traits from `needed` the crafter has not researched yet
+pub fn missing_traits<'a>(known: &[KnownTrait], needed: &'a [KnownTrait]) -> Vec<&'a KnownTrait> {
+    needed.iter()
+        .filter(|n| !known.contains(n))
+        .collect()
+}
+
+/// Items from `needed` on which the crafter knows fewer than `traits` traits, as crafting a set requires
+pub fn items_below<'a>(known: &[KnownTrait], needed: &'a [KnownTrait], traits: usize) -> Vec<&'a ResearchItem> {
+    let mut items: Vec<&ResearchItem> = needed.iter()
+        .map(|n| &n.item)
+        .filter(|item| known.iter().filter(|k| &k.item == *item).count() < traits)
+        .collect();
+    items.sort();
+    items.dedup();
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn armour(part: ArmourParts, weight: ArmourWeights, gear_trait: ArmourTraits) -> KnownTrait {
+        KnownTrait { item: ResearchItem::armour(part, weight), gear_trait: GearTrait::Armour(gear_trait) }
+    }
+
+    #[test]
+    fn reads_a_bulk_import() {
+        let known = parse_research("Cabeza Pesada: Divinidad, Imbuición; Escudo: Divinidad\nBastón infernal: Precisión\n\n").unwrap();
+        assert!(known == [
+            armour(ArmourParts::Head, ArmourWeights::Heavy, ArmourTraits::Divines),
+            armour(ArmourParts::Head, ArmourWeights::Heavy, ArmourTraits::Infused),
+            armour(ArmourParts::Shield, ArmourWeights::Heavy, ArmourTraits::Divines),
+            KnownTrait { item: ResearchItem::Weapon(WeaponKind::TwoHanded(TwoHandedWeapons::FireStaff)), gear_trait: GearTrait::Weapon(WeaponTraits::Precise) }
+        ]);
+    }
+
+    #[test]
+    fn points_to_the_line_that_cannot_be_read() {
+        let why = |text| parse_research(text).err().unwrap();
+        assert_eq!(why("Anillo: Arcanidad\nCabeza: Divinidad"), "Línea 2: falta el peso de `Cabeza`, p. ej. `Cabeza Pesada`");
+        assert_eq!(why("Cabeza Pesada Divinidad"), "Línea 1: falta ':' entre la pieza y los rasgos en `Cabeza Pesada Divinidad`");
+        assert_eq!(why("Anillo: Divinidad"), "Línea 1: no reconozco `Anillo` con el rasgo `Divinidad`");
+    }
+
+    #[test]
+    fn shields_have_no_weight() {
+        assert_eq!(ResearchItem::armour(ArmourParts::Shield, ArmourWeights::Light).to_string(), "Escudo");
+        assert_eq!(ResearchItem::armour(ArmourParts::Head, ArmourWeights::Light).to_string(), "Cabeza Ligera");
+        assert!(ResearchItem::from_str("Escudo Pesada").is_err());
+        assert_eq!(research_items().len(), 7 * 3 + 1 + OneHandedWeapons::iter().count() + TwoHandedWeapons::iter().count() + Jewelries::iter().count());
+    }
+
+    #[test]
+    fn finds_the_gaps_in_the_research() {
+        let known = [armour(ArmourParts::Head, ArmourWeights::Heavy, ArmourTraits::Divines)];
+        let needed = [
+            armour(ArmourParts::Head, ArmourWeights::Heavy, ArmourTraits::Divines),
+            armour(ArmourParts::Head, ArmourWeights::Light, ArmourTraits::Divines)
+        ];
+        let missing = missing_traits(&known, &needed);
+        assert!(missing.len() == 1 && *missing[0] == needed[1]);
+        let below = items_below(&known, &needed, 1);
+        assert!(below.len() == 1 && *below[0] == needed[1].item);
+        assert!(items_below(&known, &needed[..1], 2).len() == 1);
+    }
+}
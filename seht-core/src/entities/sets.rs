@@ -0,0 +1,214 @@
+pub struct CraftableSet {
+    pub name_es: &'static str,
+    pub name_en: &'static str,
+    pub traits: usize,
+    pub location: &'static str,
+    pub bonuses: &'static [&'static str]
+}
+
+// Trait counts and bonuses follow the UESP list of crafted sets, https://en.uesp.net/wiki/Online:Crafted_Sets.
+// Requests and claims are checked against this catalog, so every set crafted at a station has to be here.
+// Each bonus is the effect of wearing five pieces, which is what tells sets apart
+pub const CRAFTABLE_SETS: &[CraftableSet] = &[
+    CraftableSet { name_es: "Viento de la muerte", name_en: "Death's Wind", traits: 2, location: "Glenumbra",
+        bonuses: &["5: Al ser derribado, inflige daño físico a los enemigos cercanos y los repele"] },
+    CraftableSet { name_es: "Garra cenicienta", name_en: "Ashen Grip", traits: 2, location: "Stonefalls",
+        bonuses: &["5: Al infligir daño directo, puedes escupir fuego que daña a los enemigos frente a ti"] },
+    CraftableSet { name_es: "Silencio de la noche", name_en: "Night's Silence", traits: 2, location: "Auridon",
+        bonuses: &["5: Anula la reducción de velocidad del sigilo y aumenta tu velocidad en sigilo"] },
+    CraftableSet { name_es: "Axioma innato", name_en: "Innate Axiom", traits: 2, location: "Clockwork City",
+        bonuses: &["5: Aumenta el daño de arma y hechizo, pero reduce la resistencia física y mágica"] },
+    CraftableSet { name_es: "Chamán naga", name_en: "Naga Shaman", traits: 2, location: "Murkmire",
+        bonuses: &["5: Al infligir daño, reduce la velocidad de movimiento del objetivo"] },
+    CraftableSet { name_es: "Pacto de Torug", name_en: "Torug's Pact", traits: 3, location: "Stormhaven",
+        bonuses: &["5: Aumenta la eficacia de los encantamientos de arma y reduce su enfriamiento"] },
+    CraftableSet { name_es: "Abrazo del crepúsculo", name_en: "Twilight's Embrace", traits: 3, location: "Deshaan",
+        bonuses: &["5: Al curar a un aliado, tú y él obtenéis Coraje menor"] },
+    CraftableSet { name_es: "Armadura del seductor", name_en: "Armor of the Seducer", traits: 3, location: "Grahtwood",
+        bonuses: &["5: Reduce el coste de magia de tus habilidades"] },
+    CraftableSet { name_es: "Prueba de fuego", name_en: "Trial by Fire", traits: 3, location: "Wrothgar",
+        bonuses: &["5: Aumenta la resistencia al tipo de daño elemental que más recibes"] },
+    CraftableSet { name_es: "Astucia del asesino", name_en: "Assassin's Guile", traits: 3, location: "Vvardenfell",
+        bonuses: &["5: Aumenta la eficacia de los venenos y reduce su tiempo de recarga"] },
+    CraftableSet { name_es: "Jinete experto", name_en: "Adept Rider", traits: 3, location: "Summerset",
+        bonuses: &["5: Aumenta la velocidad de montura y reduce el coste de esprintar"] },
+    CraftableSet { name_es: "Tutela de Vastarie", name_en: "Vastarie's Tutelage", traits: 3, location: "Elsweyr Norte",
+        bonuses: &["5: Al resucitar a un aliado, tú y él recuperáis magia y aguante más rápido"] },
+    CraftableSet { name_es: "Corsario osado", name_en: "Daring Corsair", traits: 3, location: "Elsweyr Sur",
+        bonuses: &["5: Aumenta la fuerza de los escudos de daño que das a tu grupo"] },
+    CraftableSet { name_es: "Victoria de Diamante", name_en: "Diamond's Victory", traits: 3, location: "Blackwood",
+        bonuses: &["5: Al matar a un enemigo, recuperas definitiva"] },
+    CraftableSet { name_es: "Trenza del druida", name_en: "Druid's Braid", traits: 3, location: "High Isle",
+        bonuses: &["5: Aumenta la recuperación de magia y aguante según la salud que te falta"] },
+    CraftableSet { name_es: "Réplica crítica", name_en: "Critical Riposte", traits: 3, location: "Skyrim Occidental",
+        bonuses: &["5: Aumenta la resistencia crítica y al recibir un golpe crítico desangras al atacante"] },
+    CraftableSet { name_es: "Favor de Stuhn", name_en: "Stuhn's Favor", traits: 3, location: "The Reach",
+        bonuses: &["5: Al dañar a un enemigo con Brecha, aumenta tu penetración física y mágica"] },
+    CraftableSet { name_es: "Vitalidad miserable", name_en: "Wretched Vitality", traits: 3, location: "Deadlands",
+        bonuses: &["5: Tras usar una habilidad definitiva, aumenta la recuperación de magia y aguante"] },
+    CraftableSet { name_es: "Corteza de Hist", name_en: "Hist Bark", traits: 4, location: "Shadowfen",
+        bonuses: &["5: Mientras bloqueas, aumenta tu probabilidad de esquivar ataques"] },
+    CraftableSet { name_es: "Don de Magnus", name_en: "Magnus' Gift", traits: 4, location: "Greenshade",
+        bonuses: &["5: Reduce el coste de tus habilidades definitivas"] },
+    CraftableSet { name_es: "Represalia de Whitestrake", name_en: "Whitestrake's Retribution", traits: 4, location: "Rivenspire",
+        bonuses: &["5: Al bajar del 30 % de salud, obtienes un escudo de daño"] },
+    CraftableSet { name_es: "Latón fortificado", name_en: "Fortified Brass", traits: 4, location: "Clockwork City",
+        bonuses: &["5: Aumenta la resistencia física y mágica, pero reduce el daño de arma y hechizo"] },
+    CraftableSet { name_es: "Poder de la legión perdida", name_en: "Might of the Lost Legion", traits: 4, location: "Murkmire",
+        bonuses: &["5: Al bloquear un ataque, aumenta tu daño de arma y hechizo"] },
+    CraftableSet { name_es: "Beso del vampiro", name_en: "Vampire's Kiss", traits: 5, location: "Eastmarch",
+        bonuses: &["5: Al matar a un enemigo, recuperas salud"] },
+    CraftableSet { name_es: "Canción de Lamae", name_en: "Song of Lamae", traits: 5, location: "Malabal Tor",
+        bonuses: &["5: Al recibir daño, puedes curarte y aumentar tu daño de arma"] },
+    CraftableSet { name_es: "Baluarte de Alessia", name_en: "Alessia's Bulwark", traits: 5, location: "Bangkorai",
+        bonuses: &["5: Los enemigos que te atacan cuerpo a cuerpo sufren Mutilación menor"] },
+    CraftableSet { name_es: "Conquista del noble", name_en: "Noble's Conquest", traits: 5, location: "Craglorn",
+        bonuses: &["5: Aumenta el daño de tus armas de asedio"] },
+    CraftableSet { name_es: "Favor de Tava", name_en: "Tava's Favor", traits: 5, location: "Hew's Bane",
+        bonuses: &["5: Al beber una poción, recuperas definitiva"] },
+    CraftableSet { name_es: "Gladiador de Kvatch", name_en: "Kvatch Gladiator", traits: 5, location: "Gold Coast",
+        bonuses: &["5: Reduce el daño que recibes de otros jugadores"] },
+    CraftableSet { name_es: "Determinación del senche-raht", name_en: "Senche-raht's Grit", traits: 5, location: "Elsweyr Norte",
+        bonuses: &["5: Aumenta la resistencia física y mágica mientras esprintas"] },
+    CraftableSet { name_es: "Desdén de la serpiente", name_en: "Serpent's Disdain", traits: 5, location: "High Isle",
+        bonuses: &["5: Aumenta el daño de arma y hechizo, pero también el coste de tus habilidades"] },
+    CraftableSet { name_es: "Agresor desencadenado", name_en: "Unchained Aggressor", traits: 5, location: "Skyrim Occidental",
+        bonuses: &["5: Al liberarte de un control, aumenta tu velocidad y tu daño"] },
+    CraftableSet { name_es: "Apetito de dragón", name_en: "Dragon's Appetite", traits: 5, location: "The Reach",
+        bonuses: &["5: Al infligir daño de sangrado, te curas"] },
+    CraftableSet { name_es: "Demoledor de Deadlands", name_en: "Deadlands Demolisher", traits: 5, location: "Deadlands",
+        bonuses: &["5: Al romperse tu escudo de daño, inflige daño a los enemigos cercanos"] },
+    CraftableSet { name_es: "Mirada de la Madre Noche", name_en: "Night Mother's Gaze", traits: 6, location: "Reaper's March",
+        bonuses: &["5: Tus golpes críticos aplican Brecha menor al objetivo"] },
+    CraftableSet { name_es: "Senda del sauce", name_en: "Willow's Path", traits: 6, location: "Grahtwood",
+        bonuses: &["5: Aumenta la recuperación de salud, magia y aguante"] },
+    CraftableSet { name_es: "Furia de Hunding", name_en: "Hunding's Rage", traits: 6, location: "The Rift",
+        bonuses: &["5: Aumenta el daño de arma y hechizo"] },
+    CraftableSet { name_es: "Ley de Julianos", name_en: "Law of Julianos", traits: 6, location: "Wrothgar",
+        bonuses: &["5: Aumenta el daño de arma y hechizo"] },
+    CraftableSet { name_es: "Rompegrilletes", name_en: "Shacklebreaker", traits: 6, location: "Vvardenfell",
+        bonuses: &["5: Aumenta la magia y el aguante máximos"] },
+    CraftableSet { name_es: "Agudeza mecánica", name_en: "Mechanical Acuity", traits: 6, location: "Clockwork City",
+        bonuses: &["5: Al infligir daño, tus ataques pueden ser críticos seguros durante unos segundos"] },
+    CraftableSet { name_es: "Favor de Nocturnal", name_en: "Nocturnal's Favor", traits: 6, location: "Summerset",
+        bonuses: &["5: Tus ataques pesados con bastón de restauración restauran magia adicional"] },
+    CraftableSet { name_es: "Antigua guardia dragón", name_en: "Ancient Dragonguard", traits: 6, location: "Elsweyr Sur",
+        bonuses: &["5: Aumenta el daño de arma y hechizo contra dragones"] },
+    CraftableSet { name_es: "Conquistador del corazón", name_en: "Heartland Conqueror", traits: 6, location: "Blackwood",
+        bonuses: &["5: Al completar un ataque pesado, aumenta tu penetración"] },
+    CraftableSet { name_es: "Redistribuidor", name_en: "Redistributor", traits: 7, location: "Craglorn",
+        bonuses: &["5: Al activar una sinergia, restauras aguante a los aliados cercanos"] },
+    CraftableSet { name_es: "Alquimista astuto", name_en: "Clever Alchemist", traits: 7, location: "Hew's Bane",
+        bonuses: &["5: Al beber una poción en combate, aumenta el daño de arma y hechizo"] },
+    CraftableSet { name_es: "Legado de Varen", name_en: "Varen's Legacy", traits: 7, location: "Gold Coast",
+        bonuses: &["5: Al recibir daño, acumulas poder que aumenta tu siguiente curación"] },
+    CraftableSet { name_es: "Coleccionista de estacas", name_en: "Grave-Stake Collector", traits: 7, location: "Murkmire",
+        bonuses: &["5: Al matar a un enemigo, aumenta tu daño de arma y hechizo"] },
+    CraftableSet { name_es: "Combatiente intrépido", name_en: "Dauntless Combatant", traits: 7, location: "Skyrim Occidental",
+        bonuses: &["5: Aumenta la resistencia física y mágica y al bloquear recuperas salud"] },
+    CraftableSet { name_es: "Parásito de hechizos", name_en: "Spell Parasite", traits: 7, location: "The Reach",
+        bonuses: &["5: Aumenta la resistencia mágica y el daño de hechizo"] },
+    CraftableSet { name_es: "Frasco de hierro", name_en: "Iron Flask", traits: 7, location: "Deadlands",
+        bonuses: &["5: Al beber una poción, reduce el daño que recibes durante unos segundos"] },
+    CraftableSet { name_es: "Cólera de la orden", name_en: "Order's Wrath", traits: 8, location: "High Isle",
+        bonuses: &["5: Aumenta tu daño crítico"] },
+    CraftableSet { name_es: "Enemigo de Oblivion", name_en: "Oblivion's Foe", traits: 8, location: "Coldharbour",
+        bonuses: &["5: Al infligir daño, aplica Brecha menor al objetivo"] },
+    CraftableSet { name_es: "Ojo del espectro", name_en: "Spectre's Eye", traits: 8, location: "Coldharbour",
+        bonuses: &["5: Al recibir daño, puedes invocar espectros que aterrorizan a los enemigos cercanos"] },
+    CraftableSet { name_es: "Esperanza de Kagrenac", name_en: "Kagrenac's Hope", traits: 8, location: "Cyrodiil",
+        bonuses: &["5: Aumenta la recuperación de magia"] },
+    CraftableSet { name_es: "Escamas de Orgnum", name_en: "Orgnum's Scales", traits: 8, location: "Cyrodiil",
+        bonuses: &["5: Aumenta la recuperación de salud mientras estás por debajo del 60 % de salud"] },
+    CraftableSet { name_es: "Ojos de Mara", name_en: "Eyes of Mara", traits: 8, location: "Cyrodiil",
+        bonuses: &["5: Al curar, puedes curar también a los aliados cercanos"] },
+    CraftableSet { name_es: "Maldición de Shalidor", name_en: "Shalidor's Curse", traits: 8, location: "Cyrodiil",
+        bonuses: &["5: Aumenta la duración de tus habilidades de magia"] },
+    CraftableSet { name_es: "Engaño daédrico", name_en: "Daedric Trickery", traits: 8, location: "Vvardenfell",
+        bonuses: &["5: Al cambiar de barra, obtienes una mejora aleatoria"] },
+    CraftableSet { name_es: "Favorito de Coldharbour", name_en: "Coldharbour's Favorite", traits: 8, location: "Elsweyr Norte",
+        bonuses: &["5: Tus curaciones críticas liberan una explosión que cura a los aliados y daña a los enemigos"] },
+    CraftableSet { name_es: "Estrella dos veces nacida", name_en: "Twice-Born Star", traits: 9, location: "Craglorn",
+        bonuses: &["5: Permite tener dos piedras de Mundus activas"] },
+    CraftableSet { name_es: "Maestro de armaduras", name_en: "Armor Master", traits: 9, location: "Craglorn",
+        bonuses: &["5: Aumenta la resistencia física y mágica y la resistencia a efectos de estado"] },
+    CraftableSet { name_es: "Morkuldin", name_en: "Morkuldin", traits: 9, location: "Wrothgar",
+        bonuses: &["5: Invoca un arma animada que ataca a tus enemigos"] },
+    CraftableSet { name_es: "Caza eterna", name_en: "Eternal Hunt", traits: 9, location: "Hew's Bane",
+        bonuses: &["5: Al esquivar rodando, dejas una runa que inmoviliza a los enemigos"] },
+    CraftableSet { name_es: "Aptitud de Pelinal", name_en: "Pelinal's Aptitude", traits: 9, location: "Gold Coast",
+        bonuses: &["5: Tus ataques ligeros y pesados infligen daño adicional"] },
+    CraftableSet { name_es: "Semblanza sload", name_en: "Sload's Semblance", traits: 9, location: "Summerset",
+        bonuses: &["5: Tus ataques aplican una maldición que explota y daña a los enemigos cercanos"] },
+    CraftableSet { name_es: "Acólito de la luna nueva", name_en: "New Moon Acolyte", traits: 9, location: "Elsweyr Sur",
+        bonuses: &["5: Aumenta el daño de arma y hechizo de tus ataques pesados"] },
+    CraftableSet { name_es: "Susurrador Hist", name_en: "Hist Whisperer", traits: 9, location: "Blackwood",
+        bonuses: &["5: Reduce el tiempo de carga de tus ataques pesados"] },
+];
+
+// Set and catalog names are compared ignoring case, accents and punctuation
+pub(crate) fn normalize(name: &str) -> String {
+    name.chars()
+        .filter_map(|c| match c.to_lowercase().next().unwrap_or(c) {
+            'á' | 'à' | 'ä' => Some('a'),
+            'é' | 'è' | 'ë' => Some('e'),
+            'í' | 'ì' | 'ï' => Some('i'),
+            'ó' | 'ò' | 'ö' => Some('o'),
+            'ú' | 'ù' | 'ü' => Some('u'),
+            c if c.is_alphanumeric() || c == ' ' => Some(c),
+            _ => None
+        })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+pub fn find_set(name: &str) -> Option<&'static CraftableSet> {
+    let name = normalize(name);
+    CRAFTABLE_SETS.iter()
+        .find(|s| normalize(s.name_es) == name || normalize(s.name_en) == name)
+}
+
+pub fn search_sets(query: &str) -> Vec<&'static CraftableSet> {
+    let query = normalize(query);
+    CRAFTABLE_SETS.iter()
+        .filter(|s| normalize(s.name_es).contains(&query) || normalize(s.name_en).contains(&query))
+        .collect()
+}
+
+/// Sets sharing any word with the given name, to suggest when it is not in the catalog
+pub fn suggest_sets(name: &str) -> Vec<&'static CraftableSet> {
+    let name = normalize(name);
+    let words: Vec<&str> = name.split(' ').filter(|w| w.len() > 3).collect();
+    CRAFTABLE_SETS.iter()
+        .filter(|s| {
+            let es = normalize(s.name_es);
+            let en = normalize(s.name_en);
+            words.iter().any(|w| es.contains(w) || en.contains(w))
+        })
+        .take(5)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_set_has_its_bonuses_and_a_unique_name() {
+        for set in CRAFTABLE_SETS {
+            assert!(!set.bonuses.is_empty() && set.bonuses.iter().all(|b| !b.trim().is_empty()), "{}", set.name_en);
+            assert!((2..=9).contains(&set.traits), "{}", set.name_en);
+            assert_eq!(find_set(set.name_en).map(|s| s.name_es), Some(set.name_es));
+            assert_eq!(find_set(set.name_es).map(|s| s.name_en), Some(set.name_en));
+        }
+    }
+
+    #[test]
+    fn finds_sets_ignoring_case_and_accents() {
+        assert_eq!(find_set("colera de la ORDEN").map(|s| s.traits), Some(8));
+        assert_eq!(find_set("Dragons Appetite").map(|s| s.name_es), Some("Apetito de dragón"));
+        assert!(find_set("Set inventado").is_none());
+    }
+}
@@ -1,8 +1,10 @@
 use std::fmt::{Display, Formatter};
-use strum_macros::{Display, EnumIter, EnumMessage, EnumString};
-use std::string::ToString;
-use crate::entities::{GearQuality, get_blacksmith_quality_cost, get_enchantment_quality_cost, get_woodworking_quality_cost, MaterialCost};
-use crate::entities::materials::{EssenceRunes, PartMaterials, PotencyRunes, WeaponTraitMaterials};
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumIter, EnumMessage, EnumProperty, EnumString};
+use crate::entities::{GearQuality, get_blacksmith_quality_cost, get_woodworking_quality_cost, MaterialCost};
+use crate::entities::level::CraftingLevel;
+use crate::entities::glyph::{Enchantment, Glyph, Potency};
+use crate::entities::materials::{EssenceRunes, MaterialBill, WeaponTraitMaterials};
 
 #[derive(Clone, EnumIter, Ord, PartialOrd, Eq, PartialEq, Display, EnumString, EnumMessage)]
 pub enum OneHandedWeapons {
@@ -85,57 +87,78 @@ pub enum WeaponTraits {
     Decisive
 }
 
-#[derive(Clone, EnumIter, Ord, PartialOrd, Eq, PartialEq, Display, EnumString, EnumMessage)]
+#[derive(Clone, EnumIter, Ord, PartialOrd, Eq, PartialEq, Display, EnumString, EnumMessage, EnumProperty)]
 pub enum WeaponEnchantments {
     /// Inflige daño de llamas
     #[strum(serialize = "Glifo de fuego")]
+    #[strum(props(English = "Glyph of Flame"))]
     Fire,
     /// Inflige daño de escarcha
     #[strum(serialize = "Glifo de escarcha")]
+    #[strum(props(English = "Glyph of Frost"))]
     Frost,
     /// Inflige daño de descarga eléctica
     #[strum(serialize = "Glifo de descarga")]
+    #[strum(props(English = "Glyph of Shock"))]
     Shock,
     /// Inflige daño de veneno
     #[strum(serialize = "Glifo de veneno")]
+    #[strum(props(English = "Glyph of Poison"))]
     Poison,
     /// Inflige daño de enfermedad
     #[strum(serialize = "Glifo de podredumbre")]
+    #[strum(props(English = "Glyph of Foulness"))]
     Foulness,
     /// Inflige daño de Oblivion usando la salud máxima del enemigo
     #[strum(serialize = "Glifo de disminución de salud")]
+    #[strum(props(English = "Glyph of Decrease Health"))]
     DecreaseHealth,
     /// Otorga un escudo de daño que protege del daño
     #[strum(serialize = "Glifo de robustez")]
+    #[strum(props(English = "Glyph of Hardening"))]
     Hardening,
     /// Inglige daño de magia y restablece salud
     #[strum(serialize = "Glifo de absorción de salud")]
+    #[strum(props(English = "Glyph of Absorb Health"))]
     AbsorbHealth,
     /// Inflige daño de magia y recuperas magia
     #[strum(serialize = "Glifo de absorción de magia")]
+    #[strum(props(English = "Glyph of Absorb Magicka"))]
     AbsorbMagicka,
     /// Inflige daño físico y recuperas aguante
     #[strum(serialize = "Glifo de absorción de aguante")]
+    #[strum(props(English = "Glyph of Absorb Stamina"))]
     AbsorbStamina,
     /// Aumenta el daño de arma y hechizo
     #[strum(serialize = "Glifo de daño por arma")]
+    #[strum(props(English = "Glyph of Weapon Damage"))]
     WeaponDamage,
     /// Reduce el daño de arma y hechizo del objetivo
     #[strum(serialize = "Glifo de debilidad")]
+    #[strum(props(English = "Glyph of Weakening"))]
     Weakening,
     /// Reduce la resistencia física y a hechizos del objetivo
     #[strum(serialize = "Glifo de aplastamiento")]
+    #[strum(props(English = "Glyph of Crushing"))]
     Crushing,
     /// Inflige daño de magia y restablece salud, magia y aguante
     #[strum(serialize = "Glifo de asalto prismático")]
+    #[strum(props(English = "Glyph of Prismatic Onslaught"))]
     PrismaticOnslaught
 }
 
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Weapon {
+    #[serde(rename = "pieza")]
     pub kind: WeaponKind,
+    #[serde(rename = "rasgo")]
     pub weapon_trait: WeaponTraits,
+    #[serde(rename = "glifo", default, skip_serializing_if = "Option::is_none")]
     pub enchantment: Option<WeaponEnchantments>,
-    pub quality: GearQuality
+    #[serde(rename = "calidad")]
+    pub quality: GearQuality,
+    #[serde(rename = "nivel", default)]
+    pub level: CraftingLevel
 }
 
 impl std::str::FromStr for WeaponKind {
@@ -161,74 +184,60 @@ impl Display for WeaponKind {
     }
 }
 
-impl MaterialCost for OneHandedWeapons {
-    fn cost(&self) -> Vec<(i32, String)> {
-        match *self {
-            OneHandedWeapons::Dagger => vec![(100, PartMaterials::RubediteIngots.to_string())],
-            _ => vec![(110, PartMaterials::RubediteIngots.to_string())],
-        }
-    }
-}
-
-impl MaterialCost for TwoHandedWeapons {
-    fn cost(&self) -> Vec<(i32, String)> {
-        match *self {
-            TwoHandedWeapons::Bow |
-            TwoHandedWeapons::FireStaff |
-            TwoHandedWeapons::LightningStaff |
-            TwoHandedWeapons::RestorationStaff => vec![(120, PartMaterials::SandedRubyAsh.to_string())],
-            _ => vec![(140, PartMaterials::RubediteIngots.to_string())]
-        }
-    }
-}
+impl WeaponKind {
+    fn part_cost(&self, level: &CraftingLevel) -> MaterialBill {
+        let tier = level.tier();
+        let (size, material) = match self {
+            WeaponKind::OneHanded(OneHandedWeapons::Dagger) => (-3, tier.ingot()),
+            WeaponKind::OneHanded(_) => (-2, tier.ingot()),
+            WeaponKind::TwoHanded(TwoHandedWeapons::Mace | TwoHandedWeapons::Sword | TwoHandedWeapons::Axe) => (1, tier.ingot()),
+            WeaponKind::TwoHanded(_) => (-1, tier.wood())
+        };
 
-impl MaterialCost for WeaponKind {
-    fn cost(&self) -> Vec<(i32, String)> {
-        match self {
-            WeaponKind::OneHanded(w) => w.cost(),
-            WeaponKind::TwoHanded(w) => w.cost()
-        }
+        MaterialBill::new().with(level.part_amount(size), material)
     }
 }
 
 impl MaterialCost for WeaponTraits {
-    fn cost(&self) -> Vec<(i32, String)> {
+    fn cost(&self) -> MaterialBill {
         match *self {
-            WeaponTraits::Charged => vec![(1, WeaponTraitMaterials::Amethyst.to_string())],
-            WeaponTraits::Defending => vec![(1, WeaponTraitMaterials::Turquoise.to_string())],
-            WeaponTraits::Powered => vec![(1, WeaponTraitMaterials::Chysolite.to_string())],
-            WeaponTraits::Infused => vec![(1, WeaponTraitMaterials::Jade.to_string())],
-            WeaponTraits::Nirnhoned => vec![(1, WeaponTraitMaterials::PotentNirncrux.to_string())],
-            WeaponTraits::Precise => vec![(1, WeaponTraitMaterials::Ruby.to_string())],
-            WeaponTraits::Sharpened => vec![(1, WeaponTraitMaterials::FireOpal.to_string())],
-            WeaponTraits::Training => vec![(1, WeaponTraitMaterials::Carnelian.to_string())],
-            WeaponTraits::Decisive => vec![(1, WeaponTraitMaterials::Citrine.to_string())],
+            WeaponTraits::Charged => MaterialBill::new().with(1, WeaponTraitMaterials::Amethyst),
+            WeaponTraits::Defending => MaterialBill::new().with(1, WeaponTraitMaterials::Turquoise),
+            WeaponTraits::Powered => MaterialBill::new().with(1, WeaponTraitMaterials::Chysolite),
+            WeaponTraits::Infused => MaterialBill::new().with(1, WeaponTraitMaterials::Jade),
+            WeaponTraits::Nirnhoned => MaterialBill::new().with(1, WeaponTraitMaterials::PotentNirncrux),
+            WeaponTraits::Precise => MaterialBill::new().with(1, WeaponTraitMaterials::Ruby),
+            WeaponTraits::Sharpened => MaterialBill::new().with(1, WeaponTraitMaterials::FireOpal),
+            WeaponTraits::Training => MaterialBill::new().with(1, WeaponTraitMaterials::Carnelian),
+            WeaponTraits::Decisive => MaterialBill::new().with(1, WeaponTraitMaterials::Citrine),
         }
     }
 }
 
-impl MaterialCost for WeaponEnchantments {
-    fn cost(&self) -> Vec<(i32, String)> {
+impl WeaponEnchantments {
+    /// Kind of potency rune and essence rune of the glyph, the exact potency rune depending on
+    /// its level and the aspect rune on its quality
+    pub fn runes(&self) -> (Potency, EssenceRunes) {
         match *self {
-            WeaponEnchantments::Fire => vec![(1, PotencyRunes::Repora.to_string()), (1, EssenceRunes::Rakeipa.to_string())],
-            WeaponEnchantments::Frost => vec![(1, PotencyRunes::Repora.to_string()), (1, EssenceRunes::Dekeipa.to_string())],
-            WeaponEnchantments::Shock => vec![(1, PotencyRunes::Repora.to_string()), (1, EssenceRunes::Meip.to_string())],
-            WeaponEnchantments::Poison => vec![(1, PotencyRunes::Repora.to_string()), (1, EssenceRunes::Kuoko.to_string())],
-            WeaponEnchantments::Foulness => vec![(1, PotencyRunes::Repora.to_string()), (1, EssenceRunes::Haoko.to_string())],
-            WeaponEnchantments::DecreaseHealth => vec![(1, PotencyRunes::Itade.to_string()), (1, EssenceRunes::Okoma.to_string())],
-            WeaponEnchantments::Hardening => vec![(1, PotencyRunes::Repora.to_string()), (1, EssenceRunes::Deteri.to_string())],
-            WeaponEnchantments::AbsorbHealth => vec![(1, PotencyRunes::Itade.to_string()), (1, EssenceRunes::Oko.to_string())],
-            WeaponEnchantments::AbsorbMagicka => vec![(1, PotencyRunes::Itade.to_string()), (1, EssenceRunes::Makko.to_string())],
-            WeaponEnchantments::AbsorbStamina => vec![(1, PotencyRunes::Itade.to_string()), (1, EssenceRunes::Deni.to_string())],
-            WeaponEnchantments::WeaponDamage => vec![(1, PotencyRunes::Repora.to_string()), (1, EssenceRunes::Okori.to_string())],
-            WeaponEnchantments::Weakening => vec![(1, PotencyRunes::Itade.to_string()), (1, EssenceRunes::Okori.to_string())],
-            WeaponEnchantments::Crushing => vec![(1, PotencyRunes::Itade.to_string()), (1, EssenceRunes::Deteri.to_string())],
-            WeaponEnchantments::PrismaticOnslaught => vec![(1, PotencyRunes::Itade.to_string()), (1, EssenceRunes::Hakeijo.to_string())],
+            WeaponEnchantments::Fire => (Potency::Additive, EssenceRunes::Rakeipa),
+            WeaponEnchantments::Frost => (Potency::Additive, EssenceRunes::Dekeipa),
+            WeaponEnchantments::Shock => (Potency::Additive, EssenceRunes::Meip),
+            WeaponEnchantments::Poison => (Potency::Additive, EssenceRunes::Kuoko),
+            WeaponEnchantments::Foulness => (Potency::Additive, EssenceRunes::Haoko),
+            WeaponEnchantments::DecreaseHealth => (Potency::Subtractive, EssenceRunes::Okoma),
+            WeaponEnchantments::Hardening => (Potency::Additive, EssenceRunes::Deteri),
+            WeaponEnchantments::AbsorbHealth => (Potency::Subtractive, EssenceRunes::Oko),
+            WeaponEnchantments::AbsorbMagicka => (Potency::Subtractive, EssenceRunes::Makko),
+            WeaponEnchantments::AbsorbStamina => (Potency::Subtractive, EssenceRunes::Deni),
+            WeaponEnchantments::WeaponDamage => (Potency::Additive, EssenceRunes::Okori),
+            WeaponEnchantments::Weakening => (Potency::Subtractive, EssenceRunes::Okori),
+            WeaponEnchantments::Crushing => (Potency::Subtractive, EssenceRunes::Deteri),
+            WeaponEnchantments::PrismaticOnslaught => (Potency::Subtractive, EssenceRunes::Hakeijo),
         }
     }
 }
 
-fn get_quality_mats(weapon: &WeaponKind, quality: &GearQuality) -> Vec<(i32, String)> {
+fn get_quality_mats(weapon: &WeaponKind, quality: &GearQuality) -> MaterialBill {
     match weapon {
         WeaponKind::OneHanded(_) => get_blacksmith_quality_cost(quality),
         WeaponKind::TwoHanded(w) => match w {
@@ -239,15 +248,14 @@ fn get_quality_mats(weapon: &WeaponKind, quality: &GearQuality) -> Vec<(i32, Str
 }
 
 impl MaterialCost for Weapon {
-    fn cost(&self) -> Vec<(i32, String)> {
-        let mut vec = Vec::new();
-        vec.append(&mut self.kind.cost());
-        vec.append(&mut self.weapon_trait.cost());
+    fn cost(&self) -> MaterialBill {
+        let mut bill = MaterialBill::new();
+        bill.merge(&self.kind.part_cost(&self.level));
+        bill.merge(&self.weapon_trait.cost());
         if let Some(e) = &self.enchantment {
-            vec.append(&mut e.cost());
-            vec.append(&mut get_enchantment_quality_cost(&self.quality));
+            bill.merge(&Glyph::new(&Enchantment::Weapon(e.clone()), &self.quality, self.level).cost());
         }
-        vec.append(&mut get_quality_mats(&self.kind, &self.quality));
-        vec
+        bill.merge(&get_quality_mats(&self.kind, &self.quality));
+        bill
     }
 }
\ No newline at end of file
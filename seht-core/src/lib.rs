@@ -0,0 +1,6 @@
+//! Crafting data and material cost computations, independent of the Discord bot
+
+pub mod build;
+pub mod entities;
+pub mod piece;
+pub mod wizard;
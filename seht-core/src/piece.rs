@@ -0,0 +1,241 @@
+use std::fmt::Display;
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+use crate::entities::{choices, Gear, GearQuality, MaterialCost};
+use crate::entities::armour::{Armour, ArmourEnchantments, ArmourTraits, ArmourWeights};
+use crate::entities::jewelry::{Jewelry, JewelryEnchantments, JewelryTraits};
+use crate::entities::level::CraftingLevel;
+use crate::entities::materials::MaterialBill;
+use crate::entities::research::{self, GearTrait, KnownTrait, ResearchItem};
+use crate::entities::weapon::{Weapon, WeaponEnchantments, WeaponTraits};
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(tag = "tipo")]
+pub enum SetPiece {
+    #[serde(rename = "arma")]
+    Weapon(Weapon),
+    #[serde(rename = "armadura")]
+    Armour(Armour),
+    #[serde(rename = "joya")]
+    Jewelry(Jewelry)
+}
+
+impl SetPiece {
+    pub fn level(&self) -> CraftingLevel {
+        match self {
+            SetPiece::Weapon(w) => w.level,
+            SetPiece::Armour(a) => a.level,
+            SetPiece::Jewelry(j) => j.level
+        }
+    }
+
+    pub fn set_quality(&mut self, quality: GearQuality) {
+        match self {
+            SetPiece::Weapon(w) => w.quality = quality,
+            SetPiece::Armour(a) => a.quality = quality,
+            SetPiece::Jewelry(j) => j.quality = quality
+        }
+    }
+
+    pub fn item(&self) -> Gear {
+        match self {
+            SetPiece::Weapon(w) => Gear::Weapon(w.kind.clone()),
+            SetPiece::Armour(a) => Gear::Armour(a.kind.clone()),
+            SetPiece::Jewelry(j) => Gear::Jewelry(j.kind.clone())
+        }
+    }
+
+    pub fn has_enchantment(&self) -> bool {
+        match self {
+            SetPiece::Weapon(w) => w.enchantment.is_some(),
+            SetPiece::Armour(a) => a.enchantment.is_some(),
+            SetPiece::Jewelry(j) => j.enchantment.is_some()
+        }
+    }
+
+    pub fn clear_enchantment(&mut self) {
+        match self {
+            SetPiece::Weapon(w) => w.enchantment = None,
+            SetPiece::Armour(a) => a.enchantment = None,
+            SetPiece::Jewelry(j) => j.enchantment = None
+        }
+    }
+
+    /// The piece as written in a JSON build spec
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("pieces always serialize")
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        serde_json::from_str(text).map_err(|e| e.to_string())
+    }
+}
+
+impl From<&SetPiece> for KnownTrait {
+    fn from(piece: &SetPiece) -> Self {
+        match piece {
+            SetPiece::Weapon(w) => KnownTrait { item: ResearchItem::Weapon(w.kind.clone()), gear_trait: GearTrait::Weapon(w.weapon_trait.clone()) },
+            SetPiece::Armour(a) => KnownTrait { item: ResearchItem::armour(a.kind.clone(), a.weight.clone()), gear_trait: GearTrait::Armour(a.armour_trait.clone()) },
+            SetPiece::Jewelry(j) => KnownTrait { item: ResearchItem::Jewelry(j.kind.clone()), gear_trait: GearTrait::Jewelry(j.jewelry_trait.clone()) }
+        }
+    }
+}
+
+impl MaterialCost for SetPiece {
+    fn cost(&self) -> MaterialBill {
+        match self {
+            SetPiece::Weapon(w) => w.cost(),
+            SetPiece::Armour(a) => a.cost(),
+            SetPiece::Jewelry(j) => j.cost()
+        }
+    }
+}
+
+pub trait ResearchCost {
+    fn research_cost(&self) -> MaterialBill;
+}
+
+impl ResearchCost for SetPiece {
+    fn research_cost(&self) -> MaterialBill {
+        match self {
+            SetPiece::Weapon(w) => w.weapon_trait.cost(),
+            SetPiece::Armour(a) => a.armour_trait.cost(),
+            SetPiece::Jewelry(j) => j.jewelry_trait.cost()
+        }
+    }
+}
+
+/// Materials needed to craft every piece
+pub fn material_cost(pieces: &[SetPiece]) -> MaterialBill {
+    pieces.iter().map(|p| p.cost()).collect()
+}
+
+/// Materials consumed by researching the trait of every piece
+pub fn research_cost(pieces: &[SetPiece]) -> MaterialBill {
+    pieces.iter().map(|p| p.research_cost()).collect()
+}
+
+/// Parses a piece written as `Pieza[:Peso]:Rasgo:Calidad[:Glifo][:Nivel]`, the weight only being
+/// given for armour, e.g. `Cabeza:Pesada:Divinidad:Amarilla:Glifo de salud`. Pieces without a level
+/// are crafted at `level`
+pub fn parse_piece(spec: &str, level: CraftingLevel) -> Result<SetPiece, String> {
+    let fields: Vec<&str> = spec.split(':').map(str::trim).collect();
+    let item = Gear::from_str(fields[0]).map_err(|_| {
+        let items: Vec<String> = research::gear_items().iter().map(|i| i.to_string()).collect();
+        format!("`{}` no es una pieza conocida, usa: {}", fields[0], items.join(", "))
+    })?;
+    let (weight, rest) = match item {
+        Gear::Armour(_) => {
+            let weight = fields.get(1).ok_or_else(|| format!("falta el peso de `{}`", fields[0]))?;
+            (Some(parse_field::<ArmourWeights>(weight, "peso")?), &fields[2..])
+        }
+        _ => (None, &fields[1..])
+    };
+    let [gear_trait, quality, extra @ ..] = rest else {
+        return Err(format!("`{}` necesita al menos rasgo y calidad", spec));
+    };
+    let quality = parse_field::<GearQuality>(quality, "calidad")?;
+
+    let mut level = level;
+    let mut enchantment = None;
+    for field in extra {
+        match CraftingLevel::from_str(field) {
+            Ok(l) => level = l,
+            Err(_) if enchantment.is_none() => enchantment = Some(*field),
+            Err(_) => return Err(format!("`{}` sobra en `{}`, tras la calidad solo van el glifo y el nivel", field, spec))
+        }
+    }
+
+    Ok(match item {
+        Gear::Weapon(kind) => SetPiece::Weapon(Weapon {
+            kind,
+            weapon_trait: parse_field::<WeaponTraits>(gear_trait, "rasgo")?,
+            enchantment: enchantment.map(|e| parse_field::<WeaponEnchantments>(e, "glifo")).transpose()?,
+            quality,
+            level,
+        }),
+        Gear::Armour(kind) => SetPiece::Armour(Armour {
+            kind,
+            weight: weight.unwrap(),
+            armour_trait: parse_field::<ArmourTraits>(gear_trait, "rasgo")?,
+            enchantment: enchantment.map(|e| parse_field::<ArmourEnchantments>(e, "glifo")).transpose()?,
+            quality,
+            level,
+        }),
+        Gear::Jewelry(kind) => SetPiece::Jewelry(Jewelry {
+            kind,
+            jewelry_trait: parse_field::<JewelryTraits>(gear_trait, "rasgo")?,
+            enchantment: enchantment.map(|e| parse_field::<JewelryEnchantments>(e, "glifo")).transpose()?,
+            quality,
+            level,
+        })
+    })
+}
+
+pub(crate) fn parse_field<T: FromStr + IntoEnumIterator + Display>(value: &str, name: &str) -> Result<T, String> {
+    T::from_str(value).map_err(|_| format!("`{}` no es válido como {}, usa: {}", value, name, choices::<T>()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::materials::{ArmourTraitMaterials, Material, PartMaterials};
+
+    fn amount(bill: &MaterialBill, material: Material) -> i32 {
+        bill.iter().find(|(m, _)| **m == material).map(|(_, amount)| *amount).unwrap_or_default()
+    }
+
+    #[test]
+    fn a_cp160_head_costs_130_rubedite() {
+        let head = parse_piece("Cabeza:Pesada:Divinidad:Blanco", CraftingLevel::CP160).unwrap();
+        let bill = head.cost();
+        assert_eq!(amount(&bill, Material::Part(PartMaterials::RubediteIngots)), 130);
+        assert_eq!(amount(&bill, Material::ArmourTrait(ArmourTraitMaterials::Sapphire)), 1);
+        assert_eq!(bill.iter().count(), 2);
+    }
+
+    #[test]
+    fn bigger_pieces_cost_more_and_light_armour_uses_cloth() {
+        let body = parse_piece("Cuerpo:Ligera:Divinidad:Blanco", CraftingLevel::CP160).unwrap();
+        assert_eq!(amount(&body.cost(), Material::Part(PartMaterials::AncestorSilk)), 150);
+        let head = parse_piece("Cabeza:Media:Divinidad:Blanco:CP150", CraftingLevel::CP160).unwrap();
+        assert_eq!(amount(&head.cost(), Material::Part(PartMaterials::RubedoLeather)), 13);
+    }
+
+    #[test]
+    fn adds_the_pieces_and_their_research() {
+        let pieces = [
+            parse_piece("Cabeza:Pesada:Divinidad:Blanco", CraftingLevel::CP160).unwrap(),
+            parse_piece("Pies:Pesada:Divinidad:Blanco", CraftingLevel::CP160).unwrap()
+        ];
+        assert_eq!(amount(&material_cost(&pieces), Material::Part(PartMaterials::RubediteIngots)), 260);
+        assert_eq!(amount(&research_cost(&pieces), Material::ArmourTrait(ArmourTraitMaterials::Sapphire)), 2);
+    }
+
+    #[test]
+    fn reads_the_glyph_and_level_after_the_quality() {
+        let piece = parse_piece("Cabeza:Pesada:Divinidad:Amarilla:Glifo de salud:CP150", CraftingLevel::CP160).unwrap();
+        assert!(piece.has_enchantment());
+        assert!(piece.level() == CraftingLevel::ChampionPoints(150));
+        let piece = parse_piece("Anillo:Arcanidad:Amarilla", CraftingLevel::Level(30)).unwrap();
+        assert!(!piece.has_enchantment());
+        assert!(piece.level() == CraftingLevel::Level(30));
+    }
+
+    #[test]
+    fn explains_what_is_wrong_with_a_piece() {
+        let why = |spec| parse_piece(spec, CraftingLevel::CP160).err().unwrap();
+        assert!(why("Sombrero:Divinidad:Amarilla").starts_with("`Sombrero` no es una pieza conocida"));
+        assert_eq!(why("Cabeza"), "falta el peso de `Cabeza`");
+        assert!(why("Cabeza:Pesada:Divinidad").contains("necesita al menos rasgo y calidad"));
+        assert!(why("Cabeza:Pesada:Afilado:Amarilla").starts_with("`Afilado` no es válido como rasgo"));
+        assert!(why("Anillo:Arcanidad:Amarilla:Glifo de salud:Glifo de magia").contains("sobra"));
+    }
+
+    #[test]
+    fn round_trips_through_json() {
+        let piece = parse_piece("Cabeza:Pesada:Divinidad:Amarilla:Glifo de salud:CP150", CraftingLevel::CP160).unwrap();
+        assert_eq!(SetPiece::from_json(&piece.to_json()).unwrap().to_json(), piece.to_json());
+    }
+}
@@ -0,0 +1,658 @@
+//! The gear request conversation as a plain state machine. Every answer given in Discord moves the
+//! wizard to its next [`Step`], so a whole request can be stored between interactions, resumed after
+//! a restart and driven without Discord at all.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+use crate::build::BuildSpec;
+use crate::entities::{Gear, GearQuality};
+use crate::entities::armour::{Armour, ArmourEnchantments, ArmourTraits, ArmourWeights};
+use crate::entities::jewelry::{Jewelries, Jewelry, JewelryEnchantments, JewelryTraits};
+use crate::entities::level::{CraftingLevel, MaterialTier};
+use crate::entities::weapon::{Weapon, WeaponEnchantments, WeaponKind, WeaponTraits};
+use crate::piece::{parse_field, SetPiece};
+
+/// Research only consumes the trait material, so the cheapest level is always requested
+pub const RESEARCH_LEVEL: CraftingLevel = CraftingLevel::Level(1);
+/// Value of the quality option that keeps what a saved build already has
+pub const KEEP_QUALITY: &str = "keep";
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WizardMode {
+    /// Pieces of a set to be crafted
+    Set,
+    /// Pieces whose traits are to be researched
+    Research
+}
+
+/// Values asked for each piece
+#[derive(Clone, Copy, Ord, PartialOrd, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Field {
+    Trait,
+    Weight,
+    Quality,
+    Enchantment
+}
+
+/// Answer given by the user to the current step
+pub enum Answer {
+    /// Values picked in a select menu
+    Choice(Vec<String>),
+    Yes,
+    No,
+    /// Undoes the last answer
+    Back,
+    /// Leaves the current piece out, or the default of the current question
+    Skip,
+    /// Configures again the finished piece at that position
+    Edit(usize)
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub enum Step {
+    /// Items wanted from the set
+    Parts,
+    Tier,
+    Level(MaterialTier),
+    /// Whether the pieces carry glyphs
+    Enchantments,
+    /// Whether a one handed weapon is wanted twice for dual wielding
+    DualWield(WeaponKind),
+    Feat(Gear, Field),
+    /// Whether the value given to the first piece of a group is used for the rest of it
+    ApplyToRest(Gear, Field, String),
+    /// Saved build to order again, loaded by the bot through [`GearWizard::load_build`]
+    Preset,
+    /// Quality for every piece of a loaded build, or [`KEEP_QUALITY`]
+    OverrideQuality,
+    /// Whether the glyphs of a loaded build are kept
+    KeepGlyphs,
+    /// Summary waiting for the user to confirm, handled by the bot
+    Confirm
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+enum Task {
+    /// Starts an armour or jewelry group, forgetting the values applied to the previous one
+    Group,
+    DualWield(WeaponKind),
+    Piece { item: Gear, sample: bool },
+    ApplyToRest(Gear, Field, String),
+    /// Piece left half configured to edit another one
+    Resume(Draft)
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct Draft {
+    item: Gear,
+    /// First piece of a group, its values are offered for the rest of the group
+    sample: bool,
+    values: BTreeMap<Field, String>,
+    /// Position of the finished piece being edited
+    #[serde(default)]
+    index: Option<usize>,
+    /// Level of the piece being edited, builds can mix levels
+    #[serde(default)]
+    level: Option<CraftingLevel>
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct GearWizard {
+    pub mode: WizardMode,
+    pub set_name: String,
+    pub level: CraftingLevel,
+    pub with_enchantments: bool,
+    pub pieces: Vec<SetPiece>,
+    pub step: Step,
+    /// Increased on every answer, so answers to messages of earlier steps can be told apart
+    pub turn: u32,
+    tasks: VecDeque<Task>,
+    draft: Option<Draft>,
+    defaults: BTreeMap<Field, String>,
+    /// State before each answer, to go back through them
+    #[serde(default)]
+    history: Vec<GearWizard>
+}
+
+impl GearWizard {
+    fn new(mode: WizardMode, set_name: &str, level: CraftingLevel, step: Step) -> Self {
+        GearWizard {
+            mode,
+            set_name: set_name.to_string(),
+            level,
+            with_enchantments: false,
+            pieces: vec![],
+            step,
+            turn: 0,
+            tasks: VecDeque::new(),
+            draft: None,
+            defaults: BTreeMap::new(),
+            history: vec![]
+        }
+    }
+
+    pub fn set(set_name: &str) -> Self {
+        Self::new(WizardMode::Set, set_name, CraftingLevel::default(), Step::Parts)
+    }
+
+    pub fn research() -> Self {
+        Self::new(WizardMode::Research, "Investigar", RESEARCH_LEVEL, Step::Parts)
+    }
+
+    /// Starts straight at the confirmation of an already complete build
+    pub fn build(spec: BuildSpec) -> Self {
+        let mut wizard = Self::new(WizardMode::Set, "", CraftingLevel::default(), Step::Confirm);
+        wizard.use_build(spec);
+        wizard
+    }
+
+    /// Starts choosing one of the saved builds
+    pub fn preset() -> Self {
+        Self::new(WizardMode::Set, "", CraftingLevel::default(), Step::Preset)
+    }
+
+    /// Loads the saved build picked on [`Step::Preset`], asking next for quality changes
+    pub fn load_build(&mut self, spec: BuildSpec) {
+        self.history.push(self.snapshot());
+        self.use_build(spec);
+        self.step = Step::OverrideQuality;
+        self.turn += 1;
+    }
+
+    /// Takes the pieces of a build, edited pieces following its glyphs and keeping their own level
+    fn use_build(&mut self, spec: BuildSpec) {
+        self.set_name = spec.set_name;
+        self.level = spec.pieces.first().map(SetPiece::level).unwrap_or_default();
+        self.with_enchantments = spec.pieces.iter().any(SetPiece::has_enchantment);
+        self.pieces = spec.pieces;
+    }
+
+    pub fn can_go_back(&self) -> bool {
+        !self.history.is_empty()
+    }
+
+    pub fn can_skip(&self) -> bool {
+        !matches!(self.step, Step::Parts | Step::Preset | Step::Confirm)
+    }
+
+    /// Finished pieces can be edited while going through pieces and on the summary
+    pub fn can_edit(&self) -> bool {
+        !self.pieces.is_empty()
+            && matches!(self.step, Step::Feat(..) | Step::DualWield(_) | Step::ApplyToRest(..) | Step::Confirm)
+    }
+
+    fn snapshot(&self) -> GearWizard {
+        GearWizard { history: vec![], ..self.clone() }
+    }
+
+    /// Moves to the next step, the current one is kept when the answer does not fit it
+    pub fn answer(&mut self, answer: Answer) -> Result<(), String> {
+        if let Answer::Back = answer {
+            let mut previous = self.history.pop().ok_or("No hay ningún paso anterior")?;
+            previous.history = std::mem::take(&mut self.history);
+            previous.turn = self.turn + 1;
+            *self = previous;
+            return Ok(());
+        }
+
+        let snapshot = self.snapshot();
+        match (self.step.clone(), answer) {
+            (_, Answer::Edit(index)) => self.edit(index)?,
+            (Step::Parts | Step::Preset | Step::Confirm, Answer::Skip) => return Err("Este paso no se puede omitir".to_string()),
+            (Step::Tier | Step::Level(_), Answer::Skip) => {
+                self.level = CraftingLevel::default();
+                self.step = Step::Enchantments;
+            }
+            (Step::Feat(..), Answer::Skip) => {
+                // A skipped edit keeps the piece as it was
+                self.draft = None;
+                self.advance();
+            }
+            (Step::OverrideQuality, Answer::Skip) => return self.answer(Answer::Choice(vec![KEEP_QUALITY.to_string()])),
+            (_, Answer::Skip) => return self.answer(Answer::No),
+            (Step::Parts, Answer::Choice(values)) => {
+                let items = values.iter()
+                    .map(|v| Gear::from_str(v).map_err(|_| format!("`{}` no es una pieza conocida", v)))
+                    .collect::<Result<Vec<Gear>, String>>()?;
+                if items.is_empty() {
+                    return Err("Selecciona al menos una pieza".to_string());
+                }
+                self.plan(items);
+                match self.mode {
+                    WizardMode::Set => self.step = Step::Tier,
+                    WizardMode::Research => self.advance()
+                }
+            }
+            (Step::Tier, Answer::Choice(values)) => {
+                self.step = Step::Level(parse_field::<MaterialTier>(first(&values), "nivel")?);
+            }
+            (Step::Level(tier), Answer::Choice(values)) => {
+                let level = CraftingLevel::from_str(first(&values))
+                    .ok()
+                    .filter(|l| tier.levels().contains(l))
+                    .ok_or_else(|| format!("`{}` no es un nivel de {}", first(&values), tier))?;
+                self.level = level;
+                self.step = Step::Enchantments;
+            }
+            (Step::Enchantments, answer @ (Answer::Yes | Answer::No)) => {
+                self.with_enchantments = matches!(answer, Answer::Yes);
+                self.advance();
+            }
+            (Step::DualWield(weapon), answer @ (Answer::Yes | Answer::No)) => {
+                let item = Gear::Weapon(weapon);
+                self.tasks.push_front(Task::Piece { item: item.clone(), sample: false });
+                if let Answer::Yes = answer {
+                    self.tasks.push_front(Task::Piece { item, sample: false });
+                }
+                self.advance();
+            }
+            (Step::Feat(item, field), Answer::Choice(values)) => {
+                let value = first(&values);
+                check_field(&item, field, value)?;
+                if let Some(draft) = self.draft.as_mut() {
+                    draft.values.insert(field, value.to_string());
+                }
+                self.advance();
+            }
+            (Step::ApplyToRest(_, field, value), answer @ (Answer::Yes | Answer::No)) => {
+                if let Answer::Yes = answer {
+                    self.defaults.insert(field, value);
+                }
+                self.advance();
+            }
+            (Step::OverrideQuality, Answer::Choice(values)) => {
+                let value = first(&values);
+                if value != KEEP_QUALITY {
+                    let quality = parse_field::<GearQuality>(value, "calidad")?;
+                    self.pieces.iter_mut().for_each(|p| p.set_quality(quality.clone()));
+                }
+                self.step = if self.pieces.iter().any(SetPiece::has_enchantment) { Step::KeepGlyphs } else { Step::Confirm };
+            }
+            (Step::KeepGlyphs, answer @ (Answer::Yes | Answer::No)) => {
+                if let Answer::No = answer {
+                    self.pieces.iter_mut().for_each(SetPiece::clear_enchantment);
+                }
+                self.with_enchantments = self.pieces.iter().any(SetPiece::has_enchantment);
+                self.step = Step::Confirm;
+            }
+            _ => return Err("Esa respuesta no corresponde a este paso".to_string())
+        }
+        self.history.push(snapshot);
+        self.turn += 1;
+        Ok(())
+    }
+
+    /// Puts the current step back in the queue and asks again every value of a finished piece
+    fn edit(&mut self, index: usize) -> Result<(), String> {
+        if !self.can_edit() {
+            return Err("Ahora no se pueden editar piezas".to_string());
+        }
+        let piece = self.pieces.get(index).ok_or_else(|| format!("No hay una pieza {}", index + 1))?;
+        let (item, level) = (piece.item(), piece.level());
+        match self.step.clone() {
+            Step::Feat(..) => if let Some(draft) = self.draft.take() {
+                self.tasks.push_front(Task::Resume(draft));
+            },
+            Step::DualWield(weapon) => self.tasks.push_front(Task::DualWield(weapon)),
+            Step::ApplyToRest(item, field, value) => self.tasks.push_front(Task::ApplyToRest(item, field, value)),
+            _ => {}
+        }
+        self.draft = Some(Draft { item, sample: false, values: BTreeMap::new(), index: Some(index), level: Some(level) });
+        self.advance();
+        Ok(())
+    }
+
+    /// Queues the selected items, weapons first, then armour and jewelry each as a group
+    fn plan(&mut self, items: Vec<Gear>) {
+        let weapons: Vec<WeaponKind> = items.iter()
+            .filter_map(|g| if let Gear::Weapon(w) = g {Some(w.clone())} else {None})
+            .collect();
+        let armour: Vec<Gear> = items.iter()
+            .filter(|g| matches!(g, Gear::Armour(_)))
+            .cloned()
+            .collect();
+        let mut jewelry: Vec<Gear> = items.iter()
+            .filter(|g| matches!(g, Gear::Jewelry(_)))
+            .cloned()
+            .collect();
+
+        if self.mode == WizardMode::Research {
+            self.tasks = weapons.into_iter().map(Gear::Weapon)
+                .chain(armour)
+                .chain(jewelry)
+                .map(|item| Task::Piece { item, sample: false })
+                .collect();
+            return;
+        }
+
+        for weapon in weapons {
+            self.tasks.push_back(match weapon {
+                WeaponKind::OneHanded(_) => Task::DualWield(weapon),
+                WeaponKind::TwoHanded(_) => Task::Piece { item: Gear::Weapon(weapon), sample: false }
+            });
+        }
+        // Both rings are always crafted together
+        if jewelry.contains(&Gear::Jewelry(Jewelries::Ring)) {
+            jewelry.push(Gear::Jewelry(Jewelries::Ring));
+        }
+        for mut group in [armour, jewelry] {
+            if group.is_empty() {
+                continue;
+            }
+            self.tasks.push_back(Task::Group);
+            if group.len() > 1 {
+                let sample = group.pop().unwrap();
+                self.tasks.push_back(Task::Piece { item: sample, sample: true });
+            }
+            self.tasks.extend(group.into_iter().map(|item| Task::Piece { item, sample: false }));
+        }
+    }
+
+    /// Runs queued tasks until one needs an answer
+    fn advance(&mut self) {
+        loop {
+            if let Some(draft) = self.draft.take() {
+                if let Some(field) = self.fields(&draft).into_iter().find(|f| !draft.values.contains_key(f)) {
+                    self.step = Step::Feat(draft.item.clone(), field);
+                    self.draft = Some(draft);
+                    return;
+                }
+                if draft.sample {
+                    for field in rest_fields(&draft.item).into_iter().rev() {
+                        if let Some(value) = draft.values.get(&field) {
+                            self.tasks.push_front(Task::ApplyToRest(draft.item.clone(), field, value.clone()));
+                        }
+                    }
+                }
+                let index = draft.index;
+                let piece = self.finish(draft);
+                match index {
+                    Some(index) => {
+                        self.pieces[index] = piece;
+                        // Later edits ask for glyphs only while the build still has some
+                        self.with_enchantments = self.pieces.iter().any(SetPiece::has_enchantment);
+                    }
+                    None => self.pieces.push(piece)
+                }
+                continue;
+            }
+
+            match self.tasks.pop_front() {
+                None => {
+                    self.step = Step::Confirm;
+                    return;
+                }
+                Some(Task::Group) => self.defaults.clear(),
+                Some(Task::DualWield(weapon)) => {
+                    self.step = Step::DualWield(weapon);
+                    return;
+                }
+                Some(Task::Piece { item, sample }) => {
+                    let values = if sample { BTreeMap::new() } else { self.defaults.clone() };
+                    self.draft = Some(Draft { item, sample, values, index: None, level: None });
+                }
+                Some(Task::Resume(draft)) => self.draft = Some(draft),
+                Some(Task::ApplyToRest(item, field, value)) => {
+                    self.step = Step::ApplyToRest(item, field, value);
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Values asked for a piece, in the order they are asked
+    fn fields(&self, draft: &Draft) -> Vec<Field> {
+        let mut fields = match (self.mode, &draft.item) {
+            (WizardMode::Research, Gear::Armour(_)) => return vec![Field::Trait, Field::Weight],
+            (WizardMode::Research, _) => return vec![Field::Trait],
+            (WizardMode::Set, Gear::Weapon(_)) => vec![Field::Trait, Field::Quality],
+            (WizardMode::Set, Gear::Armour(_)) if draft.sample => vec![Field::Trait, Field::Quality, Field::Weight],
+            (WizardMode::Set, Gear::Armour(_)) => vec![Field::Trait, Field::Weight, Field::Quality],
+            (WizardMode::Set, Gear::Jewelry(_)) => vec![Field::Trait, Field::Quality]
+        };
+        if self.with_enchantments {
+            match draft.item {
+                // Weapons are enchanted before choosing their quality
+                Gear::Weapon(_) => fields.insert(1, Field::Enchantment),
+                _ => fields.push(Field::Enchantment)
+            }
+        }
+        fields
+    }
+
+    fn finish(&self, draft: Draft) -> SetPiece {
+        let value = |field: Field| draft.values.get(&field).map(String::as_str).unwrap_or_default();
+        let quality = match self.mode {
+            WizardMode::Set => GearQuality::from_str(value(Field::Quality)).unwrap(),
+            WizardMode::Research => GearQuality::White
+        };
+        let level = draft.level.unwrap_or(self.level);
+        // Every value was checked against its item when answered
+        match draft.item {
+            Gear::Weapon(kind) => SetPiece::Weapon(Weapon {
+                kind,
+                weapon_trait: WeaponTraits::from_str(value(Field::Trait)).unwrap(),
+                enchantment: WeaponEnchantments::from_str(value(Field::Enchantment)).ok(),
+                quality,
+                level
+            }),
+            Gear::Armour(kind) => SetPiece::Armour(Armour {
+                kind,
+                armour_trait: ArmourTraits::from_str(value(Field::Trait)).unwrap(),
+                enchantment: ArmourEnchantments::from_str(value(Field::Enchantment)).ok(),
+                weight: ArmourWeights::from_str(value(Field::Weight)).unwrap(),
+                quality,
+                level
+            }),
+            Gear::Jewelry(kind) => SetPiece::Jewelry(Jewelry {
+                kind,
+                jewelry_trait: JewelryTraits::from_str(value(Field::Trait)).unwrap(),
+                enchantment: JewelryEnchantments::from_str(value(Field::Enchantment)).ok(),
+                quality,
+                level
+            })
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("wizards always serialize")
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        serde_json::from_str(text).map_err(|e| e.to_string())
+    }
+}
+
+/// Values of the first piece of a group offered for the rest, in the order they are offered
+fn rest_fields(item: &Gear) -> Vec<Field> {
+    match item {
+        Gear::Armour(_) => vec![Field::Trait, Field::Enchantment, Field::Weight, Field::Quality],
+        _ => vec![Field::Trait, Field::Enchantment, Field::Quality]
+    }
+}
+
+fn check_field(item: &Gear, field: Field, value: &str) -> Result<(), String> {
+    match (field, item) {
+        (Field::Trait, Gear::Weapon(_)) => parse_field::<WeaponTraits>(value, "rasgo").map(drop),
+        (Field::Trait, Gear::Armour(_)) => parse_field::<ArmourTraits>(value, "rasgo").map(drop),
+        (Field::Trait, Gear::Jewelry(_)) => parse_field::<JewelryTraits>(value, "rasgo").map(drop),
+        (Field::Enchantment, Gear::Weapon(_)) => parse_field::<WeaponEnchantments>(value, "glifo").map(drop),
+        (Field::Enchantment, Gear::Armour(_)) => parse_field::<ArmourEnchantments>(value, "glifo").map(drop),
+        (Field::Enchantment, Gear::Jewelry(_)) => parse_field::<JewelryEnchantments>(value, "glifo").map(drop),
+        (Field::Weight, Gear::Armour(_)) => parse_field::<ArmourWeights>(value, "peso").map(drop),
+        (Field::Weight, _) => Err("Solo las armaduras tienen peso".to_string()),
+        (Field::Quality, _) => parse_field::<GearQuality>(value, "calidad").map(drop)
+    }
+}
+
+fn first(values: &[String]) -> &str {
+    values.first().map(String::as_str).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::armour::ArmourParts;
+    use crate::entities::weapon::OneHandedWeapons;
+
+    fn choose(wizard: &mut GearWizard, values: &[&str]) {
+        wizard.answer(Answer::Choice(values.iter().map(|v| v.to_string()).collect())).unwrap();
+    }
+
+    /// A set wizard for the items, with a CP150 level and no glyphs
+    fn set_wizard(items: &[&str]) -> GearWizard {
+        let mut wizard = GearWizard::set("Cólera de la orden");
+        choose(&mut wizard, items);
+        assert!(matches!(wizard.step, Step::Tier));
+        choose(&mut wizard, &["CP150-CP160"]);
+        assert!(matches!(wizard.step, Step::Level(MaterialTier::Rubedite)));
+        choose(&mut wizard, &["CP150"]);
+        assert!(matches!(wizard.step, Step::Enchantments));
+        wizard.answer(Answer::No).unwrap();
+        wizard
+    }
+
+    #[test]
+    fn asks_tier_level_and_glyphs_before_the_pieces() {
+        let wizard = set_wizard(&["Mandoble"]);
+        assert!(wizard.level == CraftingLevel::ChampionPoints(150));
+        assert!(!wizard.with_enchantments);
+        assert!(matches!(wizard.step, Step::Feat(Gear::Weapon(WeaponKind::TwoHanded(_)), Field::Trait)));
+    }
+
+    #[test]
+    fn rejects_a_level_outside_the_tier() {
+        let mut wizard = GearWizard::set("Cólera de la orden");
+        choose(&mut wizard, &["Mandoble"]);
+        choose(&mut wizard, &["CP150-CP160"]);
+        assert!(wizard.answer(Answer::Choice(vec!["Nivel 30".to_string()])).is_err());
+        assert!(matches!(wizard.step, Step::Level(MaterialTier::Rubedite)));
+    }
+
+    #[test]
+    fn dual_wield_asks_for_the_weapon_twice() {
+        for (answer, swords) in [(Answer::Yes, 2), (Answer::No, 1)] {
+            let mut wizard = set_wizard(&["Espada"]);
+            assert!(matches!(wizard.step, Step::DualWield(WeaponKind::OneHanded(OneHandedWeapons::Sword))));
+            wizard.answer(answer).unwrap();
+            for _ in 0..swords {
+                choose(&mut wizard, &["Precisión"]);
+                choose(&mut wizard, &["Amarilla"]);
+            }
+            assert!(matches!(wizard.step, Step::Confirm));
+            assert_eq!(wizard.pieces.len(), swords);
+        }
+    }
+
+    #[test]
+    fn applies_the_first_armour_piece_to_the_rest() {
+        let mut wizard = set_wizard(&["Cabeza", "Manos", "Pies"]);
+        choose(&mut wizard, &["Divinidad"]);
+        choose(&mut wizard, &["Amarilla"]);
+        choose(&mut wizard, &["Pesada"]);
+        for field in [Field::Trait, Field::Weight, Field::Quality] {
+            assert!(matches!(&wizard.step, Step::ApplyToRest(Gear::Armour(_), f, _) if *f == field));
+            wizard.answer(Answer::Yes).unwrap();
+        }
+        assert!(matches!(wizard.step, Step::Confirm));
+        assert_eq!(wizard.pieces.len(), 3);
+        for piece in &wizard.pieces {
+            let SetPiece::Armour(armour) = piece else { panic!("only armour was asked for") };
+            assert_eq!(
+                (armour.armour_trait.to_string(), armour.weight.to_string(), armour.quality.to_string()),
+                ("Divinidad".to_string(), "Pesada".to_string(), "Amarilla".to_string())
+            );
+        }
+    }
+
+    #[test]
+    fn asks_every_armour_value_when_not_applied_to_the_rest() {
+        let mut wizard = set_wizard(&["Cabeza", "Manos"]);
+        choose(&mut wizard, &["Divinidad"]);
+        choose(&mut wizard, &["Amarilla"]);
+        choose(&mut wizard, &["Pesada"]);
+        for _ in 0..3 {
+            wizard.answer(Answer::No).unwrap();
+        }
+        assert!(matches!(wizard.step, Step::Feat(Gear::Armour(ArmourParts::Head), Field::Trait)));
+    }
+
+    #[test]
+    fn rings_are_crafted_in_pairs_sharing_their_values() {
+        let mut wizard = set_wizard(&["Anillo"]);
+        choose(&mut wizard, &["Arcanidad"]);
+        choose(&mut wizard, &["Amarilla"]);
+        for field in [Field::Trait, Field::Quality] {
+            assert!(matches!(&wizard.step, Step::ApplyToRest(Gear::Jewelry(Jewelries::Ring), f, _) if *f == field));
+            wizard.answer(Answer::Yes).unwrap();
+        }
+        assert!(matches!(wizard.step, Step::Confirm));
+        assert_eq!(wizard.pieces.len(), 2);
+        assert_eq!(wizard.pieces[0].to_json(), wizard.pieces[1].to_json());
+    }
+
+    #[test]
+    fn research_asks_only_traits_and_armour_weight() {
+        let mut wizard = GearWizard::research();
+        choose(&mut wizard, &["Cabeza", "Espada"]);
+        assert!(matches!(wizard.step, Step::Feat(Gear::Weapon(_), Field::Trait)));
+        choose(&mut wizard, &["Precisión"]);
+        assert!(matches!(wizard.step, Step::Feat(Gear::Armour(_), Field::Trait)));
+        choose(&mut wizard, &["Divinidad"]);
+        choose(&mut wizard, &["Ligera"]);
+        assert!(matches!(wizard.step, Step::Confirm));
+        assert!(wizard.pieces.iter().all(|p| p.level() == RESEARCH_LEVEL));
+    }
+
+    #[test]
+    fn resumes_from_json_in_the_middle_of_a_request() {
+        let mut wizard = set_wizard(&["Cabeza", "Manos"]);
+        choose(&mut wizard, &["Divinidad"]);
+        let mut resumed = GearWizard::from_json(&wizard.to_json()).unwrap();
+        assert_eq!(resumed.turn, wizard.turn);
+        assert!(matches!(resumed.step, Step::Feat(Gear::Armour(ArmourParts::Hands), Field::Quality)));
+
+        resumed.answer(Answer::Back).unwrap();
+        assert!(matches!(resumed.step, Step::Feat(Gear::Armour(ArmourParts::Hands), Field::Trait)));
+        choose(&mut resumed, &["Divinidad"]);
+        choose(&mut resumed, &["Amarilla"]);
+        choose(&mut resumed, &["Pesada"]);
+        for _ in 0..3 {
+            resumed.answer(Answer::Yes).unwrap();
+        }
+        assert!(matches!(resumed.step, Step::Confirm));
+        assert_eq!(resumed.pieces.len(), 2);
+    }
+
+    #[test]
+    fn editing_a_piece_keeps_its_own_level() {
+        let spec = BuildSpec::from_lines("Set: Cólera de la orden\nAnillo:Arcanidad:Amarilla:CP150\nCollar:Arcanidad:Amarilla:Nivel 30").unwrap();
+        let mut wizard = GearWizard::build(spec);
+        wizard.answer(Answer::Edit(1)).unwrap();
+        assert!(matches!(wizard.step, Step::Feat(Gear::Jewelry(Jewelries::Necklace), Field::Trait)));
+        choose(&mut wizard, &["Sed de sangre"]);
+        choose(&mut wizard, &["Morada"]);
+        assert!(matches!(wizard.step, Step::Confirm));
+        assert!(wizard.pieces[1].level() == CraftingLevel::Level(30));
+        assert!(wizard.pieces[0].level() == CraftingLevel::ChampionPoints(150));
+    }
+
+    #[test]
+    fn edits_ask_for_glyphs_only_while_the_build_has_some() {
+        let spec = BuildSpec::from_lines("Set: Cólera de la orden\nAnillo:Arcanidad:Amarilla:Glifo de regeneración de magia\nCollar:Arcanidad:Amarilla:Glifo de regeneración de magia").unwrap();
+        let mut wizard = GearWizard::preset();
+        wizard.load_build(spec);
+        assert!(wizard.with_enchantments);
+        choose(&mut wizard, &[KEEP_QUALITY]);
+        assert!(matches!(wizard.step, Step::KeepGlyphs));
+        wizard.answer(Answer::No).unwrap();
+        assert!(!wizard.with_enchantments);
+
+        wizard.answer(Answer::Edit(1)).unwrap();
+        choose(&mut wizard, &["Sed de sangre"]);
+        choose(&mut wizard, &["Morada"]);
+        assert!(matches!(wizard.step, Step::Confirm));
+        assert!(!wizard.pieces[1].has_enchantment());
+    }
+}
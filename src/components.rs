@@ -1,73 +1,88 @@
 pub mod traits;
 pub mod enchantments;
 
-use std::collections::HashMap;
 use std::fmt::Display;
 use serenity::builder::{CreateActionRow, CreateEmbed, CreateInputText, CreateSelectMenu};
 use strum::{EnumMessage, EnumProperty, IntoEnumIterator};
-use crate::entities::armour::{Armour, ArmourParts, ArmourWeights};
-use crate::entities::{GearQuality, MaterialCost};
-use crate::entities::jewelry::{Jewelries, Jewelry};
-use crate::entities::weapon::{OneHandedWeapons, TwoHandedWeapons, Weapon};
+use seht_core::entities::armour::{ArmourParts, ArmourWeights};
+use seht_core::entities::{GearQuality, MaterialCost};
+use seht_core::entities::consumables::{self, ConsumableKind, ConsumableOrder, Recipe};
+use seht_core::entities::glyph::{Enchantment, EnchantmentKind, GlyphOrder};
+use seht_core::entities::materials::MaterialBill;
+use seht_core::entities::jewelry::Jewelries;
+use seht_core::entities::level::{CraftingLevel, MaterialTier};
+use seht_core::entities::research;
+use seht_core::entities::research::{KnownTrait, ResearchItem};
+use seht_core::entities::sets;
+use seht_core::entities::sets::CraftableSet;
+use seht_core::entities::weapon::{OneHandedWeapons, TwoHandedWeapons};
+use seht_core::piece::{self, SetPiece};
+use seht_core::wizard::KEEP_QUALITY;
+use crate::store::{GuildSettings, Language, Order, OrderAction, OrderKind, OrderStatus, Preset};
 use std::string::ToString;
-use serenity::all::{ButtonStyle, CreateButton, CreateSelectMenuKind, CreateSelectMenuOption, EmojiId, InputTextStyle, Mention, ReactionType, RoleId};
+use serenity::all::{ButtonStyle, CreateButton, CreateSelectMenuKind, CreateSelectMenuOption, EmojiId, InputTextStyle, Mention, ReactionType, RoleId, UserId};
 
-pub enum SetPiece {
-    Weapon(Weapon),
-    Armour(Armour),
-    Jewelry(Jewelry)
+pub fn display_research_cost(pieces: &[SetPiece]) -> CreateEmbed {
+    display_material_cost(piece::research_cost(pieces), "Lista de los materiales necesarios para este equipo")
 }
 
-impl MaterialCost for SetPiece {
-    fn cost(&self) -> Vec<(i32, String)> {
-        match self {
-            SetPiece::Weapon(w) => w.cost(),
-            SetPiece::Armour(a) => a.cost(),
-            SetPiece::Jewelry(j) => j.cost()
-        }
-    }
+pub fn display_cost(pieces: &[SetPiece]) -> CreateEmbed {
+    display_material_cost(piece::material_cost(pieces), "Lista de los materiales necesarios para este equipo")
 }
 
-trait ResearchCost {
-    fn research_cost(&self) -> Vec<(i32, String)>;
+pub fn display_glyphs_cost(glyphs: &[GlyphOrder]) -> CreateEmbed {
+    let bill = glyphs.iter().map(|g| g.cost()).collect();
+    display_material_cost(bill, "Runas necesarias para estos glifos")
 }
 
-impl ResearchCost for SetPiece {
-    fn research_cost(&self) -> Vec<(i32, String)> {
-        match self {
-            SetPiece::Weapon(w) => w.weapon_trait.cost(),
-            SetPiece::Armour(a) => a.armour_trait.cost(),
-            SetPiece::Jewelry(j) => j.jewelry_trait.cost()
-        }
-    }
+pub fn display_consumables_cost(items: &[ConsumableOrder]) -> CreateEmbed {
+    let bill = items.iter().map(|i| i.cost()).collect();
+    display_material_cost(bill, "Ingredientes necesarios para estos consumibles, por tandas completas")
 }
 
-pub fn display_research_cost(pieces: &Vec<SetPiece>) -> CreateEmbed {
-    display_material_cost(pieces.iter()
-        .map(|p| p.research_cost())
-        .flatten().collect())
+/// Lines of a free text order that could not be read, for the crafter to check by hand
+pub fn unknown_lines_embed(unknown: &[String]) -> CreateEmbed {
+    CreateEmbed::new()
+        .title("⚠️ Sin reconocer")
+        .description(format!("No se han incluido en los materiales:\n{}", unknown.iter()
+            .map(|line| format!("- {}", line))
+            .collect::<Vec<_>>()
+            .join("\n")))
 }
 
-pub fn display_cost(pieces: &Vec<SetPiece>) -> CreateEmbed {
-    display_material_cost(pieces.iter()
-        .map(|p| p.cost())
-        .flatten().collect())
+/// Reagent mix and crafts for each potion or poison, none when there are no alchemy items
+pub fn alchemy_recipe_embed(items: &[ConsumableOrder]) -> Option<CreateEmbed> {
+    let recipes: Vec<_> = items.iter()
+        .filter_map(|i| i.item.alchemy().map(|recipe| (i, recipe)))
+        .collect();
+    if recipes.is_empty() {
+        return None
+    }
+
+    let mut b = CreateEmbed::new()
+        .title("Receta sugerida")
+        .description("Reactivos y disolvente de nivel CP150 para cada poción o veneno");
+    for (order, recipe) in recipes {
+        let effects = recipe.effects.iter().map(|e| e.to_string()).collect::<Vec<_>>().join(", ");
+        b = b.field(order.item.name_es,
+            format!("{}
+*{}*
+{} tandas de {}", recipe, effects, order.crafts(), order.item.kind.batch()),
+            false);
+    }
+    Some(b)
 }
 
-fn display_material_cost(cost: Vec<(i32, String)>) -> CreateEmbed {
+fn display_material_cost(bill: MaterialBill, description: &str) -> CreateEmbed {
     let mut b = CreateEmbed::new()
         .title("Materiales")
-        .description("Lista de los materiales necesarios para este equipo");
+        .description(description);
 
-    let mut costs: HashMap<String, i32> = HashMap::new();
-    for (amount, material) in cost {
-        if let Some(old_amount) = costs.insert(material.clone(), amount) {
-            costs.insert(material.clone(), amount + old_amount);
-        }
+    if bill.is_empty() {
+        b = b.field("Sin materiales", "", false);
     }
-
-    for (material, amount) in costs {
-        b = b.field(material, amount.to_string(), true);
+    for (material, amount) in bill.iter() {
+        b = b.field(material.to_string(), amount.to_string(), true);
     }
     b
 }
@@ -115,13 +130,230 @@ pub fn gear_quality(name: &str) -> CreateSelectMenu {
         .placeholder("Selecciona la calidad de la pieza")
 }
 
+pub fn preset_select(name: &str, presets: &[Preset]) -> CreateSelectMenu {
+    // Discord select menus hold at most 25 options
+    let options = CreateSelectMenuKind::String {
+        options: presets.iter()
+            .take(25)
+            .map(|p| CreateSelectMenuOption::new(&p.name, &p.name)
+                .description(format!("{} ({} piezas)", p.spec.set_name, p.spec.pieces.len())))
+            .collect()
+    };
+
+    CreateSelectMenu::new(name, options)
+        .placeholder("Selecciona la build que quieres volver a pedir")
+}
+
+/// Quality menu with an extra first option to keep the qualities saved in the build
+pub fn preset_quality(name: &str) -> CreateSelectMenu {
+    let mut options = vec![CreateSelectMenuOption::new("Mantener calidad", KEEP_QUALITY)
+        .emoji(ReactionType::Unicode("💾".to_string()))];
+    options.extend(GearQuality::iter()
+        .map(|opt| CreateSelectMenuOption::new(opt.to_string(), opt.to_string())
+            .emoji(ReactionType::Unicode(opt.get_str("Emoji").unwrap().to_string()))));
+
+    CreateSelectMenu::new(name, CreateSelectMenuKind::String { options })
+        .placeholder("Selecciona la calidad de las piezas")
+}
+
+pub fn consumable_kind(name: &str) -> CreateSelectMenu {
+    let options = CreateSelectMenuKind::String {
+        options: ConsumableKind::iter()
+            .map(|kind| CreateSelectMenuOption::new(kind.to_string(), kind.to_string())
+                .description(kind.get_documentation().unwrap())
+                .emoji(ReactionType::Unicode(kind.get_str("Emoji").unwrap().to_string())))
+            .collect()
+    };
+
+    CreateSelectMenu::new(name, options)
+        .placeholder("Selecciona el tipo de consumible")
+}
+
+pub fn consumable_select(name: &str, kind: ConsumableKind) -> CreateSelectMenu {
+    let items: Vec<_> = consumables::consumables_of(kind).collect();
+    // Each picked item gets its own quantity field and modals hold at most five
+    let max = items.len().min(5) as u8;
+    let options = CreateSelectMenuKind::String {
+        options: items.into_iter()
+            .map(|(idx, item)| {
+                let option = CreateSelectMenuOption::new(item.name_es, idx.to_string())
+                    .description(item.effects);
+                match &item.recipe {
+                    Recipe::Provisioning { quality, .. } => option.emoji(ReactionType::Unicode(quality.get_str("Emoji").unwrap().to_string())),
+                    Recipe::Alchemy(_) => option
+                }
+            })
+            .collect()
+    };
+
+    CreateSelectMenu::new(name, options)
+        .placeholder(format!("Selecciona hasta {} consumibles", max))
+        .max_values(max)
+}
+
+pub fn enchantment_kind(name: &str) -> CreateSelectMenu {
+    let options = CreateSelectMenuKind::String {
+        options: EnchantmentKind::iter()
+            .map(|kind| CreateSelectMenuOption::new(kind.to_string(), kind.to_string())
+                .description(kind.get_documentation().unwrap())
+                .emoji(ReactionType::Unicode(kind.get_str("Emoji").unwrap().to_string())))
+            .collect()
+    };
+
+    CreateSelectMenu::new(name, options)
+        .placeholder("Selecciona dónde va el glifo")
+}
+
+pub fn glyph_quality(name: &str) -> CreateSelectMenu {
+    let options = CreateSelectMenuKind::String {
+        options: GearQuality::iter()
+            .map(|opt| CreateSelectMenuOption::new(opt.to_string(), opt.to_string())
+                .description(format!("Runa de aspecto {}", opt.aspect_rune()))
+                .emoji(ReactionType::Unicode(opt.get_str("Emoji").unwrap().to_string())))
+            .collect()
+    };
+
+    CreateSelectMenu::new(name, options)
+        .placeholder("Selecciona la calidad de los glifos")
+}
+
+pub fn glyph_select(name: &str, kind: EnchantmentKind) -> CreateSelectMenu {
+    let glyphs: Vec<_> = Enchantment::all().into_iter()
+        .enumerate()
+        .filter(|(_, e)| e.kind() == kind)
+        .collect();
+    // Each picked glyph gets its own quantity field and modals hold at most five
+    let max = glyphs.len().min(5) as u8;
+    let options = CreateSelectMenuKind::String {
+        options: glyphs.into_iter()
+            .map(|(idx, e)| CreateSelectMenuOption::new(e.to_string(), idx.to_string())
+                .description(e.description()))
+            .collect()
+    };
+
+    CreateSelectMenu::new(name, options)
+        .placeholder(format!("Selecciona hasta {} glifos", max))
+        .max_values(max)
+}
+
+pub fn free_text_button(name: &str) -> CreateButton {
+    CreateButton::new(name)
+        .emoji(ReactionType::Unicode("✍️".to_string()))
+        .label("Escribir a mano")
+        .style(ButtonStyle::Secondary)
+}
+
+pub fn crafting_tier(name: &str) -> CreateSelectMenu {
+    get_enum_as_menu::<MaterialTier>(name, "Selecciona el nivel del equipo")
+}
+
+pub fn crafting_level(name: &str, levels: &[CraftingLevel]) -> CreateSelectMenu {
+    let options = CreateSelectMenuKind::String {
+        options: levels.iter()
+            .map(|l| CreateSelectMenuOption::new(l.to_string(), l.to_string()))
+            .collect()
+    };
+
+    CreateSelectMenu::new(name, options)
+        .placeholder("Selecciona el nivel exacto del equipo")
+}
+
 pub fn gear_set_embed(set: &str) -> CreateEmbed {
     CreateEmbed::new()
         .title(format!("🛡️ {} 🛡️", set))
         .description("Configura el equipo que deseas con las opciones")
 }
 
-pub fn gear_research_piece_embed(pieces: &Vec<SetPiece>) -> CreateEmbed {
+pub fn craftable_set_embed(set: &CraftableSet) -> CreateEmbed {
+    let b = CreateEmbed::new()
+        .title(format!("🛡️ {} 🛡️", set.name_es))
+        .description(set.name_en)
+        .color((127,255,0))
+        .field(":hourglass: Rasgos necesarios", set.traits.to_string(), true)
+        .field(":map: Estación", set.location, true);
+    if set.bonuses.is_empty() {
+        return b
+    }
+    b.field("Bonificaciones", set.bonuses.join("\n"), false)
+}
+
+pub fn set_traits_warning(set: &CraftableSet) -> String {
+    format!(
+        "⚠️ **{}** necesita **{} rasgos** investigados en cada pieza, solo podrán reclamar la solicitud los crafteadores que los tengan",
+        set.name_es, set.traits)
+}
+
+pub fn research_embed(crafter: UserId, known: &[KnownTrait]) -> CreateEmbed {
+    let mut b = CreateEmbed::new()
+        .title("🔬 Investigación 🔬")
+        .color((127,255,0))
+        .field("Crafteador", Mention::User(crafter).to_string(), false);
+
+    if known.is_empty() {
+        b = b.field("Rasgos", "Sin rasgos registrados", false);
+    }
+    // Armour goes in one field per weight, as an embed holds at most 25 fields
+    let mut weights: Vec<(ArmourWeights, Vec<String>)> = vec![];
+    for item in research::research_items() {
+        let traits: Vec<String> = known.iter()
+            .filter(|k| k.item == item)
+            .map(|k| k.gear_trait.to_string())
+            .collect();
+        if traits.is_empty() {
+            continue;
+        }
+        let total = research::research_traits(&item).len();
+        let listed = if traits.len() == total { "todos".to_string() } else { traits.join(", ") };
+        match &item {
+            ResearchItem::Armour(part, Some(weight)) => {
+                let line = format!("**{}** ({}/{}): {}", part, traits.len(), total, listed);
+                match weights.iter_mut().find(|(w, _)| w == weight) {
+                    Some((_, lines)) => lines.push(line),
+                    None => weights.push((weight.clone(), vec![line]))
+                }
+            }
+            _ => b = b.field(format!("{} ({}/{})", item, traits.len(), total), listed, true)
+        }
+    }
+    for (weight, lines) in weights {
+        b = b.field(format!("Armadura {}", weight), lines.join("\n"), false);
+    }
+    b
+}
+
+/// Explains what the crafter is missing to make the pieces, `None` when they can craft all of them
+pub fn research_gap(known: &[KnownTrait], needed: &[KnownTrait], set: Option<&CraftableSet>) -> Option<String> {
+    let mut gaps: Vec<String> = research::missing_traits(known, needed).iter()
+        .map(|k| format!("- {} sin investigar en {}", k.gear_trait, k.item))
+        .collect();
+    if let Some(set) = set {
+        gaps.extend(research::items_below(known, needed, set.traits).iter()
+            .map(|item| format!("- {} necesita {} rasgos investigados en {}", set.name_es, set.traits, item)));
+    }
+
+    if gaps.is_empty() { None } else { Some(gaps.join("\n")) }
+}
+
+pub fn capable_crafters_message(crafters: &[UserId]) -> String {
+    if crafters.is_empty() {
+        return String::new();
+    }
+    let mentions: Vec<String> = crafters.iter().map(|c| Mention::User(*c).to_string()).collect();
+    format!("Tienen todos los rasgos investigados: {}\n", mentions.join(" "))
+}
+
+pub fn unknown_set_message(name: &str, suggestions: &[&CraftableSet]) -> String {
+    let mut content = format!("No encuentro **{}** entre los sets crafteables, revisa el nombre y vuelve a empezar", name);
+    if !suggestions.is_empty() {
+        let names: Vec<String> = suggestions.iter()
+            .map(|s| format!("- {} ({})", s.name_es, s.name_en))
+            .collect();
+        content = format!("{}\n\nQuizás buscabas:\n{}", content, names.join("\n"));
+    }
+    content
+}
+
+pub fn gear_research_piece_embed(pieces: &[SetPiece]) -> CreateEmbed {
     let mut b = CreateEmbed::new()
         .title("🛠️ Investigación 🛠️️")
         .color((127,255,0));
@@ -129,19 +361,19 @@ pub fn gear_research_piece_embed(pieces: &Vec<SetPiece>) -> CreateEmbed {
     for piece in pieces {
         match piece {
             SetPiece::Weapon(w) => {
-                b = b.field(&w.kind.to_string(), "", false);
-                b = b.field(":hourglass: Rasgo", &w.weapon_trait.to_string(), true);
+                b = b.field(w.kind.to_string(), "", false);
+                b = b.field(":hourglass: Rasgo", w.weapon_trait.to_string(), true);
             }
             SetPiece::Armour(a) => {
-                b = b.field(&a.kind.to_string(), "", false);
+                b = b.field(a.kind.to_string(), "", false);
                 b = b.color((127,255,0));
-                b = b.field(":lifter: Peso", &a.weight.to_string(), true);
-                b = b.field(":hourglass: Rasgo", &a.armour_trait.to_string(), true);
+                b = b.field(":lifter: Peso", a.weight.to_string(), true);
+                b = b.field(":hourglass: Rasgo", a.armour_trait.to_string(), true);
             }
             SetPiece::Jewelry(j) => {
-                b = b.field(&j.kind.to_string(), "", false);
+                b = b.field(j.kind.to_string(), "", false);
                 b = b.color((127,255,0));
-                b = b.field(":hourglass: Rasgo", &j.jewelry_trait.to_string(), true);
+                b = b.field(":hourglass: Rasgo", j.jewelry_trait.to_string(), true);
             }
         }
     }
@@ -149,36 +381,43 @@ pub fn gear_research_piece_embed(pieces: &Vec<SetPiece>) -> CreateEmbed {
 }
 
 
-pub fn gear_set_piece_embed(set: &str, pieces: &Vec<SetPiece>) -> CreateEmbed {
+pub fn gear_set_piece_embed(set: &str, pieces: &[SetPiece]) -> CreateEmbed {
     let mut b = CreateEmbed::new()
         .title(format!("🛠️ {} 🛠️️", set))
         .color((127,255,0));
 
+    if let Some(piece) = pieces.first() {
+        b = b.field(":bar_chart: Nivel", piece.level().to_string(), false);
+    }
+    if let Some(craftable) = sets::find_set(set) {
+        b = b.field(":hourglass: Rasgos necesarios", format!("{} rasgos investigados por pieza", craftable.traits), false);
+    }
+
     for piece in pieces {
         match piece {
             SetPiece::Weapon(w) => {
-                b = b.field(&w.kind.to_string(), "", false);
-                b = b.field(":hourglass: Rasgo", &w.weapon_trait.to_string(), true);
-                b = b.field(":gem: Calidad", &w.quality.to_string(), true);
+                b = b.field(w.kind.to_string(), "", false);
+                b = b.field(":hourglass: Rasgo", w.weapon_trait.to_string(), true);
+                b = b.field(":gem: Calidad", w.quality.to_string(), true);
                 if let Some(enchantment) = &w.enchantment {
                     b = b.field(":magic_wand: Encantamiento", enchantment.to_string(), false);
                 }
             }
             SetPiece::Armour(a) => {
-                b = b.field(&a.kind.to_string(), "", false);
+                b = b.field(a.kind.to_string(), "", false);
                 b = b.color((127,255,0));
-                b = b.field(":lifter: Peso", &a.weight.to_string(), true);
-                b = b.field(":hourglass: Rasgo", &a.armour_trait.to_string(), true);
-                b = b.field(":gem: Calidad", &a.quality.to_string(), true);
+                b = b.field(":lifter: Peso", a.weight.to_string(), true);
+                b = b.field(":hourglass: Rasgo", a.armour_trait.to_string(), true);
+                b = b.field(":gem: Calidad", a.quality.to_string(), true);
                 if let Some(enchantment) = &a.enchantment {
                     b = b.field(":magic_wand: Encantamiento", enchantment.to_string(), false);
                 }
             }
             SetPiece::Jewelry(j) => {
-                b = b.field(&j.kind.to_string(), "", false);
+                b = b.field(j.kind.to_string(), "", false);
                 b = b.color((127,255,0));
-                b = b.field(":hourglass: Rasgo", &j.jewelry_trait.to_string(), true);
-                b = b.field(":gem: Calidad", &j.quality.to_string(), true);
+                b = b.field(":hourglass: Rasgo", j.jewelry_trait.to_string(), true);
+                b = b.field(":gem: Calidad", j.quality.to_string(), true);
                 if let Some(enchantment) = &j.enchantment {
                     b = b.field(":magic_wand: Encantamiento", enchantment.to_string(), false);
                 }
@@ -193,25 +432,25 @@ pub fn gear_piece_embed(part: &SetPiece) -> CreateEmbed {
     match part {
         SetPiece::Weapon(w) => {
             b = b.title(format!("🛠️ {} 🛠️️", &w.kind.to_string()));
-            b = b.field("Rasgo", &w.weapon_trait.to_string(), true);
-            b = b.field("Calidad", &w.quality.to_string(), true);
+            b = b.field("Rasgo", w.weapon_trait.to_string(), true);
+            b = b.field("Calidad", w.quality.to_string(), true);
             if let Some(enchantment) = &w.enchantment {
                 b = b.field("Encantamiento", enchantment.to_string(), true);
             }
         }
         SetPiece::Armour(a) => {
             b = b.title(format!("🛠️ {} 🛠️", &a.kind.to_string()));
-            b = b.field("Peso", &a.weight.to_string(), true);
-            b = b.field("Rasgo", &a.armour_trait.to_string(), true);
-            b = b.field("Calidad", &a.quality.to_string(), true);
+            b = b.field("Peso", a.weight.to_string(), true);
+            b = b.field("Rasgo", a.armour_trait.to_string(), true);
+            b = b.field("Calidad", a.quality.to_string(), true);
             if let Some(enchantment) = &a.enchantment {
                 b = b.field("Encantamiento", enchantment.to_string(), true);
             }
         }
         SetPiece::Jewelry(j) => {
             b = b.title(format!("🛠️ {} 🛠️️", &j.kind.to_string()));
-            b = b.field("Rasgo", &j.jewelry_trait.to_string(), true);
-            b = b.field("Calidad", &j.quality.to_string(), true);
+            b = b.field("Rasgo", j.jewelry_trait.to_string(), true);
+            b = b.field("Calidad", j.quality.to_string(), true);
             if let Some(enchantment) = &j.enchantment {
                 b = b.field("Encantamiento", enchantment.to_string(), true);
             }
@@ -223,29 +462,32 @@ pub fn gear_piece_embed(part: &SetPiece) -> CreateEmbed {
 pub fn gear_result_embed(set: &Vec<SetPiece>, name: &str) -> CreateEmbed {
     let mut b = CreateEmbed::new();
     b = b.title(format!("🛠️ {} 🛠️️", name));
+    if let Some(piece) = set.first() {
+        b = b.field("Nivel", piece.level().to_string(), false);
+    }
     for piece in set {
         match piece {
             SetPiece::Weapon(w) => {
-                b = b.field(&w.kind.to_string(), "", false);
-                b = b.field("Rasgo", &w.weapon_trait.to_string(), true);
-                b = b.field("Calidad", &w.quality.to_string(), true);
+                b = b.field(w.kind.to_string(), "", false);
+                b = b.field("Rasgo", w.weapon_trait.to_string(), true);
+                b = b.field("Calidad", w.quality.to_string(), true);
                 if let Some(enchantment) = &w.enchantment {
                     b = b.field("Encantamiento", enchantment.to_string(), true);
                 }
             }
             SetPiece::Armour(a) => {
-                b = b.field(&a.kind.to_string(), "", false);
-                b = b.field("Peso", &a.weight.to_string(), true);
-                b = b.field("Rasgo", &a.armour_trait.to_string(), true);
-                b = b.field("Calidad", &a.quality.to_string(), true);
+                b = b.field(a.kind.to_string(), "", false);
+                b = b.field("Peso", a.weight.to_string(), true);
+                b = b.field("Rasgo", a.armour_trait.to_string(), true);
+                b = b.field("Calidad", a.quality.to_string(), true);
                 if let Some(enchantment) = &a.enchantment {
                     b = b.field("Encantamiento", enchantment.to_string(), true);
                 }
             }
             SetPiece::Jewelry(j) => {
-                b = b.field(&j.kind.to_string(), "", false);
-                b = b.field("Rasgo", &j.jewelry_trait.to_string(), true);
-                b = b.field("Calidad", &j.quality.to_string(), true);
+                b = b.field(j.kind.to_string(), "", false);
+                b = b.field("Rasgo", j.jewelry_trait.to_string(), true);
+                b = b.field("Calidad", j.quality.to_string(), true);
                 if let Some(enchantment) = &j.enchantment {
                     b = b.field("Encantamiento", enchantment.to_string(), true);
                 }
@@ -255,19 +497,163 @@ pub fn gear_result_embed(set: &Vec<SetPiece>, name: &str) -> CreateEmbed {
     b
 }
 
+pub fn preset_list_embed(presets: &[Preset]) -> CreateEmbed {
+    let mut b = CreateEmbed::new()
+        .title("💾 Tus builds 💾");
+
+    if presets.is_empty() {
+        b = b.description("No tienes builds guardadas, usa **Confirmar y guardar** al terminar una solicitud de equipamiento");
+    }
+    // Discord embeds are limited to 25 fields
+    for preset in presets.iter().take(25) {
+        b = b.field(
+            &preset.name,
+            format!("{} · {} piezas · guardada <t:{}:d>, cambiada <t:{}:R>", preset.spec.set_name, preset.spec.pieces.len(), preset.created_at, preset.updated_at),
+            false);
+    }
+    b
+}
+
+pub fn guild_settings_embed(settings: &GuildSettings) -> CreateEmbed {
+    let prices = OrderKind::iter()
+        .map(|kind| match settings.price(kind) {
+            Some(price) => format!("**{}:** {} de oro", kind, price),
+            None => format!("**{}:** sin precio", kind)
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+    let channels = OrderKind::iter()
+        .map(|kind| match settings.channels.get(&kind) {
+            Some(channel) => format!("**{}:** {}", kind, Mention::Channel(*channel)),
+            None => format!("**{}:** canal por defecto", kind)
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    CreateEmbed::new()
+        .title("⚙️ Configuración del servidor ⚙️")
+        .field("Asistente", settings.delivery.get_str("Label").unwrap(), true)
+        .field("Rol de crafteadores", settings.crafters.map_or("Sin configurar".to_string(), |r| Mention::Role(r).to_string()), true)
+        .field("Canal de solicitudes", settings.channel.map_or("El canal del menú".to_string(), |c| Mention::Channel(c).to_string()), true)
+        .field("Hilos por solicitud", if settings.threads { "Sí" } else { "No" }, true)
+        .field("Idioma de los sets", settings.language.get_str("Label").unwrap(), true)
+        .field("Caducidad", settings.timeout.map_or("Nunca".to_string(), |m| format!("{} minutos", m)), true)
+        .field("Materiales", if settings.show_costs { "Se muestran" } else { "Ocultos" }, true)
+        .field("Precios", prices, false)
+        .field("Canales por categoría", channels, false)
+}
+
+pub fn order_request_embed(order: &Order, language: Language) -> CreateEmbed {
+    let set_name = order.set_name.as_deref().unwrap_or_default();
+    let embed = match order.kind {
+        OrderKind::Research => gear_research_piece_embed(&order.pieces),
+        _ => gear_set_piece_embed(sets::find_set(set_name).map_or(set_name, |set| language.set_name(set)), &order.pieces)
+    };
+    order_state_embed(embed, &order.status, order.assignee)
+}
+
+pub fn order_state_embed(embed: CreateEmbed, status: &OrderStatus, assignee: Option<UserId>) -> CreateEmbed {
+    let mut b = embed
+        .field(":bookmark: Estado", format!("{} {}", status.get_str("Emoji").unwrap(), status), true);
+    if let Some(assignee) = assignee {
+        b = b.field(":hammer: Crafteador", Mention::User(assignee).to_string(), true);
+    }
+    match status {
+        OrderStatus::Delivered => b.color((30,144,255)),
+        OrderStatus::Cancelled => b.color((128,128,128)),
+        _ => b
+    }
+}
+
+pub fn order_action_row(status: &OrderStatus) -> Vec<CreateActionRow> {
+    let buttons: Vec<CreateButton> = OrderAction::iter()
+        .filter(|action| status.next(action).is_some())
+        .map(|action| {
+            let button = CreateButton::new(action.to_string());
+            match action {
+                OrderAction::Claim => button.label("Reclamar").emoji(ReactionType::Unicode("🙋".to_string())).style(ButtonStyle::Primary),
+                OrderAction::Start => button.label("Empezar").emoji(ReactionType::Unicode("⚒️".to_string())).style(ButtonStyle::Secondary),
+                OrderAction::Deliver => button.label("Entregada").emoji(ReactionType::Unicode("✅".to_string())).style(ButtonStyle::Success),
+                OrderAction::Cancel => button.label("Cancelar").emoji(ReactionType::Unicode("❌".to_string())).style(ButtonStyle::Danger),
+            }
+        })
+        .collect();
+
+    if buttons.is_empty() { vec![] } else { vec![CreateActionRow::Buttons(buttons)] }
+}
+
+pub fn order_embed(order: &Order) -> CreateEmbed {
+    let title = order.set_name.clone().unwrap_or_else(|| order.kind.to_string());
+    let mut b = gear_set_piece_embed(&title, &order.pieces)
+        .description(format!("Solicitud #{} de {} <t:{}:R>", order.id, order.kind, order.created_at))
+        .field("Crafteadores", Mention::Role(order.crafters).to_string(), true)
+        .field("Estado", format!("{} {}", order.status.get_str("Emoji").unwrap(), order.status), true)
+        .field("Actualizada", format!("<t:{}:R>", order.updated_at), true)
+        .url(order.message_id.link(order.channel_id, order.guild_id));
+    if let Some(assignee) = order.assignee {
+        b = b.field("Crafteador", Mention::User(assignee).to_string(), true);
+    }
+    if let Some(price) = order.price {
+        b = b.field("Precio por pieza", price.to_string(), true);
+    }
+    if let Some(thread) = order.thread {
+        b = b.field("Hilo", Mention::Channel(thread).to_string(), true);
+    }
+    if let Some(details) = &order.details {
+        b = b.field("Detalles", details, false);
+    }
+    b
+}
+
+pub fn order_list_embed(orders: &[Order]) -> CreateEmbed {
+    let mut b = CreateEmbed::new()
+        .title("📜 Tus solicitudes 📜");
+
+    if orders.is_empty() {
+        b = b.description("No tienes solicitudes registradas");
+    }
+    // Discord embeds are limited to 25 fields
+    for order in orders.iter().take(25) {
+        b = b.field(
+            format!("#{} {}", order.id, order.kind),
+            format!("{} {} {} <t:{}:R>", order.status.get_str("Emoji").unwrap(), order.status, order.set_name.as_deref().unwrap_or_default(), order.created_at),
+            false);
+    }
+    b
+}
+
 pub fn gear_set_modal(name: &str) -> CreateInputText {
     CreateInputText::new(InputTextStyle::Short, "Nombre del Set/Conjunto", name)
         .placeholder("Cólera de la orden")
 }
 
+pub fn gear_build_modal(name: &str) -> CreateInputText {
+    CreateInputText::new(InputTextStyle::Paragraph, "Build (texto, TOML o JSON)", name)
+        .placeholder("Set: Cólera de la orden\nCabeza:Pesada:Divinidad:Amarilla:Glifo de salud\nAnillo:Arcanidad:Morada")
+}
+
+pub fn preset_name_modal(name: &str) -> CreateInputText {
+    CreateInputText::new(InputTextStyle::Short, "Nombre de la build", name)
+        .placeholder("Cólera tanque")
+        .max_length(100)
+}
+
 pub fn consumables_modal(name: &str) -> CreateInputText {
     CreateInputText::new(InputTextStyle::Paragraph, "Pociones y Comida", name)
-        .placeholder("- Poción de poder de hechizo (x20)\n- Sopa de zanahorioa (x10)")
+        .placeholder("- Poción de poder de hechizo (x20)\n- Sopa de zanahoria (x10)")
+}
+
+pub fn quantity_modal(name: &str, item: &str, placeholder: &str) -> CreateInputText {
+    // Discord cuts input labels at 45 characters
+    let label: String = format!("Cantidad de {}", item).chars().take(45).collect();
+    CreateInputText::new(InputTextStyle::Short, label, name)
+        .placeholder(placeholder)
+        .max_length(4)
 }
 
 pub fn enchantments_modal(name: &str) -> CreateInputText {
     CreateInputText::new(InputTextStyle::Paragraph, "Glifos", name)
-        .placeholder("- Glifo Realmente Soberbio de Magia (x2)\n- Glifo Realmente Soberbio de Vida (x2)")
+        .placeholder("- Glifo Realmente Soberbio de Magia (x2)\n- Glifo Superior de salud (Azul) (x2)")
 }
 
 pub fn menu_description(price: &f64, crafters: RoleId) -> String {
@@ -276,7 +662,9 @@ pub fn menu_description(price: &f64, crafters: RoleId) -> String {
 
 De cara a solicitar un crafting a los {} en el siguiente canal se deberá rellenar mediante los tres botones que aparecen al final de este mensaje, vease:
 - **Equipamiento:** Para solicitar diversas piezas de set, incluyendo armas, armaduras y joyeria.
-- **Consumibles:** Para 🍖 comida y 🧪 pociones.
+- **Pegar build:** Para solicitar un set completo pegando su build, una pieza por línea.
+- **Mis builds:** Para volver a pedir una build guardada al confirmar una solicitud.
+- **Consumibles:** Para 🍖 comida, 🍺 bebidas, 🧪 pociones y ☠️ venenos, eligiéndolos del catálogo o escribiéndolos a mano.
 - **Encantamientos:** Para los distintos glifos de armas, armadura y joyeria.
 
 Se deberan enviar los materiales al fabricante que se encargue y, __**en caso de tener nivel CP300+ y solo en los encargos de equipamiento**__, abonar el pago al banco del gremio de __**{}**__ de oro por pieza, __**{}**__ de oro por set",
@@ -297,8 +685,48 @@ pub fn confirmation_row(name: &str) -> CreateActionRow {
     ])
 }
 
-pub fn menu_action_row() -> CreateActionRow {
-    CreateActionRow::Buttons(vec![
+/// Back, skip and cancel buttons shown under every step of the gear wizard
+pub fn wizard_navigation_row(name: &str, back: bool, skip: bool) -> CreateActionRow {
+    let mut buttons = vec![];
+    if back {
+        buttons.push(CreateButton::new(format!("{}_back", name))
+            .emoji(ReactionType::Unicode("⬅️".to_string()))
+            .label("Atrás")
+            .style(ButtonStyle::Secondary));
+    }
+    if skip {
+        buttons.push(CreateButton::new(format!("{}_skip", name))
+            .emoji(ReactionType::Unicode("⏭️".to_string()))
+            .label("Omitir")
+            .style(ButtonStyle::Secondary));
+    }
+    buttons.push(CreateButton::new(format!("{}_cancel", name))
+        .emoji(ReactionType::Unicode("✖️".to_string()))
+        .label("Cancelar")
+        .style(ButtonStyle::Danger));
+    CreateActionRow::Buttons(buttons)
+}
+
+pub fn piece_edit_select(name: &str, pieces: &[SetPiece]) -> CreateSelectMenu {
+    // Discord select menus hold at most 25 options
+    let options = CreateSelectMenuKind::String {
+        options: pieces.iter()
+            .enumerate()
+            .take(25)
+            .map(|(idx, piece)| CreateSelectMenuOption::new(
+                format!("{}. {}", idx + 1, piece.item()),
+                idx.to_string())
+                .description(KnownTrait::from(piece).gear_trait.to_string()))
+            .collect()
+    };
+
+    CreateSelectMenu::new(format!("{}_edit", name), options)
+        .placeholder("Editar una pieza ya configurada")
+}
+
+pub fn menu_action_rows() -> Vec<CreateActionRow> {
+    // Discord fits at most five buttons per row
+    vec![CreateActionRow::Buttons(vec![
         CreateButton::new("Gear")
             .emoji(ReactionType::Unicode("⚔️".to_string()))
             .label("Equipamiento")
@@ -314,8 +742,17 @@ pub fn menu_action_row() -> CreateActionRow {
         CreateButton::new("GearResearch")
             .emoji(ReactionType::Unicode("🔬".to_string()))
             .label("Investigar Rasgos")
-            .style(ButtonStyle::Primary)
-    ])
+            .style(ButtonStyle::Primary),
+        CreateButton::new("GearImport")
+            .emoji(ReactionType::Unicode("📋".to_string()))
+            .label("Pegar build")
+            .style(ButtonStyle::Secondary)
+    ]), CreateActionRow::Buttons(vec![
+        CreateButton::new("GearPresets")
+            .emoji(ReactionType::Unicode("💾".to_string()))
+            .label("Mis builds")
+            .style(ButtonStyle::Secondary)
+    ])]
 }
 
 fn get_enum_as_menu<T: IntoEnumIterator + EnumMessage + Display>(name: &str, placeholder: &str) -> CreateSelectMenu {
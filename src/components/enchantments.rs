@@ -1,7 +1,7 @@
 use serenity::all::{CreateSelectMenu};
-use crate::entities::armour::{ArmourEnchantments};
-use crate::entities::jewelry::{JewelryEnchantments};
-use crate::entities::weapon::{WeaponEnchantments};
+use seht_core::entities::armour::{ArmourEnchantments};
+use seht_core::entities::jewelry::{JewelryEnchantments};
+use seht_core::entities::weapon::{WeaponEnchantments};
 
 pub fn gear_armour_enchantments(name: &str) -> CreateSelectMenu {
     super::get_enum_as_menu::<ArmourEnchantments>(name, "Selecciona el encantamiento que quieres")
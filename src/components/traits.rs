@@ -1,7 +1,7 @@
 use serenity::builder::CreateSelectMenu;
-use crate::entities::armour::ArmourTraits;
-use crate::entities::jewelry::JewelryTraits;
-use crate::entities::weapon::WeaponTraits;
+use seht_core::entities::armour::ArmourTraits;
+use seht_core::entities::jewelry::JewelryTraits;
+use seht_core::entities::weapon::WeaponTraits;
 
 pub fn gear_armour_traits(name: &str) -> CreateSelectMenu {
     super::get_enum_as_menu::<ArmourTraits>(name, "Selecciona el rasgo que quieres")
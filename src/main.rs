@@ -1,15 +1,19 @@
 mod requests;
 mod components;
-mod entities;
+mod store;
 
 use anyhow::anyhow;
-use serenity::all::{CreateCommand, CreateCommandOption, CreateInteractionResponse, CreateInteractionResponseMessage};
+use serenity::all::{CreateCommand, CreateCommandOption};
 use serenity::async_trait;
 use serenity::model::gateway::Ready;
 use serenity::prelude::*;
 use serenity::model::prelude::*;
+use std::sync::Arc;
 use shuttle_secrets::SecretStore;
 use tracing::{error, info};
+use strum::{EnumProperty, IntoEnumIterator};
+use crate::store::{Delivery, Language, MenuStore, OrderAction, OrderKind, OrderStore, PresetStore, ResearchStore, SessionRepository, SessionStore, SettingsStore};
+use crate::store::sqlite::SqliteStore;
 
 struct Bot;
 
@@ -18,22 +22,142 @@ impl EventHandler for Bot {
     async fn ready(&self, ctx: Context, ready: Ready) {
         info!("{} is connected!", ready.user.name);
 
-        let guild_command = Command::create_global_command(&ctx.http, CreateCommand::new("menu")
-            .description("Menu de solicitudes de crafteo")
-            .add_option(
-                CreateCommandOption::new(
-                    CommandOptionType::Number, "precio", "Precio por pieza de la solicitud")
-                    .required(true))
-            .add_option(
-                CreateCommandOption::new(CommandOptionType::Role, "rol", "Rol de crafteadores").required(true)
-            )
-        ).await;
+        let commands = vec![
+            CreateCommand::new("menu")
+                .description("Menu de solicitudes de crafteo")
+                .add_option(
+                    CreateCommandOption::new(
+                        CommandOptionType::Number, "precio", "Precio por pieza de la solicitud, el configurado si no se indica"))
+                .add_option(
+                    CreateCommandOption::new(CommandOptionType::Role, "rol", "Rol de crafteadores, el configurado si no se indica")
+                ),
+            CreateCommand::new("pedidos")
+                .description("Lista tus solicitudes de crafteo")
+                .add_option(
+                    CreateCommandOption::new(CommandOptionType::Integer, "id", "Numero de la solicitud")
+                ),
+            CreateCommand::new("set")
+                .description("Consulta un set crafteable")
+                .add_option(
+                    CreateCommandOption::new(CommandOptionType::String, "nombre", "Nombre del set")
+                        .required(true)
+                        .set_autocomplete(true)
+                ),
+            CreateCommand::new("investigacion")
+                .description("Rasgos que tienes investigados")
+                .add_option(
+                    CreateCommandOption::new(CommandOptionType::SubCommand, "aprender", "Marca un rasgo como investigado")
+                        .add_sub_option(CreateCommandOption::new(CommandOptionType::String, "pieza", "Pieza investigada")
+                            .required(true).set_autocomplete(true))
+                        .add_sub_option(CreateCommandOption::new(CommandOptionType::String, "rasgo", "Rasgo investigado")
+                            .required(true).set_autocomplete(true))
+                )
+                .add_option(
+                    CreateCommandOption::new(CommandOptionType::SubCommand, "olvidar", "Quita un rasgo investigado")
+                        .add_sub_option(CreateCommandOption::new(CommandOptionType::String, "pieza", "Pieza investigada")
+                            .required(true).set_autocomplete(true))
+                        .add_sub_option(CreateCommandOption::new(CommandOptionType::String, "rasgo", "Rasgo investigado")
+                            .required(true).set_autocomplete(true))
+                )
+                .add_option(
+                    CreateCommandOption::new(CommandOptionType::SubCommand, "importar", "Importa varios rasgos a la vez")
+                        .add_sub_option(CreateCommandOption::new(CommandOptionType::String, "texto", "Cabeza Pesada: Divinidad, Imbuición; Anillo: Arcanidad"))
+                        .add_sub_option(CreateCommandOption::new(CommandOptionType::Attachment, "archivo", "Fichero con una pieza y sus rasgos por línea"))
+                )
+                .add_option(
+                    CreateCommandOption::new(CommandOptionType::SubCommand, "ver", "Muestra los rasgos investigados")
+                        .add_sub_option(CreateCommandOption::new(CommandOptionType::User, "usuario", "Crafteador a consultar"))
+                ),
+            CreateCommand::new("builds")
+                .description("Builds de equipamiento guardadas")
+                .add_option(CreateCommandOption::new(CommandOptionType::SubCommand, "listar", "Muestra tus builds guardadas"))
+                .add_option(
+                    CreateCommandOption::new(CommandOptionType::SubCommand, "ver", "Muestra las piezas y materiales de una build")
+                        .add_sub_option(CreateCommandOption::new(CommandOptionType::String, "nombre", "Nombre de la build")
+                            .required(true).set_autocomplete(true))
+                )
+                .add_option(
+                    CreateCommandOption::new(CommandOptionType::SubCommand, "pedir", "Vuelve a pedir una build, pudiendo cambiar su calidad y glifos")
+                        .add_sub_option(CreateCommandOption::new(CommandOptionType::String, "nombre", "Nombre de la build")
+                            .required(true).set_autocomplete(true))
+                )
+                .add_option(
+                    CreateCommandOption::new(CommandOptionType::SubCommand, "renombrar", "Cambia el nombre de una build")
+                        .add_sub_option(CreateCommandOption::new(CommandOptionType::String, "nombre", "Nombre de la build")
+                            .required(true).set_autocomplete(true))
+                        .add_sub_option(CreateCommandOption::new(CommandOptionType::String, "nuevo", "Nuevo nombre")
+                            .required(true).max_length(100))
+                )
+                .add_option(
+                    CreateCommandOption::new(CommandOptionType::SubCommand, "borrar", "Borra una build guardada")
+                        .add_sub_option(CreateCommandOption::new(CommandOptionType::String, "nombre", "Nombre de la build")
+                            .required(true).set_autocomplete(true))
+                ),
+            CreateCommand::new("config")
+                .description("Configuración del bot en el servidor")
+                .default_member_permissions(Permissions::MANAGE_GUILD)
+                .dm_permission(false)
+                .add_option(
+                    CreateCommandOption::new(CommandOptionType::SubCommand, "asistente", "Dónde se muestran los pasos de las solicitudes de equipamiento")
+                        .add_sub_option(Delivery::iter().fold(
+                            CreateCommandOption::new(CommandOptionType::String, "modo", "Mensajes directos o mensaje efímero").required(true),
+                            |option, delivery| option.add_string_choice(delivery.get_str("Label").unwrap(), delivery.to_string())))
+                )
+                .add_option(
+                    CreateCommandOption::new(CommandOptionType::SubCommand, "menu", "Cambia el precio o el rol de un menú ya publicado")
+                        .add_sub_option(CreateCommandOption::new(CommandOptionType::String, "mensaje", "Id o enlace del mensaje del menú")
+                            .required(true))
+                        .add_sub_option(CreateCommandOption::new(CommandOptionType::Number, "precio", "Precio por pieza de la solicitud"))
+                        .add_sub_option(CreateCommandOption::new(CommandOptionType::Role, "rol", "Rol de crafteadores"))
+                )
+                .add_option(CreateCommandOption::new(CommandOptionType::SubCommand, "ver", "Muestra la configuración del servidor"))
+                .add_option(
+                    CreateCommandOption::new(CommandOptionType::SubCommand, "rol", "Rol de crafteadores por defecto de /menu")
+                        .add_sub_option(CreateCommandOption::new(CommandOptionType::Role, "rol", "Rol de crafteadores, sin rol para quitarlo"))
+                )
+                .add_option(
+                    CreateCommandOption::new(CommandOptionType::SubCommand, "canal", "Canal donde se publican las solicitudes")
+                        .add_sub_option(CreateCommandOption::new(CommandOptionType::Channel, "canal", "Canal de texto o foro, sin canal para usar el del menú")
+                            .channel_types(vec![ChannelType::Text, ChannelType::Forum]))
+                        .add_sub_option(OrderKind::iter().fold(
+                            CreateCommandOption::new(CommandOptionType::String, "categoria", "Categoría que usará el canal, todas si no se indica"),
+                            |option, kind| option.add_string_choice(kind.to_string(), kind.to_string())))
+                )
+                .add_option(
+                    CreateCommandOption::new(CommandOptionType::SubCommand, "hilos", "Abre un hilo por solicitud para hablar con el crafteador")
+                        .add_sub_option(CreateCommandOption::new(CommandOptionType::Boolean, "activar", "Abrir hilos").required(true))
+                )
+                .add_option(
+                    CreateCommandOption::new(CommandOptionType::SubCommand, "precio", "Precio de una categoría de solicitudes")
+                        .add_sub_option(OrderKind::iter().fold(
+                            CreateCommandOption::new(CommandOptionType::String, "categoria", "Categoría de solicitudes").required(true),
+                            |option, kind| option.add_string_choice(kind.to_string(), kind.to_string())))
+                        .add_sub_option(CreateCommandOption::new(CommandOptionType::Number, "precio", "Precio en oro, sin precio para quitarlo")
+                            .min_number_value(0.0))
+                )
+                .add_option(
+                    CreateCommandOption::new(CommandOptionType::SubCommand, "idioma", "Idioma de los nombres de set en las solicitudes")
+                        .add_sub_option(Language::iter().fold(
+                            CreateCommandOption::new(CommandOptionType::String, "idioma", "Idioma").required(true),
+                            |option, language| option.add_string_choice(language.get_str("Label").unwrap(), language.to_string())))
+                )
+                .add_option(
+                    CreateCommandOption::new(CommandOptionType::SubCommand, "caducidad", "Minutos que espera una solicitud de equipamiento sin respuesta")
+                        .add_sub_option(CreateCommandOption::new(CommandOptionType::Integer, "minutos", "Minutos, 0 para no caducar nunca")
+                            .required(true).min_int_value(0))
+                )
+                .add_option(
+                    CreateCommandOption::new(CommandOptionType::SubCommand, "materiales", "Muestra u oculta los materiales en las solicitudes")
+                        .add_sub_option(CreateCommandOption::new(CommandOptionType::Boolean, "mostrar", "Mostrar los materiales").required(true))
+                )
+        ];
 
-        match guild_command {
-            Ok(command) => info!("Registered global slash command: {}", command.name),
-            Err(e) => error!("Error registering global command: {:#?}", e)
+        for command in commands {
+            match Command::create_global_command(&ctx.http, command).await {
+                Ok(command) => info!("Registered global slash command: {}", command.name),
+                Err(e) => error!("Error registering global command: {:#?}", e)
+            }
         }
-
     }
 
     // `interaction_create` runs when the user interacts with the bot
@@ -45,26 +169,14 @@ impl EventHandler for Bot {
             Interaction::Command(command) => {
                 info!("Received command interaction: {}", command.data.name);
 
-                let menu_price = command.data.options.get(0)
-                    .expect("Expected price");
-
-                let price = if let CommandDataOptionValue::Number(price) = menu_price.value {
-                    price
-                } else { 0f64 };
-
-                let menu_role = command.data.options.get(1)
-                    .expect("Expected role");
-
-                let role = if let CommandDataOptionValue::Role(role) = menu_role.value {
-                    role
-                } else {unreachable!("Expected role")};
-
-                if let Err(why) = command.create_response(&ctx.http, CreateInteractionResponse::Message(
-                    CreateInteractionResponseMessage::new()
-                        .content(components::menu_description(&price, role))
-                        .components(vec![components::menu_action_row()])
-                )).await {
-                    info!("Cannot respond to slash command: {}", why);
+                match command.data.name.as_str() {
+                    "menu" => requests::menu::menu(command, &ctx).await,
+                    "pedidos" => requests::orders::orders(command, &ctx).await,
+                    "set" => requests::sets::set_info(command, &ctx).await,
+                    "investigacion" => requests::research::research(command, &ctx).await,
+                    "builds" => requests::presets::presets(command, &ctx).await,
+                    "config" => requests::config::config(command, &ctx).await,
+                    _ => info!("command {} not registered", command.data.name)
                 }
             }
             Interaction::Component(component) => {
@@ -72,21 +184,38 @@ impl EventHandler for Bot {
                 match component.data.custom_id.as_str() {
                     "Gear" => requests::gear::gear(component, &ctx).await,
                     "GearResearch" => requests::gear::gear_research(component, &ctx).await,
+                    "GearImport" => requests::gear::gear_import(component, &ctx).await,
+                    "GearPresets" => requests::gear::gear_presets(component, &ctx).await,
                     "Consumables" => requests::consumable::consumables(component, &ctx).await,
                     "Enchantment" => requests::enchantment::enchantment(component, &ctx).await,
+                    "order_claim" => requests::orders::order_action(component, &ctx, OrderAction::Claim).await,
+                    "order_start" => requests::orders::order_action(component, &ctx, OrderAction::Start).await,
+                    "order_deliver" => requests::orders::order_action(component, &ctx, OrderAction::Deliver).await,
+                    "order_cancel" => requests::orders::order_action(component, &ctx, OrderAction::Cancel).await,
+                    id if id.starts_with("wizard_") => requests::gear::gear_wizard(component, &ctx).await,
+                    id if id.starts_with("consumables_") => requests::consumable::consumables_picker(component, &ctx).await,
+                    id if id.starts_with("enchantments_") => requests::enchantment::enchantment_picker(component, &ctx).await,
                     _ => info!("interaction {} not registered", component.data.custom_id)
                 }
             }
             Interaction::Autocomplete(autocomplete) => {
-                info!("Received autocomplete interaction: {:#?}", autocomplete)
+                match autocomplete.data.name.as_str() {
+                    "set" => requests::sets::set_autocomplete(autocomplete, &ctx).await,
+                    "investigacion" => requests::research::research_autocomplete(autocomplete, &ctx).await,
+                    "builds" => requests::presets::presets_autocomplete(autocomplete, &ctx).await,
+                    _ => info!("autocomplete {} not registered", autocomplete.data.name)
+                }
             }
             Interaction::Modal(modal) => {
                 info!("Received modal submit interaction: {}", modal.data.custom_id);
                 match modal.data.custom_id.as_str() {
                     "gear_set_modal" => requests::gear::gear_modal(modal, &ctx).await,
+                    "gear_import_modal" => requests::gear::gear_import_modal(modal, &ctx).await,
+                    id if id.starts_with("wizard_") => requests::gear::gear_wizard_modal(modal, &ctx).await,
                     "enchantment_modal" => requests::enchantment::enchantment_modal(modal, &ctx).await,
-                    "consumables_modal" => requests::consumable::consumables_modal(modal, &ctx).await,
-                    _ => unreachable!("interaction id not found")
+                    id if id.starts_with("enchantments_") => requests::enchantment::enchantment_modal(modal, &ctx).await,
+                    id if id.starts_with("consumables_") => requests::consumable::consumables_modal(modal, &ctx).await,
+                    _ => info!("modal {} not registered", modal.data.custom_id)
                 }
             }
             _ => {}
@@ -105,8 +234,24 @@ async fn serenity(
         return Err(anyhow!("'DISCORD_TOKEN' was not found").into());
     };
 
+    // Orders are kept in a local SQLite file so they survive restarts
+    let database_path = secret_store.get("DATABASE_PATH")
+        .unwrap_or_else(|| "seht-crafting.db".to_string());
+    let store = Arc::new(SqliteStore::open(&database_path)?);
+    // Requests abandoned while the bot was down would otherwise stay stored forever
+    match store.prune_sessions() {
+        Ok(pruned) => info!("Pruned {} expired gear sessions", pruned),
+        Err(why) => error!("Cannot prune expired gear sessions: {:?}", why)
+    }
+
     let client = Client::builder(&token, GatewayIntents::empty())
         .event_handler(Bot)
+        .type_map_insert::<OrderStore>(store.clone())
+        .type_map_insert::<ResearchStore>(store.clone())
+        .type_map_insert::<PresetStore>(store.clone())
+        .type_map_insert::<SessionStore>(store.clone())
+        .type_map_insert::<SettingsStore>(store.clone())
+        .type_map_insert::<MenuStore>(store)
         .await
         .expect("Error creating client");
 
@@ -1,22 +1,211 @@
 pub mod gear;
+pub mod config;
 pub mod enchantment;
+pub mod error;
 pub mod consumable;
+pub mod menu;
+pub mod orders;
+pub mod presets;
+pub mod research;
+pub mod sets;
 
-use serenity::all::{ComponentInteraction, CreateInteractionResponse, CreateInteractionResponseMessage, Message};
+use std::num::NonZeroU64;
+use regex::Regex;
+use serenity::all::{Channel, ChannelId, ChannelType, CommandInteraction, ComponentInteraction, CreateForumPost, CreateInteractionResponse, CreateInteractionResponseFollowup, CreateInteractionResponseMessage, CreateMessage, CreateThread, GuildId, Mention, Message, MessageId, ModalInteraction, ResolvedOption, ResolvedValue, RoleId, UserId};
+use serenity::all::ActionRowComponent::InputText;
 use serenity::prelude::*;
+use tracing::{error, info};
+use seht_core::entities::MAX_QUANTITY;
+use crate::requests::error::RequestError;
+use crate::store::{GuildSettings, Menu, MenuStore, NewOrder, OrderStore, SettingsStore};
 
 async fn ok_response(interaction: &ComponentInteraction, ctx: &Context) {
-    interaction.create_response(&ctx.http, CreateInteractionResponse::Defer(CreateInteractionResponseMessage::new()))
-        .await.unwrap();
+    Reply::Component(interaction).respond(ctx, CreateInteractionResponse::Defer(CreateInteractionResponseMessage::new())).await;
 }
 
-async fn await_component_interaction(msg: Message, ctx: &Context) -> Option<ComponentInteraction> {
-    match msg.await_component_interaction(ctx).timeout(std::time::Duration::from_secs(60 * 3)).await {
-        Some(x) => Some(x),
-        None => {
-            msg.reply(&ctx.http, "Agotado el tiempo para la solicitud, vuelve a empezar").await.unwrap();
-            None
+/// Interaction a request answers to
+enum Reply<'a> {
+    Command(&'a CommandInteraction),
+    Component(&'a ComponentInteraction),
+    Modal(&'a ModalInteraction)
+}
+
+impl Reply<'_> {
+    async fn respond(&self, ctx: &Context, response: CreateInteractionResponse) {
+        if let Err(why) = self.create_response(ctx, response).await {
+            info!("Cannot respond to request: {}", why)
+        }
+    }
+
+    async fn create_response(&self, ctx: &Context, response: CreateInteractionResponse) -> serenity::Result<()> {
+        match self {
+            Reply::Command(interaction) => interaction.create_response(&ctx.http, response).await,
+            Reply::Component(interaction) => interaction.create_response(&ctx.http, response).await,
+            Reply::Modal(interaction) => interaction.create_response(&ctx.http, response).await
+        }
+    }
+
+    /// Tells the user why the request stopped, as a followup when the interaction was already answered
+    async fn report(&self, ctx: &Context, why: RequestError) {
+        error!("Request failed: {:?}", why);
+        let response = CreateInteractionResponseMessage::new()
+            .content(why.to_string())
+            .ephemeral(true);
+        if self.create_response(ctx, CreateInteractionResponse::Message(response)).await.is_ok() {
+            return
+        }
+        let followup = CreateInteractionResponseFollowup::new()
+            .content(why.to_string())
+            .ephemeral(true);
+        let result = match self {
+            Reply::Command(interaction) => interaction.create_followup(&ctx.http, followup).await,
+            Reply::Component(interaction) => interaction.create_followup(&ctx.http, followup).await,
+            Reply::Modal(interaction) => interaction.create_followup(&ctx.http, followup).await
+        };
+        if let Err(why) = result {
+            info!("Cannot report request error: {}", why)
+        }
+    }
+}
+
+
+fn menu_price(content: &str) -> Option<f64> {
+    let re = Regex::new(r"__\*\*(\d+(?:\.\d+)?)\*\*__ de oro por pieza").unwrap();
+    re.captures(content)
+        .and_then(|c| c.get(1))
+        .and_then(|p| p.as_str().parse().ok())
+}
+
+/// Where a request was posted
+struct Posted {
+    channel_id: ChannelId,
+    message_id: MessageId,
+    thread: Option<ChannelId>
+}
+
+/// Posts a request as a forum post in forum channels and as a message elsewhere,
+/// followed by a thread named `title` when the guild wants one per request
+async fn post_request(ctx: &Context, settings: &GuildSettings, channel_id: ChannelId, title: &str, requester: UserId, message: CreateMessage) -> Result<Posted, RequestError> {
+    let forum = matches!(channel_id.to_channel(&ctx.http).await, Ok(Channel::Guild(channel)) if channel.kind == ChannelType::Forum);
+    if forum {
+        let post = channel_id.create_forum_post(&ctx.http, CreateForumPost::new(title, message)).await?;
+        // The starter message of a forum post shares its id with the post
+        return Ok(Posted { channel_id: post.id, message_id: MessageId::new(post.id.get()), thread: Some(post.id) })
+    }
+
+    let request = channel_id.send_message(&ctx.http, message).await?;
+    let mut thread = None;
+    if settings.threads {
+        match channel_id.create_thread_from_message(&ctx.http, request.id, CreateThread::new(title)).await {
+            Ok(opened) => {
+                thread = Some(opened.id);
+                if let Err(why) = opened.id.send_message(&ctx.http, CreateMessage::new()
+                    .content(format!("{} usa este hilo para hablar con quien reclame la solicitud", Mention::User(requester)))
+                ).await {
+                    info!("Cannot greet in thread {}: {}", opened.id, why);
+                }
+            }
+            Err(why) => info!("Cannot open thread for request {}: {}", request.id, why)
         }
     }
+    Ok(Posted { channel_id, message_id: request.id, thread })
 }
 
+async fn save_order(ctx: &Context, order: NewOrder) {
+    let store = ctx.data.read().await.get::<OrderStore>().cloned();
+    match store {
+        Some(store) => match store.create_order(&order) {
+            Ok(id) => info!("Stored {} order #{} for {}", order.kind, id, order.requester),
+            Err(why) => error!("Cannot store {} order for {}: {:?}", order.kind, order.requester, why)
+        },
+        None => error!("Order store not initialised")
+    }
+}
+
+/// Configuration of the menu the request was made from. Menus posted before their configuration
+/// was stored are read from their text once and stored from then on
+async fn menu_config(ctx: &Context, menu: &Message) -> Result<Menu, RequestError> {
+    let store = ctx.data.read().await.get::<MenuStore>().cloned()
+        .expect("Menu store not initialised");
+    if let Some(config) = store.menu(menu.id)? {
+        return Ok(config)
+    }
+
+    let re = Regex::new(r"<@&(\d+)>").unwrap();
+    let crafters = re.captures(&menu.content)
+        .and_then(|c| c.get(1))
+        .and_then(|id| id.as_str().parse::<NonZeroU64>().ok())
+        .map(RoleId::from)
+        .ok_or(RequestError::MissingCraftersRole)?;
+    let config = Menu {
+        message_id: menu.id,
+        guild_id: menu.guild_id,
+        channel_id: menu.channel_id,
+        crafters,
+        price: menu_price(&menu.content).unwrap_or_default()
+    };
+    store.save_menu(&config)?;
+    Ok(config)
+}
+
+/// Configuration stored for a menu, for requests that no longer carry the menu message
+async fn stored_menu(ctx: &Context, menu: MessageId) -> Result<Menu, RequestError> {
+    let store = ctx.data.read().await.get::<MenuStore>().cloned()
+        .expect("Menu store not initialised");
+    store.menu(menu)?.ok_or(RequestError::MissingMenu)
+}
+
+/// Settings of the guild the request was made in, the defaults outside a guild or when they cannot be loaded
+async fn guild_settings(ctx: &Context, guild_id: Option<GuildId>) -> GuildSettings {
+    let Some(guild_id) = guild_id else { return GuildSettings::default() };
+    let store = ctx.data.read().await.get::<SettingsStore>().cloned()
+        .expect("Settings store not initialised");
+    store.guild_settings(guild_id).unwrap_or_else(|why| {
+        error!("Cannot load settings for guild {}: {:?}", guild_id, why);
+        GuildSettings::default()
+    })
+}
+
+/// Value of the text input of a single field modal
+fn modal_input(interaction: &ModalInteraction) -> Result<&str, RequestError> {
+    match interaction.data.components.first().and_then(|row| row.components.first()) {
+        Some(InputText(input)) => input.value.as_deref().ok_or(RequestError::MissingInput),
+        _ => Err(RequestError::MissingInput)
+    }
+}
+
+/// Positive whole number typed in the modal field `id`
+fn modal_quantity(interaction: &ModalInteraction, id: &str) -> Result<i32, RequestError> {
+    let value = interaction.data.components.iter()
+        .flat_map(|row| row.components.iter())
+        .find_map(|component| match component {
+            InputText(input) if input.custom_id == id => input.value.as_deref(),
+            _ => None
+        })
+        .ok_or(RequestError::MissingInput)?
+        .trim();
+    match value.parse::<i32>() {
+        Ok(quantity) if (1..=MAX_QUANTITY).contains(&quantity) => Ok(quantity),
+        _ => Err(RequestError::InvalidQuantity(value.to_string()))
+    }
+}
+
+/// Splits `<prefix><action>_<menu>[_<rest>]` ids of the request pickers into their parts
+fn parse_picker_id<'a>(prefix: &str, id: &'a str) -> Option<(&'a str, MessageId, Option<&'a str>)> {
+    let mut parts = id.strip_prefix(prefix)?.splitn(3, '_');
+    let action = parts.next()?;
+    let menu = MessageId::new(parts.next()?.parse().ok().filter(|id| *id != 0)?);
+    Some((action, menu, parts.next()))
+}
+
+/// String or autocompleted value of a command option, empty when missing
+fn option_str<'a>(options: &[ResolvedOption<'a>], name: &str) -> &'a str {
+    options.iter()
+        .find(|o| o.name == name)
+        .and_then(|o| match o.value {
+            ResolvedValue::String(s) => Some(s),
+            ResolvedValue::Autocomplete { value, .. } => Some(value),
+            _ => None
+        })
+        .unwrap_or_default()
+}
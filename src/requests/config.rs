@@ -0,0 +1,198 @@
+use std::num::NonZeroU64;
+use std::str::FromStr;
+use serenity::all::{CommandInteraction, Context, CreateEmbed, CreateInteractionResponse, CreateInteractionResponseMessage, EditMessage, GuildId, Mention, MessageId, ResolvedOption, ResolvedValue};
+use strum::EnumProperty;
+use tracing::{error, info};
+use crate::components;
+use crate::requests::option_str;
+use crate::store::{Delivery, GuildSettings, Language, MenuStore, OrderKind, SettingsStore};
+
+pub async fn config(command: CommandInteraction, ctx: &Context) {
+    let options = command.data.options();
+    let Some(ResolvedOption { name: subcommand, value: ResolvedValue::SubCommand(options), .. }) = options.first() else {
+        return info!("config command without subcommand");
+    };
+
+    let description = match (command.guild_id, *subcommand) {
+        (None, _) => "La configuración es por servidor, usa el comando desde el servidor".to_string(),
+        (Some(guild_id), "ver") => return show_settings(&command, ctx, guild_id).await,
+        (Some(guild_id), "menu") => edit_menu(ctx, guild_id, options).await,
+        (Some(guild_id), "asistente") => match Delivery::from_str(option_str(options, "modo")) {
+            Ok(delivery) => update_settings(ctx, guild_id, |settings| {
+                settings.delivery = delivery;
+                format!("Las solicitudes de equipamiento se harán por **{}**", delivery.get_str("Label").unwrap())
+            }).await,
+            Err(_) => "Modo desconocido".to_string()
+        },
+        (Some(guild_id), "rol") => {
+            let role = options.iter().find_map(|o| match o.value { ResolvedValue::Role(role) => Some(role.id), _ => None });
+            update_settings(ctx, guild_id, |settings| {
+                settings.crafters = role;
+                match role {
+                    Some(role) => format!("**/menu** mencionará a {} cuando no se indique un rol", Mention::Role(role)),
+                    None => "**/menu** necesitará que se indique el rol".to_string()
+                }
+            }).await
+        }
+        (Some(guild_id), "canal") => {
+            let channel = options.iter().find_map(|o| match o.value { ResolvedValue::Channel(channel) => Some(channel.id), _ => None });
+            // Without a category the channel is the default for all of them
+            let kind = OrderKind::from_str(option_str(options, "categoria")).ok();
+            update_settings(ctx, guild_id, |settings| match (kind, channel) {
+                (Some(kind), Some(channel)) => {
+                    settings.channels.insert(kind, channel);
+                    format!("Las solicitudes de **{}** se publicarán en {}", kind, Mention::Channel(channel))
+                }
+                (Some(kind), None) => {
+                    settings.channels.remove(&kind);
+                    format!("Las solicitudes de **{}** se publicarán en el canal por defecto", kind)
+                }
+                (None, Some(channel)) => {
+                    settings.channel = Some(channel);
+                    format!("Las solicitudes se publicarán en {}", Mention::Channel(channel))
+                }
+                (None, None) => {
+                    settings.channel = None;
+                    "Las solicitudes se publicarán en el canal de cada menú".to_string()
+                }
+            }).await
+        }
+        (Some(guild_id), "hilos") => {
+            let threads = options.iter().any(|o| matches!(o.value, ResolvedValue::Boolean(true)));
+            update_settings(ctx, guild_id, |settings| {
+                settings.threads = threads;
+                if threads { "Cada solicitud tendrá su propio hilo" } else { "Las solicitudes no abrirán hilos, salvo en canales de foro" }.to_string()
+            }).await
+        }
+        (Some(guild_id), "precio") => match OrderKind::from_str(option_str(options, "categoria")) {
+            Ok(kind) => {
+                let price = options.iter().find_map(|o| match o.value { ResolvedValue::Number(price) => Some(price), _ => None });
+                update_settings(ctx, guild_id, |settings| match price {
+                    Some(price) => {
+                        settings.prices.insert(kind, price);
+                        format!("Las solicitudes de **{}** costarán __**{}**__ de oro", kind, price)
+                    }
+                    None => {
+                        settings.prices.remove(&kind);
+                        format!("Las solicitudes de **{}** ya no tienen precio", kind)
+                    }
+                }).await
+            }
+            Err(_) => "Categoría desconocida".to_string()
+        },
+        (Some(guild_id), "idioma") => match Language::from_str(option_str(options, "idioma")) {
+            Ok(language) => update_settings(ctx, guild_id, |settings| {
+                settings.language = language;
+                format!("Los sets se mostrarán en **{}**", language.get_str("Label").unwrap())
+            }).await,
+            Err(_) => "Idioma desconocido".to_string()
+        },
+        (Some(guild_id), "caducidad") => {
+            // Zero minutes leaves requests waiting forever
+            let minutes = options.iter()
+                .find_map(|o| match o.value { ResolvedValue::Integer(minutes) => u32::try_from(minutes).ok(), _ => None })
+                .filter(|minutes| *minutes > 0);
+            update_settings(ctx, guild_id, |settings| {
+                settings.timeout = minutes;
+                match minutes {
+                    Some(minutes) => format!("Las solicitudes sin respuesta caducarán a los **{}** minutos", minutes),
+                    None => "Las solicitudes sin respuesta no caducarán".to_string()
+                }
+            }).await
+        }
+        (Some(guild_id), "materiales") => {
+            let show = options.iter().any(|o| matches!(o.value, ResolvedValue::Boolean(true)));
+            update_settings(ctx, guild_id, |settings| {
+                settings.show_costs = show;
+                if show { "Las solicitudes mostrarán los materiales" } else { "Las solicitudes no mostrarán los materiales" }.to_string()
+            }).await
+        }
+        (Some(_), subcommand) => return info!("config subcommand {} not registered", subcommand)
+    };
+
+    respond(&command, ctx, CreateEmbed::new().description(description)).await;
+}
+
+async fn show_settings(command: &CommandInteraction, ctx: &Context, guild_id: GuildId) {
+    let store = ctx.data.read().await.get::<SettingsStore>().cloned()
+        .expect("Settings store not initialised");
+    let embed = match store.guild_settings(guild_id) {
+        Ok(settings) => components::guild_settings_embed(&settings),
+        Err(why) => {
+            error!("Cannot load settings for guild {}: {:?}", guild_id, why);
+            CreateEmbed::new().description("No se ha podido cargar la configuración")
+        }
+    };
+    respond(command, ctx, embed).await;
+}
+
+/// Applies `change` to the stored settings of the guild, returning its description once saved
+async fn update_settings(ctx: &Context, guild_id: GuildId, change: impl FnOnce(&mut GuildSettings) -> String) -> String {
+    let store = ctx.data.read().await.get::<SettingsStore>().cloned()
+        .expect("Settings store not initialised");
+    let mut settings = match store.guild_settings(guild_id) {
+        Ok(settings) => settings,
+        Err(why) => {
+            error!("Cannot load settings for guild {}: {:?}", guild_id, why);
+            return "No se ha podido cargar la configuración".to_string()
+        }
+    };
+    let description = change(&mut settings);
+    match store.save_guild_settings(guild_id, &settings) {
+        Ok(()) => description,
+        Err(why) => {
+            error!("Cannot save settings for guild {}: {:?}", guild_id, why);
+            "No se ha podido guardar la configuración".to_string()
+        }
+    }
+}
+
+async fn respond(command: &CommandInteraction, ctx: &Context, embed: CreateEmbed) {
+    if let Err(why) = command.create_response(&ctx.http, CreateInteractionResponse::Message(
+        CreateInteractionResponseMessage::new()
+            .embed(embed)
+            .ephemeral(true)
+    )).await {
+        info!("Cannot respond to config command: {}", why);
+    }
+}
+
+/// Changes the price or role of a posted menu, rewriting its message
+async fn edit_menu(ctx: &Context, guild_id: GuildId, options: &[ResolvedOption<'_>]) -> String {
+    let store = ctx.data.read().await.get::<MenuStore>().cloned()
+        .expect("Menu store not initialised");
+    // Both the message id and its link are accepted
+    let reference = option_str(options, "mensaje");
+    let Some(message_id) = reference.trim().rsplit('/').next()
+        .and_then(|id| id.parse::<NonZeroU64>().ok())
+        .map(MessageId::from) else {
+        return format!("**{}** no es un id ni un enlace de mensaje", reference)
+    };
+    let mut menu = match store.menu(message_id) {
+        Ok(Some(menu)) if menu.guild_id == Some(guild_id) => menu,
+        Ok(_) => return "No encuentro ese menú, solo se pueden editar los menús publicados con **/menu**".to_string(),
+        Err(why) => {
+            error!("Cannot load menu {}: {:?}", message_id, why);
+            return "No se ha podido cargar el menú".to_string()
+        }
+    };
+
+    for option in options {
+        match (option.name, &option.value) {
+            ("precio", ResolvedValue::Number(price)) => menu.price = *price,
+            ("rol", ResolvedValue::Role(role)) => menu.crafters = role.id,
+            _ => {}
+        }
+    }
+    if let Err(why) = store.save_menu(&menu) {
+        error!("Cannot save menu {}: {:?}", message_id, why);
+        return "No se ha podido guardar el menú".to_string()
+    }
+    if let Err(why) = menu.channel_id.edit_message(&ctx.http, menu.message_id, EditMessage::new()
+        .content(components::menu_description(&menu.price, menu.crafters))
+    ).await {
+        info!("Cannot edit menu {}: {}", message_id, why);
+        return "He guardado el menú, pero no he podido actualizar su mensaje".to_string()
+    }
+    format!("Menú actualizado: __**{}**__ de oro por pieza para {}", menu.price, Mention::Role(menu.crafters))
+}
@@ -1,38 +1,165 @@
-use regex::Regex;
-use serenity::all::{ComponentInteraction, Context, CreateActionRow, CreateInteractionResponse, CreateInteractionResponseMessage, CreateMessage, CreateModal, Mention, ModalInteraction};
-use serenity::all::ActionRowComponent::InputText;
+use std::str::FromStr;
+use serenity::all::{ComponentInteraction, ComponentInteractionDataKind, Context, CreateActionRow, CreateEmbed, CreateInteractionResponse, CreateInputText, CreateInteractionResponseMessage, CreateMessage, CreateModal, Mention, MessageId, ModalInteraction};
 use tracing::info;
+use seht_core::entities::consumables::{self, ConsumableKind, ConsumableOrder, CATALOG};
 use crate::components;
+use crate::requests::{guild_settings, menu_config, modal_input, modal_quantity, parse_picker_id, post_request, save_order, stored_menu, Reply};
+use crate::requests::error::RequestError;
+use crate::store::{Menu, NewOrder, OrderKind};
 
-pub async fn consumables(interaction: ComponentInteraction, ctx: &Context) {
-    let response = CreateInteractionResponse::Modal(
-        CreateModal::new("consumables_modal", "⚒️ Solicitud de Consumibles ⚒️")
-            .components(vec![CreateActionRow::InputText(components::consumables_modal("consumables"))])
-    );
+const GUIDE: &str = "Elige el tipo de consumible y después lo que quieres pedir, o escríbelo a mano si no está en la lista";
 
-    if let Err(why) = interaction.create_response(&ctx.http, response).await {
-        info!("Cannot respond to consumables request: {}", why)
+/// Opens the consumables picker for the menu, as a message only the user sees
+pub async fn consumables(interaction: ComponentInteraction, ctx: &Context) {
+    let reply = Reply::Component(&interaction);
+    match menu_config(ctx, &interaction.message).await {
+        Ok(menu) => reply.respond(ctx, CreateInteractionResponse::Message(CreateInteractionResponseMessage::new()
+            .content(GUIDE)
+            .components(picker_rows(menu.message_id, None))
+            .ephemeral(true)
+        )).await,
+        Err(why) => reply.report(ctx, why).await
     }
 }
 
+/// Answers to the consumables picker, whose ids carry the menu it was opened from
+pub async fn consumables_picker(interaction: ComponentInteraction, ctx: &Context) {
+    let reply = Reply::Component(&interaction);
+    let Some((action, menu, _)) = parse_picker_id("consumables_", &interaction.data.custom_id) else {
+        info!("Malformed consumables id {}", interaction.data.custom_id);
+        return reply.respond(ctx, CreateInteractionResponse::Acknowledge).await
+    };
+    let values = match &interaction.data.kind {
+        ComponentInteractionDataKind::StringSelect { values } => values.as_slice(),
+        _ => &[]
+    };
 
+    let response = match action {
+        "kind" => match values.first().and_then(|v| ConsumableKind::from_str(v).ok()) {
+            Some(kind) => CreateInteractionResponse::UpdateMessage(CreateInteractionResponseMessage::new()
+                .content(GUIDE)
+                .components(picker_rows(menu, Some(kind)))),
+            None => CreateInteractionResponse::Acknowledge
+        },
+        "pick" => {
+            let picked: Vec<usize> = values.iter()
+                .filter_map(|v| v.parse().ok())
+                .filter(|idx| *idx < CATALOG.len())
+                .collect();
+            let ids = picked.iter().map(|idx| idx.to_string()).collect::<Vec<_>>().join("-");
+            CreateInteractionResponse::Modal(
+                CreateModal::new(format!("consumables_qty_{}_{}", menu, ids), "⚒️ Solicitud de Consumibles ⚒️")
+                    .components(picked.iter()
+                        .map(|idx| CreateActionRow::InputText(quantity_input(*idx)))
+                        .collect())
+            )
+        }
+        "free" => CreateInteractionResponse::Modal(
+            CreateModal::new(format!("consumables_free_{}", menu), "⚒️ Solicitud de Consumibles ⚒️")
+                .components(vec![CreateActionRow::InputText(components::consumables_modal("consumables"))])
+        ),
+        _ => CreateInteractionResponse::Acknowledge
+    };
+    reply.respond(ctx, response).await
+}
 
 pub async fn consumables_modal(interaction: ModalInteraction, ctx: &Context) {
-    if let Err(why) = interaction
-        .create_response(&ctx.http, CreateInteractionResponse::Defer(CreateInteractionResponseMessage::new())).await {
-        info!("Cannot respond to consumables modal: {}", why)
-    } else {
-        if let InputText(input) = interaction
-            .data.components.get(0).unwrap()
-            .components.get(0).unwrap()
-        {
-            let re = Regex::new(r"<@&\d+>").unwrap();
-            let msg = &interaction.message.unwrap();
-            let role = re.captures(&msg.content).unwrap()
-                .get(0).unwrap().as_str();
-            interaction.channel_id.send_message(&ctx.http, CreateMessage::new()
-                .content(format!("{}\n\n__**‼️Peticion de Consumibles para {}‼️**__ \n {}", role, Mention::User(interaction.user.id), input.value.clone().unwrap()))
-            ).await.unwrap();
+    if let Err(why) = submit_consumables(&interaction, ctx).await {
+        Reply::Modal(&interaction).report(ctx, why).await
+    }
+}
+
+async fn submit_consumables(interaction: &ModalInteraction, ctx: &Context) -> Result<(), RequestError> {
+    let reply = Reply::Modal(interaction);
+    // Modals opened straight from the menu before the picker existed carry no menu id
+    if interaction.data.custom_id == "consumables_modal" {
+        let menu = interaction.message.as_deref().ok_or(RequestError::MissingMenu)?;
+        let menu = menu_config(ctx, menu).await?;
+        let details = modal_input(interaction)?.to_string();
+        reply.respond(ctx, CreateInteractionResponse::Acknowledge).await;
+        let (items, unknown) = consumables::parse_consumables(&details);
+        return post_consumables(interaction, ctx, &menu, details, &items, &unknown).await
+    }
+
+    let Some((action, menu, items)) = parse_picker_id("consumables_", &interaction.data.custom_id) else {
+        info!("Malformed consumables id {}", interaction.data.custom_id);
+        return Err(RequestError::MissingInput)
+    };
+    let menu = stored_menu(ctx, menu).await?;
+    let (details, items, unknown) = match action {
+        "qty" => {
+            let items = quantities(interaction, items.unwrap_or_default())?;
+            (items.iter().map(|i| i.to_string()).collect::<Vec<_>>().join("\n"), items, vec![])
         }
+        _ => {
+            let details = modal_input(interaction)?.to_string();
+            let (items, unknown) = consumables::parse_consumables(&details);
+            (details, items, unknown)
+        }
+    };
+    reply.respond(ctx, CreateInteractionResponse::UpdateMessage(CreateInteractionResponseMessage::new()
+        .content("✅ Solicitud de consumibles enviada")
+        .components(vec![])
+    )).await;
+    post_consumables(interaction, ctx, &menu, details, &items, &unknown).await
+}
+
+/// Posts the order with the recipes and bill of the catalog items in it, flagging the lines of
+/// free text orders that are not in the catalog
+async fn post_consumables(interaction: &ModalInteraction, ctx: &Context, menu: &Menu, details: String, items: &[ConsumableOrder], unknown: &[String]) -> Result<(), RequestError> {
+    let settings = guild_settings(ctx, interaction.guild_id).await;
+    let channel_id = settings.channel(OrderKind::Consumables).unwrap_or(interaction.channel_id);
+    let mut embeds: Vec<CreateEmbed> = components::alchemy_recipe_embed(items).into_iter().collect();
+    if settings.show_costs && !items.is_empty() {
+        embeds.push(components::display_consumables_cost(items));
+    }
+    if !unknown.is_empty() {
+        embeds.push(components::unknown_lines_embed(unknown));
+    }
+
+    let title = format!("{} para {}", OrderKind::Consumables, interaction.user.name);
+    let posted = post_request(ctx, &settings, channel_id, &title, interaction.user.id, CreateMessage::new()
+        .content(format!("{}\n\n__**‼️Peticion de Consumibles para {}‼️**__ \n {}", Mention::Role(menu.crafters), Mention::User(interaction.user.id), details))
+        .embeds(embeds)
+    ).await?;
+    save_order(ctx, NewOrder {
+        kind: OrderKind::Consumables,
+        guild_id: interaction.guild_id,
+        channel_id: posted.channel_id,
+        message_id: posted.message_id,
+        requester: interaction.user.id,
+        crafters: menu.crafters,
+        price: settings.price(OrderKind::Consumables),
+        set_name: None,
+        pieces: vec![],
+        details: Some(details),
+        thread: posted.thread,
+    }).await;
+    Ok(())
+}
+
+/// Kind select, then the items of the chosen kind, and the free text fallback
+fn picker_rows(menu: MessageId, kind: Option<ConsumableKind>) -> Vec<CreateActionRow> {
+    let mut rows = vec![CreateActionRow::SelectMenu(components::consumable_kind(&format!("consumables_kind_{}", menu)))];
+    if let Some(kind) = kind {
+        rows.push(CreateActionRow::SelectMenu(components::consumable_select(&format!("consumables_pick_{}", menu), kind)));
     }
-}
\ No newline at end of file
+    rows.push(CreateActionRow::Buttons(vec![components::free_text_button(&format!("consumables_free_{}", menu))]));
+    rows
+}
+
+fn quantity_input(idx: usize) -> CreateInputText {
+    let item = &CATALOG[idx];
+    let batch = item.kind.batch();
+    components::quantity_modal(&idx.to_string(), item.name_es, &format!("Unidades, se fabrican de {} en {}", batch, batch))
+}
+
+/// Catalog items picked, each with the quantity typed in the field named after its catalog index
+fn quantities(interaction: &ModalInteraction, items: &str) -> Result<Vec<ConsumableOrder>, RequestError> {
+    items.split('-')
+        .map(|idx| {
+            let item = idx.parse::<usize>().ok().and_then(|idx| CATALOG.get(idx)).ok_or(RequestError::MissingInput)?;
+            Ok(ConsumableOrder { item, quantity: modal_quantity(interaction, idx)? })
+        })
+        .collect()
+}
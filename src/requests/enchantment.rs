@@ -1,36 +1,192 @@
-use regex::Regex;
-use serenity::all::{ComponentInteraction, Context, CreateActionRow, CreateInteractionResponse, CreateInteractionResponseMessage, CreateMessage, CreateModal, Mention, ModalInteraction};
-use serenity::all::ActionRowComponent::InputText;
+use std::str::FromStr;
+use serenity::all::{ComponentInteraction, ComponentInteractionDataKind, Context, CreateActionRow, CreateEmbed, CreateInputText, CreateInteractionResponse, CreateInteractionResponseMessage, CreateMessage, CreateModal, Mention, MessageId, ModalInteraction};
+use strum::IntoEnumIterator;
 use tracing::info;
+use seht_core::entities::GearQuality;
+use seht_core::entities::level::CraftingLevel;
+use seht_core::entities::glyph::{self, Enchantment, EnchantmentKind, Glyph, GlyphOrder};
 use crate::components;
+use crate::requests::{guild_settings, menu_config, modal_input, modal_quantity, parse_picker_id, post_request, save_order, stored_menu, Reply};
+use crate::requests::error::RequestError;
+use crate::store::{Menu, NewOrder, OrderKind};
 
-pub async fn enchantment(interaction: ComponentInteraction, ctx: &Context) {
-    let response = CreateInteractionResponse::Modal(
-        CreateModal::new("enchantment_modal", "⚒️ Solicitud de Runas ⚒️")
-            .components(vec![CreateActionRow::InputText(components::enchantments_modal("enchantments"))])
-    );
+const GUIDE: &str = "Elige dónde va el glifo, su calidad y después los glifos CP160 que quieres, o escríbelos a mano si los necesitas de otro nivel";
 
-    if let Err(why) = interaction.create_response(&ctx.http, response).await {
-        info!("Cannot respond to enchantments request: {}", why)
+/// Opens the glyph picker for the menu, as a message only the user sees
+pub async fn enchantment(interaction: ComponentInteraction, ctx: &Context) {
+    let reply = Reply::Component(&interaction);
+    match menu_config(ctx, &interaction.message).await {
+        Ok(menu) => reply.respond(ctx, CreateInteractionResponse::Message(CreateInteractionResponseMessage::new()
+            .content(GUIDE)
+            .components(picker_rows(menu.message_id, None, None))
+            .ephemeral(true)
+        )).await,
+        Err(why) => reply.report(ctx, why).await
     }
 }
 
+/// Answers to the glyph picker, whose ids carry the menu it was opened from and the choices made so far
+pub async fn enchantment_picker(interaction: ComponentInteraction, ctx: &Context) {
+    let reply = Reply::Component(&interaction);
+    let Some((action, menu, rest)) = parse_picker_id("enchantments_", &interaction.data.custom_id) else {
+        info!("Malformed enchantments id {}", interaction.data.custom_id);
+        return reply.respond(ctx, CreateInteractionResponse::Acknowledge).await
+    };
+    let values = match &interaction.data.kind {
+        ComponentInteractionDataKind::StringSelect { values } => values.as_slice(),
+        _ => &[]
+    };
+    let choice = values.first().map(String::as_str).unwrap_or_default();
+
+    let response = match action {
+        "kind" => match EnchantmentKind::from_str(choice) {
+            Ok(kind) => update_picker(menu, Some(kind), None),
+            Err(_) => CreateInteractionResponse::Acknowledge
+        },
+        "quality" => match (rest.and_then(nth::<EnchantmentKind>), GearQuality::from_str(choice)) {
+            (Some(kind), Ok(quality)) => update_picker(menu, Some(kind), Some(quality)),
+            _ => CreateInteractionResponse::Acknowledge
+        },
+        "pick" => {
+            let all = Enchantment::all();
+            let picked: Vec<usize> = values.iter()
+                .filter_map(|v| v.parse().ok())
+                .filter(|idx| *idx < all.len())
+                .collect();
+            let ids = picked.iter().map(|idx| idx.to_string()).collect::<Vec<_>>().join("-");
+            CreateInteractionResponse::Modal(
+                CreateModal::new(format!("enchantments_qty_{}_{}_{}", menu, rest.unwrap_or_default(), ids), "⚒️ Solicitud de Runas ⚒️")
+                    .components(picked.iter()
+                        .map(|idx| CreateActionRow::InputText(quantity_input(*idx, &all[*idx])))
+                        .collect())
+            )
+        }
+        "free" => CreateInteractionResponse::Modal(
+            CreateModal::new(format!("enchantments_free_{}", menu), "⚒️ Solicitud de Runas ⚒️")
+                .components(vec![CreateActionRow::InputText(components::enchantments_modal("enchantments"))])
+        ),
+        _ => CreateInteractionResponse::Acknowledge
+    };
+    reply.respond(ctx, response).await
+}
+
 pub async fn enchantment_modal(interaction: ModalInteraction, ctx: &Context) {
-    if let Err(why) = interaction
-        .create_response(&ctx.http, CreateInteractionResponse::Defer(CreateInteractionResponseMessage::new())).await {
-        info!("Cannot respond to enchantments modal: {}", why)
-    } else {
-        if let InputText(input) = interaction
-            .data.components.get(0).unwrap()
-            .components.get(0).unwrap()
-        {
-            let re = Regex::new(r"<@&\d+>").unwrap();
-            let msg = &interaction.message.unwrap();
-            let role = re.captures(&msg.content).unwrap()
-                .get(0).unwrap().as_str();
-            interaction.channel_id.send_message(&ctx.http, CreateMessage::new()
-                .content(format!("{}\n\n__**‼️Peticion de Encantamientos para {}‼️**__ \n {}", role, Mention::User(interaction.user.id), input.value.clone().unwrap()))
-            ).await.unwrap();
+    if let Err(why) = submit_enchantments(&interaction, ctx).await {
+        Reply::Modal(&interaction).report(ctx, why).await
+    }
+}
+
+async fn submit_enchantments(interaction: &ModalInteraction, ctx: &Context) -> Result<(), RequestError> {
+    let reply = Reply::Modal(interaction);
+    // Modals opened straight from the menu before the picker existed carry no menu id
+    if interaction.data.custom_id == "enchantment_modal" {
+        let menu = interaction.message.as_deref().ok_or(RequestError::MissingMenu)?;
+        let menu = menu_config(ctx, menu).await?;
+        let details = modal_input(interaction)?.to_string();
+        reply.respond(ctx, CreateInteractionResponse::Acknowledge).await;
+        let (glyphs, unknown) = glyph::parse_glyphs(&details);
+        return post_enchantments(interaction, ctx, &menu, details, &glyphs, &unknown).await
+    }
+
+    let Some((action, menu, rest)) = parse_picker_id("enchantments_", &interaction.data.custom_id) else {
+        info!("Malformed enchantments id {}", interaction.data.custom_id);
+        return Err(RequestError::MissingInput)
+    };
+    let menu = stored_menu(ctx, menu).await?;
+    let (details, glyphs, unknown) = match action {
+        "qty" => {
+            let glyphs = quantities(interaction, rest.unwrap_or_default())?;
+            (glyphs.iter().map(|g| g.to_string()).collect::<Vec<_>>().join("\n"), glyphs, vec![])
+        }
+        _ => {
+            let details = modal_input(interaction)?.to_string();
+            let (glyphs, unknown) = glyph::parse_glyphs(&details);
+            (details, glyphs, unknown)
         }
+    };
+    reply.respond(ctx, CreateInteractionResponse::UpdateMessage(CreateInteractionResponseMessage::new()
+        .content("✅ Solicitud de encantamientos enviada")
+        .components(vec![])
+    )).await;
+    post_enchantments(interaction, ctx, &menu, details, &glyphs, &unknown).await
+}
+
+/// Posts the order with the rune bill of its glyphs, flagging the lines of free text orders
+/// that are not glyph names
+async fn post_enchantments(interaction: &ModalInteraction, ctx: &Context, menu: &Menu, details: String, glyphs: &[GlyphOrder], unknown: &[String]) -> Result<(), RequestError> {
+    let settings = guild_settings(ctx, interaction.guild_id).await;
+    let channel_id = settings.channel(OrderKind::Enchantments).unwrap_or(interaction.channel_id);
+    let mut embeds: Vec<CreateEmbed> = vec![];
+    if settings.show_costs && !glyphs.is_empty() {
+        embeds.push(components::display_glyphs_cost(glyphs));
     }
-}
\ No newline at end of file
+    if !unknown.is_empty() {
+        embeds.push(components::unknown_lines_embed(unknown));
+    }
+
+    let title = format!("{} para {}", OrderKind::Enchantments, interaction.user.name);
+    let posted = post_request(ctx, &settings, channel_id, &title, interaction.user.id, CreateMessage::new()
+        .content(format!("{}\n\n__**‼️Peticion de Encantamientos para {}‼️**__ \n {}", Mention::Role(menu.crafters), Mention::User(interaction.user.id), details))
+        .embeds(embeds)
+    ).await?;
+    save_order(ctx, NewOrder {
+        kind: OrderKind::Enchantments,
+        guild_id: interaction.guild_id,
+        channel_id: posted.channel_id,
+        message_id: posted.message_id,
+        requester: interaction.user.id,
+        crafters: menu.crafters,
+        price: settings.price(OrderKind::Enchantments),
+        set_name: None,
+        pieces: vec![],
+        details: Some(details),
+        thread: posted.thread,
+    }).await;
+    Ok(())
+}
+
+fn update_picker(menu: MessageId, kind: Option<EnchantmentKind>, quality: Option<GearQuality>) -> CreateInteractionResponse {
+    CreateInteractionResponse::UpdateMessage(CreateInteractionResponseMessage::new()
+        .content(GUIDE)
+        .components(picker_rows(menu, kind, quality)))
+}
+
+/// Where the glyph goes, then its quality and the glyphs of that kind, and the free text fallback.
+/// Later selects carry the earlier choices by their position in the list
+fn picker_rows(menu: MessageId, kind: Option<EnchantmentKind>, quality: Option<GearQuality>) -> Vec<CreateActionRow> {
+    let mut rows = vec![CreateActionRow::SelectMenu(components::enchantment_kind(&format!("enchantments_kind_{}", menu)))];
+    if let Some(kind) = kind {
+        rows.push(CreateActionRow::SelectMenu(components::glyph_quality(&format!("enchantments_quality_{}_{}", menu, position(&kind)))));
+        if let Some(quality) = quality {
+            rows.push(CreateActionRow::SelectMenu(components::glyph_select(&format!("enchantments_pick_{}_{}", menu, position(&quality)), kind)));
+        }
+    }
+    rows.push(CreateActionRow::Buttons(vec![components::free_text_button(&format!("enchantments_free_{}", menu))]));
+    rows
+}
+
+fn quantity_input(idx: usize, enchantment: &Enchantment) -> CreateInputText {
+    components::quantity_modal(&idx.to_string(), &enchantment.to_string(), "Número de glifos")
+}
+
+/// Glyphs picked, given as `<quality>_<enchantment>-<enchantment>...`, each with the quantity
+/// typed in the field named after its position in the list
+fn quantities(interaction: &ModalInteraction, picked: &str) -> Result<Vec<GlyphOrder>, RequestError> {
+    let (quality, items) = picked.split_once('_').ok_or(RequestError::MissingInput)?;
+    let quality = nth::<GearQuality>(quality).ok_or(RequestError::MissingInput)?;
+    let all = Enchantment::all();
+    items.split('-')
+        .map(|idx| {
+            let enchantment = idx.parse::<usize>().ok().and_then(|idx| all.get(idx)).ok_or(RequestError::MissingInput)?;
+            Ok(GlyphOrder { glyph: Glyph::new(enchantment, &quality, CraftingLevel::default()), quantity: modal_quantity(interaction, idx)? })
+        })
+        .collect()
+}
+
+fn position<T: IntoEnumIterator + PartialEq>(value: &T) -> usize {
+    T::iter().position(|v| v == *value).unwrap_or_default()
+}
+
+fn nth<T: IntoEnumIterator>(idx: &str) -> Option<T> {
+    T::iter().nth(idx.parse().ok()?)
+}
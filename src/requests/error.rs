@@ -0,0 +1,60 @@
+use std::fmt;
+use seht_core::entities::MAX_QUANTITY;
+
+/// Reasons a request cannot go on. The message shown to the user is the `Display` text,
+/// while the details are only logged
+#[derive(Debug)]
+pub enum RequestError {
+    /// The menu message does not mention the crafters role
+    MissingCraftersRole,
+    /// The interaction did not come from a menu message, or it was deleted
+    MissingMenu,
+    /// A command or modal arrived without the expected values
+    MissingInput,
+    /// A quantity typed in a modal is not a positive whole number
+    InvalidQuantity(String),
+    /// A value left out of a command has no default in the guild configuration
+    Unconfigured(&'static str),
+    /// The user does not accept direct messages from the bot
+    DirectMessage(Box<serenity::Error>),
+    /// Discord rejected a message sent on behalf of the request
+    Discord(Box<serenity::Error>),
+    Store(anyhow::Error)
+}
+
+impl fmt::Display for RequestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RequestError::MissingCraftersRole => f.write_str("No encuentro el rol de crafteadores en el menú, pide que vuelvan a publicarlo"),
+            RequestError::MissingMenu => f.write_str("No encuentro el menú de la solicitud, pide que vuelvan a publicarlo"),
+            RequestError::MissingInput => f.write_str("No he recibido los datos de la solicitud, inténtalo de nuevo"),
+            RequestError::InvalidQuantity(value) => write!(f, "**{}** no es una cantidad válida, escribe un número entero entre 1 y {}", value, MAX_QUANTITY),
+            RequestError::Unconfigured(setting) => write!(f, "No hay {} configurado, indícalo en el comando o configúralo con **/config**", setting),
+            RequestError::DirectMessage(_) => f.write_str("No puedo enviarte mensajes directos, permite los mensajes de miembros del servidor e inténtalo de nuevo"),
+            RequestError::Discord(_) => f.write_str("No he podido publicar la solicitud en el canal, revisa mis permisos"),
+            RequestError::Store(_) => f.write_str("No se ha podido guardar la solicitud, inténtalo más tarde")
+        }
+    }
+}
+
+impl std::error::Error for RequestError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RequestError::DirectMessage(why) | RequestError::Discord(why) => Some(why.as_ref()),
+            RequestError::Store(why) => Some(why.as_ref()),
+            _ => None
+        }
+    }
+}
+
+impl From<serenity::Error> for RequestError {
+    fn from(why: serenity::Error) -> Self {
+        RequestError::Discord(Box::new(why))
+    }
+}
+
+impl From<anyhow::Error> for RequestError {
+    fn from(why: anyhow::Error) -> Self {
+        RequestError::Store(why)
+    }
+}
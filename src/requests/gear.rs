@@ -1,16 +1,15 @@
-use std::fmt::Display;
-use std::str::FromStr;
-use regex::Regex;
-use serenity::all::{ButtonStyle, ComponentInteraction, ComponentInteractionDataKind, Context, CreateActionRow, CreateButton, CreateInteractionResponse, CreateInteractionResponseMessage, CreateMessage, CreateModal, CreateSelectMenu, Mention, ModalInteraction, User};
-use serenity::all::ActionRowComponent::InputText;
-use tracing::info;
+use serenity::all::{ButtonStyle, CommandInteraction, ComponentInteraction, ComponentInteractionDataKind, Context, CreateActionRow, CreateButton, CreateEmbed, CreateInteractionResponse, CreateInteractionResponseMessage, CreateMessage, CreateModal, GuildId, Mention, Message, ModalInteraction, ReactionType, User};
+use tracing::{error, info};
 use crate::components;
-use crate::components::SetPiece;
-use crate::entities::armour::{Armour, ArmourEnchantments, ArmourParts, ArmourTraits, ArmourWeights};
-use crate::entities::{Gear, GearQuality};
-use crate::entities::jewelry::{Jewelries, Jewelry, JewelryEnchantments, JewelryTraits};
-use crate::entities::weapon::{Weapon, WeaponEnchantments, WeaponKind, WeaponTraits};
-use crate::requests::{await_component_interaction, ok_response};
+use seht_core::piece::SetPiece;
+use seht_core::entities::Gear;
+use crate::requests::{presets, research};
+use crate::requests::{guild_settings, menu_config, modal_input, ok_response, post_request, save_order, Reply};
+use crate::requests::error::RequestError;
+use seht_core::build::BuildSpec;
+use seht_core::entities::sets;
+use seht_core::wizard::{Answer, Field, GearWizard, Step, WizardMode};
+use crate::store::{now, Delivery, GearSession, GuildSettings, NewOrder, OrderKind, OrderStatus, PresetStore, SessionStore};
 
 pub async fn gear(interaction: ComponentInteraction, ctx: &Context) {
     let response = CreateInteractionResponse::Modal(
@@ -23,450 +22,518 @@ pub async fn gear(interaction: ComponentInteraction, ctx: &Context) {
 }
 
 pub async fn gear_research(interaction: ComponentInteraction, ctx: &Context) {
-    if let Err(why) = interaction
-        .create_response(&ctx.http, CreateInteractionResponse::Defer(CreateInteractionResponseMessage::new())).await {
-        info!("Cannot respond to gear request: {}", why)
-    } else {
-        let parts = select_gear_research_options(&interaction.user, ctx).await;
-        let re = Regex::new(r"<@&\d+>").unwrap();
-        let msg = &interaction.message;
-        let role = re.captures(&msg.content).unwrap()
-            .get(0).unwrap().as_str();
-        let material_cost = components::display_research_cost(&parts);
-        if confirm_set("Investigar", &parts, &interaction.user, ctx).await {
-            interaction.channel_id.send_message(&ctx.http, CreateMessage::new()
-                .content(format!("{}\n\n__**‼️Peticion de Investigación para {}‼️**__\n\n", role, Mention::User(interaction.user.id)))
-                .add_embed(components::gear_research_piece_embed(&parts))
-                .add_embed(material_cost)
-            ).await.unwrap();
-        }
+    let reply = Reply::Component(&interaction);
+    if let Err(why) = start_session(ctx, &reply, Some(&interaction.message), interaction.guild_id, &interaction.user, GearWizard::research(), None).await {
+        reply.report(ctx, why).await
     }
 }
 
 pub async fn gear_modal(interaction: ModalInteraction, ctx: &Context) {
-    if let Err(why) = interaction
-        .create_response(&ctx.http, CreateInteractionResponse::Defer(CreateInteractionResponseMessage::new())).await {
-        info!("Cannot respond to gear set modal: {}", why)
-    } else {
-        if let InputText(input) = interaction
-            .data.components.get(0).unwrap()
-            .components.get(0).unwrap()
-        {
-            let input_value = input.value.clone().unwrap();
-            let parts = select_gear_set_options(&input_value, &interaction.user, ctx).await;
-            let re = Regex::new(r"<@&\d+>").unwrap();
-            let msg = &interaction.message.unwrap();
-            let role = re.captures(&msg.content).unwrap()
-                .get(0).unwrap().as_str();
-
-            let material_cost = if confirm_dialog("Este equipo es CP160?", &interaction.user, ctx).await {
-                Some(components::display_cost(&parts))
-            } else { None };
-            if confirm_set(&input_value, &parts, &interaction.user, ctx).await {
-                let mut embeds = vec![components::gear_set_piece_embed(&input_value, &parts)];
-                if let Some(materials) = material_cost {
-                    embeds.push(materials);
-                }
-                interaction.channel_id.send_message(&ctx.http, CreateMessage::new()
-                    .content(format!("{}\n\n__**‼️Peticion de Equipamiento para {}‼️**__\n\n", role, Mention::User(interaction.user.id)))
-                    .embeds(embeds)).await.unwrap();
-            }
-        }
+    let reply = Reply::Modal(&interaction);
+    let input_value = match modal_input(&interaction) {
+        Ok(value) => value,
+        Err(why) => return reply.report(ctx, why).await
+    };
+    let Some(set) = sets::find_set(input_value) else {
+        let suggestions = sets::suggest_sets(input_value);
+        return reply.respond(ctx, CreateInteractionResponse::Message(CreateInteractionResponseMessage::new()
+            .content(components::unknown_set_message(input_value, &suggestions))
+            .ephemeral(true)
+        )).await;
+    };
+    info!("Gear set: {} ({} traits)", set.name_en, set.traits);
+    let notice = components::set_traits_warning(set);
+    if let Err(why) = start_session(ctx, &reply, interaction.message.as_deref(), interaction.guild_id, &interaction.user, GearWizard::set(set.name_es), Some(notice)).await {
+        reply.report(ctx, why).await
     }
 }
 
-async fn confirm_set(name: &str, set: &Vec<SetPiece>, user: &User, ctx: &Context) -> bool {
-    let msg = user.dm(&ctx.http, CreateMessage::new()
-        .content("Esta es la configuración elegida, **esta todo bien?**")
-        .embed(components::gear_result_embed(set, name))
-        .components(vec![
-            CreateActionRow::Buttons(vec![
-                CreateButton::new("confirm_set")
-                    .label("Confirmar")
-                    .style(ButtonStyle::Success)
-            ])
-        ])
-    ).await.unwrap();
-
-    let interaction = await_component_interaction(msg, ctx).await.unwrap();
-    ok_response(&interaction, ctx).await;
-
-    interaction.data.custom_id == "confirm_set"
-}
-
-async fn select_gear_research_options(user: &User, ctx: &Context) -> Vec<SetPiece> {
-    let selected_parts = select_parts("Investigar", user, ctx).await;
-
-    let (weapons, armour, jewelry) = parse_gear_parts(selected_parts);
-
-    let mut set_parts: Vec<SetPiece> = Vec::new();
-    set_parts.append(&mut process_weapons_research(weapons, user, ctx).await);
-    set_parts.append(&mut process_armour_research(armour, user, ctx).await);
-    set_parts.append(&mut process_jewelry_research(jewelry, user, ctx).await);
-    set_parts
-}
-
-async fn select_gear_set_options(set: &str, user: &User, ctx: &Context) -> Vec<SetPiece> {
-    info!("Gear set: {}", set);
-    let selected_parts = select_parts(set, user, ctx).await;
-
-    let (weapons, armour, jewelry) = parse_gear_parts(selected_parts);
-
-    let with_enchantments = confirm_dialog("Quieres tambien encantamientos para el set?", user, ctx).await;
-    let mut set_parts: Vec<SetPiece> = Vec::new();
-    set_parts.append(&mut process_weapons(weapons, user, ctx, with_enchantments).await);
-    set_parts.append(&mut process_armour(armour, user, ctx, with_enchantments).await);
-    set_parts.append(&mut process_jewelry(jewelry, user, ctx, with_enchantments).await);
-    set_parts
-}
-
-fn parse_gear_parts(parts: Vec<Gear>) -> (Vec<WeaponKind>, Vec<ArmourParts>, Vec<Jewelries>) {
-    let weapons: Vec<WeaponKind> = parts.iter()
-        .filter_map(|g| if let Gear::Weapon(w) = g {Some(w.clone())} else {None})
-        .collect();
-    let armour: Vec<ArmourParts> = parts.iter()
-        .filter_map(|g| if let Gear::Armour(a) = g {Some(a.clone())} else {None})
-        .collect();
-    let jewelry: Vec<Jewelries> = parts.iter()
-        .filter_map(|g| if let Gear::Jewelry(j) = g {Some(j.clone())} else {None})
-        .collect();
-
-    (weapons, armour, jewelry)
-}
-
-async fn process_weapons_research(weapons: Vec<WeaponKind>, user: &User, ctx: &Context) -> Vec<SetPiece> {
-    let mut processed_weapons: Vec<SetPiece> = Vec::new();
-    for weapon in weapons {
-        let piece = SetPiece::Weapon(select_weapon_feats(&weapon, user, ctx, false, false).await);
-        show_piece(&piece, user, ctx).await;
-        processed_weapons.push(piece);
+pub async fn gear_import(interaction: ComponentInteraction, ctx: &Context) {
+    let response = CreateInteractionResponse::Modal(
+        CreateModal::new("gear_import_modal", "📋 Pegar build 📋")
+            .components(vec![CreateActionRow::InputText(components::gear_build_modal("gear_build"))])
+    );
+    if let Err(why) = interaction.create_response(&ctx.http, response).await {
+        info!("Cannot respond to gear import: {}", why)
     }
-
-    processed_weapons
 }
 
-async fn process_weapons(weapons: Vec<WeaponKind>, user: &User, ctx: &Context, with_enchantments: bool) -> Vec<SetPiece> {
-    let mut processed_weapons: Vec<SetPiece> = Vec::new();
-    for weapon in weapons {
-        match weapon {
-            WeaponKind::OneHanded(_) => {
-                let question = format!("Has pedido una __**{}**__ de una mano. Quieres otra para ir con armas duales?", &weapon);
-                if confirm_dialog(&question, user, ctx).await {
-                    let piece = SetPiece::Weapon(select_weapon_feats(&weapon, user, ctx, with_enchantments, true).await);
-                    show_piece(&piece, user, ctx).await;
-                    processed_weapons.push(piece);
+pub async fn gear_import_modal(interaction: ModalInteraction, ctx: &Context) {
+    let reply = Reply::Modal(&interaction);
+    let input = match modal_input(&interaction) {
+        Ok(input) => input,
+        Err(why) => return reply.report(ctx, why).await
+    };
+
+    let spec = match BuildSpec::parse(input) {
+        Ok(spec) if spec.pieces.is_empty() => Err("La build no tiene piezas".to_string()),
+        Ok(spec) => Ok(spec),
+        Err(why) => Err(why)
+    };
+    let content = match spec {
+        Ok(spec) => match sets::find_set(&spec.set_name) {
+            Some(set) => {
+                let notice = components::set_traits_warning(set);
+                let spec = BuildSpec::new(set.name_es, spec.pieces);
+                if let Err(why) = start_session(ctx, &reply, interaction.message.as_deref(), interaction.guild_id, &interaction.user, GearWizard::build(spec), Some(notice)).await {
+                    reply.report(ctx, why).await
                 }
-                let piece = SetPiece::Weapon(select_weapon_feats(&weapon, user, ctx, with_enchantments, true).await);
-                show_piece(&piece, user, ctx).await;
-                processed_weapons.push(piece);
-            },
-            WeaponKind::TwoHanded(_) => {
-                let piece = SetPiece::Weapon(select_weapon_feats(&weapon, user, ctx, with_enchantments, true).await);
-                show_piece(&piece, user, ctx).await;
-                processed_weapons.push(piece);
+                return
             }
-        };
-    }
-
-    processed_weapons
+            None => components::unknown_set_message(&spec.set_name, &sets::suggest_sets(&spec.set_name))
+        },
+        Err(why) => format!("No he podido leer la build:\n{}", why)
+    };
+
+    reply.respond(ctx, CreateInteractionResponse::Message(CreateInteractionResponseMessage::new()
+        .content(content)
+        .ephemeral(true)
+    )).await;
 }
 
-async fn select_weapon_feats(weapon: &WeaponKind, user: &User, ctx: &Context, with_enchantments: bool, with_quality: bool) -> Weapon {
-    let selected_trait = select_weapon_trait(&weapon, user, ctx).await;
-
-    Weapon {
-        kind: weapon.clone(),
-        weapon_trait: selected_trait,
-        enchantment: if with_enchantments {Some(select_weapon_enchantment(&weapon, user, ctx).await)} else {None},
-        quality: if with_quality {select_quality(&weapon, user, ctx).await} else {GearQuality::White},
+pub async fn gear_presets(interaction: ComponentInteraction, ctx: &Context) {
+    let Some(guild_id) = interaction.guild_id else {
+        return ok_response(&interaction, ctx).await;
+    };
+    let store = ctx.data.read().await.get::<PresetStore>().cloned()
+        .expect("Preset store not initialised");
+    let reply = Reply::Component(&interaction);
+
+    let started = match store.presets(guild_id, interaction.user.id) {
+        Ok(saved) if saved.is_empty() => {
+            return reply.respond(ctx, CreateInteractionResponse::Message(CreateInteractionResponseMessage::new()
+                .embed(components::preset_list_embed(&saved))
+                .ephemeral(true)
+            )).await;
+        }
+        Ok(_) => start_session(ctx, &reply, Some(&interaction.message), interaction.guild_id, &interaction.user, GearWizard::preset(), None).await,
+        Err(why) => Err(why.into())
+    };
+    if let Err(why) = started {
+        reply.report(ctx, why).await
     }
 }
 
-async fn process_jewelry_research(jewelries: Vec<Jewelries>, user: &User, ctx: &Context) -> Vec<SetPiece> {
-    let mut processed_jewelry: Vec<SetPiece> = Vec::new();
-
-    for jewelry in jewelries {
-        let piece = SetPiece::Jewelry(Jewelry {
-            kind: jewelry.clone(),
-            jewelry_trait: select_jewelry_trait(&jewelry, user, ctx).await,
-            enchantment: None,
-            quality: GearQuality::White
-        });
-        show_piece(&piece, user, ctx).await;
-        processed_jewelry.push(piece);
+/// Answers to the steps of a gear request, whose ids carry the turn they were sent on
+pub async fn gear_wizard(interaction: ComponentInteraction, ctx: &Context) {
+    let reply = Reply::Component(&interaction);
+    if let Err(why) = answer_step(&interaction, &reply, ctx).await {
+        reply.report(ctx, why).await
     }
-
-    processed_jewelry
 }
 
-async fn process_jewelry(jewelries: Vec<Jewelries>, user: &User, ctx: &Context, with_enchantments: bool) -> Vec<SetPiece> {
-    let mut processed_jewelry: Vec<SetPiece> = Vec::new();
-
-    let mut jewelries = jewelries.clone();
-    if jewelries.contains(&Jewelries::Ring) {
-        jewelries.push(Jewelries::Ring);
+async fn answer_step(interaction: &ComponentInteraction, reply: &Reply<'_>, ctx: &Context) -> Result<(), RequestError> {
+    let store = ctx.data.read().await.get::<SessionStore>().cloned()
+        .expect("Session store not initialised");
+    let user = &interaction.user;
+    let Some((turn, action)) = parse_wizard_id(&interaction.data.custom_id) else {
+        info!("Malformed wizard id {}", interaction.data.custom_id);
+        reply.respond(ctx, CreateInteractionResponse::Acknowledge).await;
+        return Ok(())
+    };
+    let Some(mut session) = store.session(user.id)?.filter(|session| session.wizard.turn == turn) else {
+        info!("Ignoring answer from {} to a step no longer in progress", user.id);
+        reply.respond(ctx, CreateInteractionResponse::Acknowledge).await;
+        return Ok(())
+    };
+    if guild_settings(ctx, session.guild_id).await.expired(session.updated_at) {
+        return match store.take_session(user.id, turn)? {
+            Some(session) => close_session(ctx, &session, user, reply, "La solicitud ha caducado por inactividad, puedes empezar otra desde el menú".to_string()).await,
+            None => {
+                reply.respond(ctx, CreateInteractionResponse::Acknowledge).await;
+                Ok(())
+            }
+        }
     }
 
-    let mut default_trait: Option<JewelryTraits> = None;
-    let mut default_enchantment: Option<JewelryEnchantments> = None;
-    let mut default_quality: Option<GearQuality> = None;
-
-    if jewelries.len() > 1 {
-        let sample = jewelries.pop().unwrap();
-        let feats = select_jewelry_feats(&sample, user, ctx, with_enchantments).await;
-        let question = format!("Aplicar __**{}**__ al resto de __**Joyeria**__?", &feats.jewelry_trait);
-        if confirm_dialog(&question, user, ctx).await {
-            default_trait = Some(feats.jewelry_trait.clone());
-        }
-        if with_enchantments {
-            let question = format!("Aplicar __**{}**__ al resto de __**Joyeria**__?", &feats.enchantment.clone().unwrap());
-            if confirm_dialog(&question, user, ctx).await {
-                default_enchantment = Some(feats.enchantment.clone().unwrap());
+    let answer = match (&interaction.data.kind, action) {
+        (ComponentInteractionDataKind::StringSelect { values }, Some("edit")) => match values.first().and_then(|v| v.parse().ok()) {
+            Some(index) => Answer::Edit(index),
+            None => {
+                reply.respond(ctx, CreateInteractionResponse::Acknowledge).await;
+                return Ok(())
+            }
+        },
+        (ComponentInteractionDataKind::StringSelect { values }, _) => Answer::Choice(values.clone()),
+        (_, Some("no")) => Answer::No,
+        (_, Some("back")) => Answer::Back,
+        (_, Some("skip")) => Answer::Skip,
+        (_, _) => Answer::Yes
+    };
+    match (&session.wizard.step, action, &answer) {
+        (_, Some("cancel"), _) => match store.take_session(user.id, turn)? {
+            Some(session) => close_session(ctx, &session, user, reply, "Solicitud cancelada, puedes empezar otra desde el menú".to_string()).await,
+            None => {
+                reply.respond(ctx, CreateInteractionResponse::Acknowledge).await;
+                Ok(())
             }
+        },
+        (Step::Confirm, Some("save"), _) => {
+            ask_preset_name(interaction, ctx, turn).await;
+            Ok(())
         }
-        let question = format!("Aplicar __**{}**__ al resto de __**Joyeria**__?", &feats.quality);
-        if confirm_dialog(&question, user, ctx).await {
-            default_quality = Some(feats.quality.clone());
+        (Step::Confirm, None, Answer::Yes) => finish_session(ctx, turn, user, reply, None).await,
+        (Step::Preset, _, Answer::Choice(values)) => {
+            let Some(preset) = load_preset(ctx, &session, values.first().map(String::as_str).unwrap_or_default()).await? else {
+                reply.respond(ctx, CreateInteractionResponse::Acknowledge).await;
+                return Ok(())
+            };
+            let notice = sets::find_set(&preset.set_name).map(components::set_traits_warning);
+            session.wizard.load_build(preset);
+            advance_session(ctx, &session, user, reply, Some(turn), notice, &[]).await
+        }
+        _ => {
+            let finished = session.wizard.pieces.len();
+            match session.wizard.answer(answer) {
+                Ok(()) => advance_session(ctx, &session, user, reply, Some(turn), None, session.wizard.pieces.get(finished..).unwrap_or_default()).await,
+                Err(why) => match session.delivery {
+                    Delivery::Dm => {
+                        reply.respond(ctx, CreateInteractionResponse::Acknowledge).await;
+                        dm(ctx, user, CreateMessage::new().content(why)).await
+                    }
+                    Delivery::Ephemeral => advance_session(ctx, &session, user, reply, Some(turn), Some(format!("⚠️ {}", why)), &[]).await
+                }
+            }
         }
-        let piece = SetPiece::Jewelry(feats);
-        show_piece(&piece, user, ctx).await;
-        processed_jewelry.push(piece);
-    }
-
-    for jewelry in jewelries {
-        let selected_trait = if let Some(default_trait) = &default_trait {default_trait.clone()} else {select_jewelry_trait(&jewelry, user, ctx).await};
-        let quality = if let Some(default_quality) = &default_quality {default_quality.clone()} else {select_quality(&jewelry, user, ctx).await};
-
-        let piece = SetPiece::Jewelry(Jewelry {
-            kind: jewelry.clone(),
-            jewelry_trait: selected_trait,
-            enchantment: if with_enchantments {
-                Some(if let Some(default_enchantment) = &default_enchantment {default_enchantment.clone()} else {select_jewelry_enchantment(&jewelry, user, ctx).await})
-            } else { None },
-            quality,
-        });
-        show_piece(&piece, user, ctx).await;
-        processed_jewelry.push(piece);
     }
-
-    processed_jewelry
 }
 
-async fn select_jewelry_feats(jewelry: &Jewelries, user: &User, ctx: &Context, with_enchantments: bool) -> Jewelry {
-    let selected_trait = select_jewelry_trait(&jewelry, user, ctx).await;
-    let quality = select_quality(&jewelry, user, ctx).await;
-
-    Jewelry {
-        kind: jewelry.clone(),
-        jewelry_trait: selected_trait,
-        enchantment: if with_enchantments {Some(select_jewelry_enchantment(&jewelry, user, ctx).await)} else { None },
-        quality,
+/// Name given to a build saved from the confirmation step
+pub async fn gear_wizard_modal(interaction: ModalInteraction, ctx: &Context) {
+    let reply = Reply::Modal(&interaction);
+    if let Err(why) = name_preset(&interaction, &reply, ctx).await {
+        reply.report(ctx, why).await
     }
 }
 
-async fn process_armour_research(armour_parts: Vec<ArmourParts>, user: &User, ctx: &Context) -> Vec<SetPiece> {
-    let mut processed_armour: Vec<SetPiece> = Vec::new();
-
-    for armour in armour_parts {
-        let selected_trait = select_armour_trait(&armour, user, ctx).await;
-        let weight = select_weight(&armour, user, ctx).await;
-
-        let piece = SetPiece::Armour(Armour {
-            kind: armour.clone(),
-            armour_trait: selected_trait,
-            enchantment: None,
-            weight,
-            quality: GearQuality::White,
-        });
-        show_piece(&piece, user, ctx).await;
-        processed_armour.push(piece);
-    }
+async fn name_preset(interaction: &ModalInteraction, reply: &Reply<'_>, ctx: &Context) -> Result<(), RequestError> {
+    let Some((turn, _)) = parse_wizard_id(&interaction.data.custom_id) else {
+        reply.respond(ctx, CreateInteractionResponse::Acknowledge).await;
+        return Ok(())
+    };
+    let name = modal_input(interaction).ok()
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(str::to_string);
+    finish_session(ctx, turn, &interaction.user, reply, name).await
+}
 
-    processed_armour
+/// Content of a step, shown either through DM or in the ephemeral message of the request
+#[derive(Default)]
+struct Prompt {
+    content: String,
+    embeds: Vec<CreateEmbed>,
+    rows: Vec<CreateActionRow>
 }
 
-async fn process_armour(armour_parts: Vec<ArmourParts>, user: &User, ctx: &Context, with_enchantments: bool) -> Vec<SetPiece> {
-    let mut processed_armour: Vec<SetPiece> = Vec::new();
-
-    let mut default_trait: Option<ArmourTraits> = None;
-    let mut default_enchantment: Option<ArmourEnchantments> = None;
-    let mut default_weight: Option<ArmourWeights> = None;
-    let mut default_quality: Option<GearQuality> = None;
-
-    let mut armour_parts = armour_parts.clone();
-    if armour_parts.len() > 1 {
-        let sample = armour_parts.pop().unwrap();
-        let feats = select_armour_feats(&sample, user, ctx, with_enchantments).await;
-        let question = format!("Aplicar __**{}**__ al resto de la __**Armadura**__?", &feats.armour_trait);
-        if confirm_dialog(&question, user, ctx).await {
-            default_trait = Some(feats.armour_trait.clone());
-        }
-        if with_enchantments {
-            let question = format!("Aplicar __**{}**__ al resto de la __**Armadura**__?", &feats.enchantment.clone().unwrap());
-            if confirm_dialog(&question, user, ctx).await {
-                default_enchantment = Some(feats.enchantment.clone().unwrap());
-            }
-        }
-        let question = format!("Aplicar __**{}**__ al resto de la __**Armadura**__?", &feats.weight);
-        if confirm_dialog(&question, user, ctx).await {
-            default_weight = Some(feats.weight.clone());
-        }
-        let question = format!("Aplicar __**{}**__ al resto de la __**Armadura**__?", &feats.quality);
-        if confirm_dialog(&question, user, ctx).await {
-            default_quality = Some(feats.quality.clone());
-        }
-        let piece = SetPiece::Armour(feats);
-        show_piece(&piece, user, ctx).await;
-        processed_armour.push(piece);
+impl Prompt {
+    fn message(self) -> CreateMessage {
+        CreateMessage::new()
+            .content(self.content)
+            .embeds(self.embeds)
+            .components(self.rows)
     }
 
-    for armour in armour_parts {
-        let selected_trait = if let Some(default_trait) = &default_trait {default_trait.clone()} else {select_armour_trait(&armour, user, ctx).await};
-        let weight = if let Some(default_weight) = &default_weight {default_weight.clone()} else {select_weight(&armour, user, ctx).await};
-        let quality = if let Some(default_quality) = &default_quality {default_quality.clone()} else {select_quality(&armour, user, ctx).await};
-
-        let piece = SetPiece::Armour(Armour {
-            kind: armour.clone(),
-            armour_trait: selected_trait,
-            enchantment: if with_enchantments {
-                Some(if let Some(default_enchantment) = &default_enchantment {default_enchantment.clone()} else {select_armour_enchantment(&armour, user, ctx).await})
-            } else { None },
-            weight,
-            quality,
-        });
-        show_piece(&piece, user, ctx).await;
-        processed_armour.push(piece);
+    fn response(self) -> CreateInteractionResponseMessage {
+        CreateInteractionResponseMessage::new()
+            .content(self.content)
+            .embeds(self.embeds)
+            .components(self.rows)
     }
+}
 
-    processed_armour
+async fn start_session(ctx: &Context, reply: &Reply<'_>, menu: Option<&Message>, guild_id: Option<GuildId>, user: &User, wizard: GearWizard, notice: Option<String>) -> Result<(), RequestError> {
+    let menu = menu_config(ctx, menu.ok_or(RequestError::MissingMenu)?).await?;
+    let settings = guild_settings(ctx, guild_id).await;
+    // Prices set for the guild win over the one given to the menu
+    let kind = match wizard.mode {
+        WizardMode::Research => OrderKind::Research,
+        WizardMode::Set => OrderKind::Gear
+    };
+    let price = settings.price(kind).unwrap_or(menu.price);
+    let session = GearSession {
+        user: user.id,
+        guild_id,
+        channel_id: menu.channel_id,
+        crafters: menu.crafters,
+        price: Some(price),
+        // DMs are the only choice outside a guild
+        delivery: if guild_id.is_some() { settings.delivery } else { Delivery::Dm },
+        wizard,
+        updated_at: now()
+    };
+    advance_session(ctx, &session, user, reply, None, notice, &[]).await
 }
 
-async fn select_armour_feats(armour: &ArmourParts, user: &User, ctx: &Context, with_enchantments: bool) -> Armour {
-    let selected_trait = select_armour_trait(&armour, user, ctx).await;
-    let quality = select_quality(&armour, user, ctx).await;
-    let weight = select_weight(&armour, user, ctx).await;
-
-    Armour {
-        kind: armour.clone(),
-        armour_trait: selected_trait,
-        enchantment: if with_enchantments {Some(select_armour_enchantment(&armour, user, ctx).await)} else { None },
-        weight,
-        quality,
+/// Starts a request from a saved build picked with **/builds pedir**, asking for quality and glyph
+/// changes before confirming it
+pub async fn order_preset(command: &CommandInteraction, ctx: &Context, guild_id: GuildId, spec: BuildSpec) {
+    let reply = Reply::Command(command);
+    if let Err(why) = start_preset(ctx, &reply, command, guild_id, spec).await {
+        reply.report(ctx, why).await
     }
 }
 
-async fn select_parts(set: &str, user: &User, ctx: &Context) -> Vec<Gear> {
-    let msg = user.dm(&ctx.http, CreateMessage::new()
-        .embed(components::gear_set_embed(set))
-        .components(vec![CreateActionRow::SelectMenu(components::gear_set_parts("gear_set_parts"))])
-    ).await.unwrap();
-
-    let interaction = await_component_interaction(msg, ctx).await.unwrap();
-    ok_response(&interaction, ctx).await;
-
-    if let ComponentInteractionDataKind::StringSelect {values} = interaction.data.kind {
-        values.iter()
-            .filter_map(|f| Gear::from_str(f).ok())
-            .collect()
-    } else { vec![] }
+async fn start_preset(ctx: &Context, reply: &Reply<'_>, command: &CommandInteraction, guild_id: GuildId, spec: BuildSpec) -> Result<(), RequestError> {
+    // There is no menu behind a command, so everything comes from the guild configuration
+    let settings = guild_settings(ctx, Some(guild_id)).await;
+    let crafters = settings.crafters.ok_or(RequestError::Unconfigured("rol de crafteadores"))?;
+    let notice = sets::find_set(&spec.set_name).map(components::set_traits_warning);
+    let mut wizard = GearWizard::preset();
+    wizard.load_build(spec);
+    let session = GearSession {
+        user: command.user.id,
+        guild_id: Some(guild_id),
+        channel_id: settings.channel(OrderKind::Gear).unwrap_or(command.channel_id),
+        crafters,
+        price: settings.price(OrderKind::Gear),
+        delivery: settings.delivery,
+        wizard,
+        updated_at: now()
+    };
+    advance_session(ctx, &session, &command.user, reply, None, notice, &[]).await
 }
 
-async fn select_weapon_enchantment(weapon: &WeaponKind, user: &User, ctx: &Context) -> WeaponEnchantments {
-    let menu = components::enchantments::gear_weapon_enchantments("weapon_enchantment");
-    let selected_enchantment = select_feat(user, ctx, "encantamiento", weapon, menu).await;
+/// Stores the session and shows the prompt of its current step, along with the pieces just finished.
+/// Answers carry the `answered` turn, and are dropped when another answer to it was stored first.
+/// Ephemeral sessions open their message when the session starts and update it in place afterwards
+async fn advance_session(ctx: &Context, session: &GearSession, user: &User, reply: &Reply<'_>, answered: Option<u32>, notice: Option<String>, finished: &[SetPiece]) -> Result<(), RequestError> {
+    let store = ctx.data.read().await.get::<SessionStore>().cloned()
+        .expect("Session store not initialised");
+    if !store.save_session(session, answered)? {
+        info!("Ignoring answer from {} to turn {:?} already answered", user.id, answered);
+        reply.respond(ctx, CreateInteractionResponse::Acknowledge).await;
+        return Ok(())
+    }
 
-    WeaponEnchantments::from_str(&selected_enchantment).unwrap()
+    let mut shown = prompt(ctx, session).await;
+    shown.embeds.splice(0..0, finished.iter().map(components::gear_piece_embed));
+    if let Some(notice) = notice {
+        shown.content = format!("{}\n\n{}", notice, shown.content);
+    }
+    match session.delivery {
+        Delivery::Dm => {
+            reply.respond(ctx, CreateInteractionResponse::Acknowledge).await;
+            return dm(ctx, user, shown.message()).await
+        }
+        Delivery::Ephemeral if answered.is_none() => reply.respond(ctx, CreateInteractionResponse::Message(shown.response().ephemeral(true))).await,
+        Delivery::Ephemeral => reply.respond(ctx, CreateInteractionResponse::UpdateMessage(shown.response())).await
+    }
+    Ok(())
 }
 
-async fn select_armour_enchantment(armour: &ArmourParts, user: &User, ctx: &Context) -> ArmourEnchantments {
-    let menu = components::enchantments::gear_armour_enchantments("armour_enchantment");
-    let selected_enchantment = select_feat(user, ctx, "encantamiento", armour, menu).await;
-
-    ArmourEnchantments::from_str(&selected_enchantment).unwrap()
+/// Leaves a last message for a session that will not take more answers
+async fn close_session(ctx: &Context, session: &GearSession, user: &User, reply: &Reply<'_>, content: String) -> Result<(), RequestError> {
+    let shown = Prompt { content, ..Default::default() };
+    match session.delivery {
+        Delivery::Dm => {
+            reply.respond(ctx, CreateInteractionResponse::Acknowledge).await;
+            return dm(ctx, user, shown.message()).await
+        }
+        Delivery::Ephemeral => reply.respond(ctx, CreateInteractionResponse::UpdateMessage(shown.response())).await
+    }
+    Ok(())
 }
 
-async fn select_jewelry_enchantment(jewelry: &Jewelries, user: &User, ctx: &Context) -> JewelryEnchantments {
-    let menu = components::enchantments::gear_jewelry_enchantments("jewelry_enchantment");
-    let selected_enchantment = select_feat(user, ctx, "encantamiento", jewelry, menu).await;
+/// Content and components of the current step
+async fn prompt(ctx: &Context, session: &GearSession) -> Prompt {
+    let wizard = &session.wizard;
+    let id = format!("wizard_{}", wizard.turn);
+    let select = |content: String, menu| (content, Vec::new(), CreateActionRow::SelectMenu(menu));
+    let question = |content: String| (content, Vec::new(), components::confirmation_row(&id));
+
+    let (content, embeds, row) = match &wizard.step {
+        Step::Parts => (
+            String::new(),
+            vec![components::gear_set_embed(&wizard.set_name)],
+            CreateActionRow::SelectMenu(components::gear_set_parts(&id))),
+        Step::Tier => select(
+            format!("Selecciona **nivel** para __**{}**__", wizard.set_name),
+            components::crafting_tier(&id)),
+        Step::Level(tier) => select(
+            format!("Selecciona **nivel** para __**{}**__", tier),
+            components::crafting_level(&id, &tier.levels())),
+        Step::Enchantments => question("Quieres tambien encantamientos para el set?".to_string()),
+        Step::DualWield(weapon) => question(format!("Has pedido una __**{}**__ de una mano. Quieres otra para ir con armas duales?", weapon)),
+        Step::Feat(item, Field::Weight) => select(
+            format!("Selecciona **peso** de la pieza: __**{}**__", item),
+            components::armor_weight(&id)),
+        Step::Feat(item, field) => {
+            let (feat, menu) = match (field, item) {
+                (Field::Trait, Gear::Weapon(_)) => ("rasgo", components::traits::gear_weapon_traits(&id)),
+                (Field::Trait, Gear::Armour(_)) => ("rasgo", components::traits::gear_armour_traits(&id)),
+                (Field::Trait, Gear::Jewelry(_)) => ("rasgo", components::traits::gear_jewelry_traits(&id)),
+                (Field::Enchantment, Gear::Weapon(_)) => ("encantamiento", components::enchantments::gear_weapon_enchantments(&id)),
+                (Field::Enchantment, Gear::Armour(_)) => ("encantamiento", components::enchantments::gear_armour_enchantments(&id)),
+                (Field::Enchantment, Gear::Jewelry(_)) => ("encantamiento", components::enchantments::gear_jewelry_enchantments(&id)),
+                _ => ("calidad", components::gear_quality(&id))
+            };
+            select(format!("Selecciona **{}** para __**{}**__", feat, item), menu)
+        }
+        Step::ApplyToRest(Gear::Jewelry(_), _, value) => question(format!("Aplicar __**{}**__ al resto de __**Joyeria**__?", value)),
+        Step::ApplyToRest(_, _, value) => question(format!("Aplicar __**{}**__ al resto de la __**Armadura**__?", value)),
+        Step::Preset => {
+            let store = ctx.data.read().await.get::<PresetStore>().cloned()
+                .expect("Preset store not initialised");
+            let saved = session.guild_id
+                .map(|guild_id| store.presets(guild_id, session.user).unwrap_or_default())
+                .unwrap_or_default();
+            select("Selecciona **la build** para __**volver a pedir**__".to_string(), components::preset_select(&id, &saved))
+        }
+        Step::OverrideQuality => select(
+            format!("Selecciona **la calidad** para __**{}**__", wizard.set_name),
+            components::preset_quality(&id)),
+        Step::KeepGlyphs => question("¿Quieres mantener los **glifos** de la build?".to_string()),
+        Step::Confirm => {
+            let mut buttons = vec![
+                CreateButton::new(&id)
+                    .label("Confirmar")
+                    .style(ButtonStyle::Success)
+            ];
+            if wizard.mode == WizardMode::Set {
+                buttons.push(CreateButton::new(format!("{}_save", id))
+                    .emoji(ReactionType::Unicode("💾".to_string()))
+                    .label("Confirmar y guardar")
+                    .style(ButtonStyle::Primary));
+            }
+            ("Esta es la configuración elegida, **esta todo bien?**".to_string(),
+             vec![components::gear_result_embed(&wizard.pieces, &wizard.set_name)],
+             CreateActionRow::Buttons(buttons))
+        }
+    };
 
-    JewelryEnchantments::from_str(&selected_enchantment).unwrap()
+    let mut rows = vec![row, components::wizard_navigation_row(&id, wizard.can_go_back(), wizard.can_skip())];
+    if wizard.can_edit() {
+        rows.push(CreateActionRow::SelectMenu(components::piece_edit_select(&id, &wizard.pieces)));
+    }
+    Prompt { content, embeds, rows }
 }
 
-async fn select_weapon_trait(weapon: &WeaponKind, user: &User, ctx: &Context) -> WeaponTraits {
-    let menu = components::traits::gear_weapon_traits("weapon_trait");
-    let selected_trait = select_feat(user, ctx, "rasgo", weapon, menu).await;
-
-    WeaponTraits::from_str(&selected_trait).unwrap()
+async fn ask_preset_name(interaction: &ComponentInteraction, ctx: &Context, turn: u32) {
+    if let Err(why) = interaction.create_response(&ctx.http, CreateInteractionResponse::Modal(
+        CreateModal::new(format!("wizard_{}_name", turn), "💾 Guardar build 💾")
+            .components(vec![CreateActionRow::InputText(components::preset_name_modal("preset_name"))])
+    )).await {
+        info!("Cannot ask for preset name: {}", why)
+    }
 }
 
-async fn select_armour_trait(armour: &ArmourParts, user: &User, ctx: &Context) -> ArmourTraits {
-    let menu = components::traits::gear_armour_traits("armour_trait");
-    let selected_trait = select_feat(user, ctx, "rasgo", armour, menu).await;
-
-    ArmourTraits::from_str(&selected_trait).unwrap()
+async fn load_preset(ctx: &Context, session: &GearSession, name: &str) -> Result<Option<BuildSpec>, RequestError> {
+    let store = ctx.data.read().await.get::<PresetStore>().cloned()
+        .expect("Preset store not initialised");
+    let Some(guild_id) = session.guild_id else { return Ok(None) };
+    Ok(store.get_preset(guild_id, session.user, name)?.map(|p| p.spec))
 }
 
-async fn select_jewelry_trait(jewelry: &Jewelries, user: &User, ctx: &Context) -> JewelryTraits {
-    let menu = components::traits::gear_jewelry_traits("jewelry_trait");
-    let selected_trait = select_feat(user, ctx, "rasgo", jewelry, menu).await;
+/// Posts the request confirmed on `turn`, saving it as a build when a name was given. The session
+/// is taken first so a confirmation sent twice posts it once, and is put back if posting fails so
+/// it can be confirmed again
+async fn finish_session(ctx: &Context, turn: u32, user: &User, reply: &Reply<'_>, preset: Option<String>) -> Result<(), RequestError> {
+    let store = ctx.data.read().await.get::<SessionStore>().cloned()
+        .expect("Session store not initialised");
+    let Some(session) = store.take_session(user.id, turn)? else {
+        info!("Ignoring confirmation from {} for a finished request", user.id);
+        reply.respond(ctx, CreateInteractionResponse::Acknowledge).await;
+        return Ok(())
+    };
+
+    let settings = guild_settings(ctx, session.guild_id).await;
+    let posted = match session.wizard.mode {
+        WizardMode::Research => post_research_order(ctx, &session, user, &settings).await,
+        WizardMode::Set => post_gear_order(ctx, &session, user, &settings).await
+    };
+    if let Err(why) = posted {
+        store.save_session(&session, None)?;
+        return Err(why)
+    }
 
-    JewelryTraits::from_str(&selected_trait).unwrap()
+    let wizard = &session.wizard;
+    let mut content = "✅ Solicitud enviada".to_string();
+    if let Some(name) = preset {
+        let saved = presets::save_preset(ctx, session.guild_id, user.id, &name, &BuildSpec::new(&wizard.set_name, wizard.pieces.clone())).await;
+        content = format!("{}\n{}", content, saved);
+    }
+    close_session(ctx, &session, user, reply, content).await
 }
 
-async fn select_quality<F>(part: F, user: &User, ctx: &Context) -> GearQuality
-    where F: Display
-{
-    let menu = components::gear_quality("quality");
-    let selected_quality = select_feat(user, ctx, "calidad", part, menu).await;
-
-    GearQuality::from_str(&selected_quality).unwrap()
+async fn post_research_order(ctx: &Context, session: &GearSession, user: &User, settings: &GuildSettings) -> Result<(), RequestError> {
+    let parts = session.wizard.pieces.clone();
+    let mut embeds = vec![components::order_state_embed(components::gear_research_piece_embed(&parts), &OrderStatus::Open, None)];
+    if settings.show_costs {
+        embeds.push(components::display_research_cost(&parts));
+    }
+    let channel_id = settings.channel(OrderKind::Research).unwrap_or(session.channel_id);
+    let title = format!("{} para {}", OrderKind::Research, user.name);
+    let posted = post_request(ctx, settings, channel_id, &title, user.id, CreateMessage::new()
+        .content(format!("{}\n\n__**‼️Peticion de Investigación para {}‼️**__\n\n", Mention::Role(session.crafters), Mention::User(user.id)))
+        .embeds(embeds)
+        .components(components::order_action_row(&OrderStatus::Open))
+    ).await?;
+    save_order(ctx, NewOrder {
+        kind: OrderKind::Research,
+        guild_id: session.guild_id,
+        channel_id: posted.channel_id,
+        message_id: posted.message_id,
+        requester: user.id,
+        crafters: session.crafters,
+        price: session.price,
+        set_name: None,
+        pieces: parts,
+        details: None,
+        thread: posted.thread,
+    }).await;
+    Ok(())
 }
 
-async fn select_weight(part: &ArmourParts, user: &User, ctx: &Context) -> ArmourWeights {
-    let msg = user.dm(&ctx.http, CreateMessage::new()
-        .content(format!("Selecciona **peso** de la pieza: __**{}**__", part))
-        .components(vec![CreateActionRow::SelectMenu(components::armor_weight("weight"))])
-    ).await.unwrap();
-
-    let weight_interaction = await_component_interaction(msg, ctx).await.unwrap();
-    ok_response(&weight_interaction, ctx).await;
-
-    if let ComponentInteractionDataKind::StringSelect {values} = weight_interaction.data.kind {
-        ArmourWeights::from_str(&values.get(0).unwrap().to_string()).unwrap()
-    } else {
-        panic!("rework pending")
+async fn post_gear_order(ctx: &Context, session: &GearSession, user: &User, settings: &GuildSettings) -> Result<(), RequestError> {
+    let parts = session.wizard.pieces.clone();
+    let Some(set) = sets::find_set(&session.wizard.set_name) else {
+        error!("Gear session for {} with unknown set {}", user.id, session.wizard.set_name);
+        return Err(RequestError::MissingInput)
+    };
+
+    let mut embeds = vec![
+        components::order_state_embed(components::gear_set_piece_embed(settings.language.set_name(set), &parts), &OrderStatus::Open, None)
+    ];
+    if settings.show_costs {
+        embeds.push(components::display_cost(&parts));
     }
-
+    let capable = research::capable_crafters(ctx, session.guild_id, session.crafters, &parts, Some(set)).await;
+    let channel_id = settings.channel(OrderKind::Gear).unwrap_or(session.channel_id);
+    let title = format!("{} para {}", settings.language.set_name(set), user.name);
+    let posted = post_request(ctx, settings, channel_id, &title, user.id, CreateMessage::new()
+        .content(format!("{}\n\n__**‼️Peticion de Equipamiento para {}‼️**__\n\n{}", Mention::Role(session.crafters), Mention::User(user.id), components::capable_crafters_message(&capable)))
+        .embeds(embeds)
+        .components(components::order_action_row(&OrderStatus::Open))
+    ).await?;
+    save_order(ctx, NewOrder {
+        kind: OrderKind::Gear,
+        guild_id: session.guild_id,
+        channel_id: posted.channel_id,
+        message_id: posted.message_id,
+        requester: user.id,
+        crafters: session.crafters,
+        price: session.price,
+        set_name: Some(set.name_es.to_string()),
+        pieces: parts,
+        details: None,
+        thread: posted.thread,
+    }).await;
+    Ok(())
 }
 
-async fn select_feat<F: Display>(user: &User, ctx: &Context, feat: &str, item: F, menu: CreateSelectMenu) -> String {
-    let msg = user.dm(&ctx.http, CreateMessage::new()
-        .content(format!("Selecciona **{}** para __**{}**__", feat, item))
-        .components(vec![CreateActionRow::SelectMenu(menu)])
-    ).await.unwrap();
-
-    let interaction = await_component_interaction(msg, ctx).await.unwrap();
-    ok_response(&interaction, ctx).await;
-
-    if let ComponentInteractionDataKind::StringSelect {values} = interaction.data.kind {
-        values.get(0).unwrap().to_string()
-    } else { String::new() }
+/// Splits `wizard_<turn>[_<action>]` ids into the turn and the action
+fn parse_wizard_id(id: &str) -> Option<(u32, Option<&str>)> {
+    let rest = id.strip_prefix("wizard_")?;
+    let (turn, action) = match rest.split_once('_') {
+        Some((turn, action)) => (turn, Some(action)),
+        None => (rest, None)
+    };
+    Some((turn.parse().ok()?, action))
 }
 
-async fn show_piece(piece: &SetPiece, user: &User, ctx: &Context) {
-    user.dm(&ctx.http, CreateMessage::new()
-        .embed(components::gear_piece_embed(piece))
-    ).await.unwrap();
+async fn dm(ctx: &Context, user: &User, message: CreateMessage) -> Result<(), RequestError> {
+    user.dm(&ctx.http, message).await
+        .map(|_| ())
+        .map_err(|why| RequestError::DirectMessage(Box::new(why)))
 }
-
-async fn confirm_dialog(question: &str, user: &User, ctx: &Context) -> bool {
-    let msg = user.dm(&ctx.http, CreateMessage::new()
-        .content(question)
-        .components(vec![components::confirmation_row("confirm_yes")])
-    ).await.unwrap();
-
-    let interaction = await_component_interaction(msg, ctx).await.unwrap();
-    ok_response(&interaction, ctx).await;
-
-    interaction.data.custom_id == "confirm_yes"
-}
\ No newline at end of file
@@ -0,0 +1,50 @@
+use serenity::all::{CommandDataOptionValue, CommandInteraction, Context, CreateInteractionResponse, CreateInteractionResponseMessage, RoleId};
+use tracing::info;
+use crate::components;
+use crate::requests::{guild_settings, Reply};
+use crate::requests::error::RequestError;
+use crate::store::{Menu, MenuStore, OrderKind};
+
+pub async fn menu(command: CommandInteraction, ctx: &Context) {
+    // Options left out are taken from the guild configuration
+    let settings = guild_settings(ctx, command.guild_id).await;
+    let price = command.data.options.iter()
+        .find_map(|o| if let CommandDataOptionValue::Number(price) = o.value { Some(price) } else { None })
+        .or(settings.price(OrderKind::Gear));
+    let role = command.data.options.iter()
+        .find_map(|o| if let CommandDataOptionValue::Role(role) = o.value { Some(role) } else { None })
+        .or(settings.crafters);
+
+    let (price, role) = match (price, role) {
+        (Some(price), Some(role)) => (price, role),
+        (None, _) => return Reply::Command(&command).report(ctx, RequestError::Unconfigured("precio de equipamiento")).await,
+        (_, None) => return Reply::Command(&command).report(ctx, RequestError::Unconfigured("rol de crafteadores")).await
+    };
+
+    if let Err(why) = command.create_response(&ctx.http, CreateInteractionResponse::Message(
+        CreateInteractionResponseMessage::new()
+            .content(components::menu_description(&price, role))
+            .components(components::menu_action_rows())
+    )).await {
+        return info!("Cannot respond to slash command: {}", why);
+    }
+
+    if let Err(why) = save_menu(&command, ctx, role, price).await {
+        Reply::Command(&command).report(ctx, why).await
+    }
+}
+
+/// Keeps the configuration of the menu just posted so its buttons do not depend on its text
+async fn save_menu(command: &CommandInteraction, ctx: &Context, crafters: RoleId, price: f64) -> Result<(), RequestError> {
+    let store = ctx.data.read().await.get::<MenuStore>().cloned()
+        .expect("Menu store not initialised");
+    let message = command.get_response(&ctx.http).await?;
+    store.save_menu(&Menu {
+        message_id: message.id,
+        guild_id: command.guild_id,
+        channel_id: message.channel_id,
+        crafters,
+        price
+    })?;
+    Ok(())
+}
@@ -0,0 +1,147 @@
+use serenity::all::{CommandDataOptionValue, CommandInteraction, ComponentInteraction, Context, CreateEmbed, CreateInteractionResponse, CreateInteractionResponseMessage, Mention};
+use strum::EnumProperty;
+use tracing::{error, info};
+use crate::components;
+use crate::requests::guild_settings;
+use seht_core::entities::sets;
+use seht_core::entities::research::KnownTrait;
+use crate::store::{Order, OrderAction, OrderKind, OrderStore, ResearchStore};
+
+pub async fn orders(command: CommandInteraction, ctx: &Context) {
+    let order_id = command.data.options.first()
+        .and_then(|o| if let CommandDataOptionValue::Integer(id) = o.value {Some(id)} else {None});
+
+    let store = ctx.data.read().await.get::<OrderStore>().cloned()
+        .expect("Order store not initialised");
+
+    let embed = match order_id {
+        Some(id) => match store.get_order(id) {
+            Ok(Some(order)) if order.requester == command.user.id => components::order_embed(&order),
+            Ok(_) => CreateEmbed::new().description(format!("No encuentro tu solicitud #{}", id)),
+            Err(why) => {
+                error!("Cannot load order #{}: {:?}", id, why);
+                CreateEmbed::new().description("No se han podido cargar tus solicitudes")
+            }
+        },
+        None => match store.orders_by_requester(command.user.id) {
+            Ok(orders) => components::order_list_embed(&orders),
+            Err(why) => {
+                error!("Cannot load orders for {}: {:?}", command.user.id, why);
+                CreateEmbed::new().description("No se han podido cargar tus solicitudes")
+            }
+        }
+    };
+
+    if let Err(why) = command.create_response(&ctx.http, CreateInteractionResponse::Message(
+        CreateInteractionResponseMessage::new()
+            .embed(embed)
+            .ephemeral(true)
+    )).await {
+        info!("Cannot respond to orders command: {}", why);
+    }
+}
+
+pub async fn order_action(interaction: ComponentInteraction, ctx: &Context, action: OrderAction) {
+    let store = ctx.data.read().await.get::<OrderStore>().cloned()
+        .expect("Order store not initialised");
+    let research_store = ctx.data.read().await.get::<ResearchStore>().cloned()
+        .expect("Research store not initialised");
+
+    let order = match store.get_order_by_message(interaction.message.id) {
+        Ok(Some(order)) => order,
+        Ok(None) => return reject(&interaction, ctx, "No encuentro esta solicitud").await,
+        Err(why) => {
+            error!("Cannot load order for message {}: {:?}", interaction.message.id, why);
+            return reject(&interaction, ctx, "No se ha podido cargar la solicitud").await
+        }
+    };
+
+    let user = interaction.user.id;
+    let allowed = match action {
+        OrderAction::Claim => interaction.member.as_ref()
+            .is_some_and(|m| m.roles.contains(&order.crafters)),
+        OrderAction::Start | OrderAction::Deliver => order.assignee == Some(user),
+        OrderAction::Cancel => order.requester == user || order.assignee == Some(user)
+    };
+    if !allowed {
+        return reject(&interaction, ctx, "No puedes realizar esta acción sobre la solicitud").await
+    }
+
+    // Gear can only be claimed by crafters whose registered research covers every piece
+    if action == OrderAction::Claim && order.kind == OrderKind::Gear {
+        let known = match research_store.known_traits(user) {
+            Ok(known) => known,
+            Err(why) => {
+                error!("Cannot load research for {}: {:?}", user, why);
+                return reject(&interaction, ctx, "No se ha podido cargar la investigación").await
+            }
+        };
+        if known.is_empty() {
+            return reject(&interaction, ctx, "No puedes reclamar esta solicitud sin registrar antes tu investigación con `/investigacion`").await
+        }
+        let set = order.set_name.as_deref().and_then(sets::find_set);
+        let needed: Vec<KnownTrait> = order.pieces.iter().map(KnownTrait::from).collect();
+        if let Some(gap) = components::research_gap(&known, &needed, set) {
+            return reject(&interaction, ctx, &format!("No puedes reclamar esta solicitud:\n{}", gap)).await
+        }
+    }
+
+    let Some(status) = order.status.next(&action) else {
+        return reject(&interaction, ctx, &format!("La solicitud ya está **{}**", order.status)).await
+    };
+    let assignee = if action == OrderAction::Claim { Some(user) } else { order.assignee };
+
+    match store.update_order_status(order.id, order.status, status, assignee) {
+        Ok(true) => {}
+        // Someone else acted on the order since it was loaded
+        Ok(false) => {
+            let current = store.get_order(order.id).ok().flatten().map_or(order.status, |o| o.status);
+            return reject(&interaction, ctx, &format!("La solicitud ya está **{}**", current)).await
+        }
+        Err(why) => {
+            error!("Cannot update order #{}: {:?}", order.id, why);
+            return reject(&interaction, ctx, "No se ha podido actualizar la solicitud").await
+        }
+    }
+
+    let order = Order { status, assignee, ..order };
+    let language = guild_settings(ctx, order.guild_id).await.language;
+    let mut embeds = vec![components::order_request_embed(&order, language)];
+    embeds.extend(interaction.message.embeds.iter().skip(1).cloned().map(CreateEmbed::from));
+
+    if let Err(why) = interaction.create_response(&ctx.http, CreateInteractionResponse::UpdateMessage(
+        CreateInteractionResponseMessage::new()
+            .embeds(embeds)
+            .components(components::order_action_row(&status))
+    )).await {
+        info!("Cannot update order message: {}", why);
+        return
+    }
+
+    let mut notice = format!(
+        "{} tu solicitud #{} está ahora **{} {}**",
+        Mention::User(order.requester), order.id, status.get_str("Emoji").unwrap(), status
+    );
+    // Mentioning the crafter also adds them to the thread of the order
+    if action == OrderAction::Claim {
+        notice = format!("{} por {}", notice, Mention::User(user));
+    }
+    // Orders with a thread are followed there, without burying the request channel
+    let notified = match order.thread {
+        Some(thread) => thread.say(&ctx.http, notice).await,
+        None => interaction.message.reply(&ctx.http, notice).await
+    };
+    if let Err(why) = notified {
+        info!("Cannot notify requester of order #{}: {}", order.id, why);
+    }
+}
+
+async fn reject(interaction: &ComponentInteraction, ctx: &Context, reason: &str) {
+    if let Err(why) = interaction.create_response(&ctx.http, CreateInteractionResponse::Message(
+        CreateInteractionResponseMessage::new()
+            .content(reason)
+            .ephemeral(true)
+    )).await {
+        info!("Cannot respond to order action: {}", why);
+    }
+}
@@ -0,0 +1,132 @@
+use serenity::all::{CommandInteraction, Context, CreateAutocompleteResponse, CreateEmbed, CreateInteractionResponse, CreateInteractionResponseMessage, GuildId, ResolvedOption, ResolvedValue, UserId};
+use tracing::{error, info};
+use crate::components;
+use crate::requests::{gear, option_str};
+use seht_core::build::BuildSpec;
+use crate::store::PresetStore;
+
+pub async fn presets(command: CommandInteraction, ctx: &Context) {
+    let store = ctx.data.read().await.get::<PresetStore>().cloned()
+        .expect("Preset store not initialised");
+    let options = command.data.options();
+    let Some(ResolvedOption { name: subcommand, value: ResolvedValue::SubCommand(options), .. }) = options.first() else {
+        return info!("presets command without subcommand");
+    };
+    let user = command.user.id;
+
+    let embeds = match command.guild_id {
+        None => vec![CreateEmbed::new().description("Las builds se guardan por servidor, usa el comando desde el servidor")],
+        Some(guild_id) => match *subcommand {
+            "ver" => {
+                let name = option_str(options, "nombre");
+                match store.get_preset(guild_id, user, name) {
+                    Ok(Some(preset)) => vec![
+                        components::gear_result_embed(&preset.spec.pieces, &format!("{} ({})", preset.name, preset.spec.set_name)),
+                        components::display_cost(&preset.spec.pieces)
+                    ],
+                    Ok(None) => vec![CreateEmbed::new().description(format!("No tienes ninguna build llamada **{}**", name))],
+                    Err(why) => {
+                        error!("Cannot load preset {} for {}: {:?}", name, user, why);
+                        vec![CreateEmbed::new().description("No se ha podido cargar la build")]
+                    }
+                }
+            }
+            "pedir" => {
+                let name = option_str(options, "nombre");
+                match store.get_preset(guild_id, user, name) {
+                    Ok(Some(preset)) => return gear::order_preset(&command, ctx, guild_id, preset.spec).await,
+                    Ok(None) => vec![CreateEmbed::new().description(format!("No tienes ninguna build llamada **{}**", name))],
+                    Err(why) => {
+                        error!("Cannot load preset {} for {}: {:?}", name, user, why);
+                        vec![CreateEmbed::new().description("No se ha podido cargar la build")]
+                    }
+                }
+            }
+            "renombrar" => {
+                let name = option_str(options, "nombre");
+                let new_name = option_str(options, "nuevo").trim();
+                let description = match store.get_preset(guild_id, user, new_name) {
+                    _ if new_name.is_empty() => "El nuevo nombre no puede estar vacío".to_string(),
+                    Ok(Some(_)) => format!("Ya tienes una build llamada **{}**", new_name),
+                    Ok(None) => match store.rename_preset(guild_id, user, name, new_name) {
+                        Ok(true) => format!("**{}** ahora se llama **{}**", name, new_name),
+                        Ok(false) => format!("No tienes ninguna build llamada **{}**", name),
+                        Err(why) => {
+                            error!("Cannot rename preset {} for {}: {:?}", name, user, why);
+                            "No se ha podido renombrar la build".to_string()
+                        }
+                    },
+                    Err(why) => {
+                        error!("Cannot load preset {} for {}: {:?}", new_name, user, why);
+                        "No se ha podido renombrar la build".to_string()
+                    }
+                };
+                vec![CreateEmbed::new().description(description)]
+            }
+            "borrar" => {
+                let name = option_str(options, "nombre");
+                let description = match store.delete_preset(guild_id, user, name) {
+                    Ok(true) => format!("Build **{}** borrada", name),
+                    Ok(false) => format!("No tienes ninguna build llamada **{}**", name),
+                    Err(why) => {
+                        error!("Cannot delete preset {} for {}: {:?}", name, user, why);
+                        "No se ha podido borrar la build".to_string()
+                    }
+                };
+                vec![CreateEmbed::new().description(description)]
+            }
+            _ => match store.presets(guild_id, user) {
+                Ok(saved) => vec![components::preset_list_embed(&saved)],
+                Err(why) => {
+                    error!("Cannot load presets for {}: {:?}", user, why);
+                    vec![CreateEmbed::new().description("No se han podido cargar tus builds")]
+                }
+            }
+        }
+    };
+
+    if let Err(why) = command.create_response(&ctx.http, CreateInteractionResponse::Message(
+        CreateInteractionResponseMessage::new()
+            .embeds(embeds)
+            .ephemeral(true)
+    )).await {
+        info!("Cannot respond to presets command: {}", why);
+    }
+}
+
+pub async fn presets_autocomplete(autocomplete: CommandInteraction, ctx: &Context) {
+    let Some(focused) = autocomplete.data.autocomplete() else { return };
+    let Some(guild_id) = autocomplete.guild_id else { return };
+    let store = ctx.data.read().await.get::<PresetStore>().cloned()
+        .expect("Preset store not initialised");
+    let query = focused.value.to_lowercase();
+
+    // Discord accepts at most 25 choices
+    let choices = store.presets(guild_id, autocomplete.user.id).unwrap_or_default().into_iter()
+        .filter(|p| p.name.to_lowercase().contains(&query))
+        .take(25)
+        .fold(CreateAutocompleteResponse::new(), |r, p| r.add_string_choice(
+            format!("{} ({})", p.name, p.spec.set_name), p.name));
+
+    if let Err(why) = autocomplete.create_response(&ctx.http, CreateInteractionResponse::Autocomplete(choices)).await {
+        info!("Cannot respond to presets autocomplete: {}", why);
+    }
+}
+
+/// Stores a confirmed build for the user, returning how it went so the wizard can tell them
+pub async fn save_preset(ctx: &Context, guild_id: Option<GuildId>, user: UserId, name: &str, spec: &BuildSpec) -> String {
+    let Some(guild_id) = guild_id else {
+        info!("Cannot save preset {} for {} outside a guild", name, user);
+        return "Las builds se guardan por servidor, no se ha podido guardar".to_string();
+    };
+    let store = ctx.data.read().await.get::<PresetStore>().cloned()
+        .expect("Preset store not initialised");
+
+    match store.save_preset(guild_id, user, name, spec) {
+        Ok(()) => format!("💾 Build guardada como **{}**, puedes volver a pedirla con **Mis builds** o **/builds pedir**", name),
+        Err(why) => {
+            error!("Cannot save preset {} for {}: {:?}", name, user, why);
+            "No se ha podido guardar la build".to_string()
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use std::str::FromStr;
+use serenity::all::{CommandInteraction, Context, CreateAutocompleteResponse, CreateEmbed, CreateInteractionResponse, CreateInteractionResponseMessage, GuildId, ResolvedOption, ResolvedValue, RoleId, UserId};
+use tracing::{error, info};
+use crate::components;
+use seht_core::piece::SetPiece;
+use seht_core::entities::research::{self, KnownTrait, ResearchItem};
+use seht_core::entities::sets::CraftableSet;
+use crate::store::ResearchStore;
+
+pub async fn research(command: CommandInteraction, ctx: &Context) {
+    let store = ctx.data.read().await.get::<ResearchStore>().cloned()
+        .expect("Research store not initialised");
+    let options = command.data.options();
+    let Some(ResolvedOption { name: subcommand, value: ResolvedValue::SubCommand(options), .. }) = options.first() else {
+        return info!("research command without subcommand");
+    };
+    let crafter = command.user.id;
+
+    let embed = match *subcommand {
+        "aprender" | "olvidar" => {
+            let item = option_str(options, "pieza");
+            let gear_trait = option_str(options, "rasgo");
+            match KnownTrait::parse(item, gear_trait) {
+                Ok(known) => {
+                    let result = if *subcommand == "aprender" {
+                        store.learn_traits(crafter, &[known])
+                    } else {
+                        store.forget_traits(crafter, &[known])
+                    };
+                    match result.and_then(|_| store.known_traits(crafter)) {
+                        Ok(known) => components::research_embed(crafter, &known),
+                        Err(why) => {
+                            error!("Cannot update research for {}: {:?}", crafter, why);
+                            CreateEmbed::new().description("No se ha podido actualizar tu investigación")
+                        }
+                    }
+                }
+                Err(_) => CreateEmbed::new().description(format!("No reconozco **{}** con el rasgo **{}**", item, gear_trait))
+            }
+        }
+        "importar" => {
+            let mut text = option_str(options, "texto").to_string();
+            if let Some(attachment) = options.iter().find_map(|o| if let ResolvedValue::Attachment(a) = o.value {Some(a)} else {None}) {
+                match attachment.download().await {
+                    Ok(bytes) => text = format!("{}\n{}", text, String::from_utf8_lossy(&bytes)),
+                    Err(why) => info!("Cannot download research import: {}", why)
+                }
+            }
+            match research::parse_research(&text) {
+                Ok(known) if known.is_empty() => CreateEmbed::new().description("No hay rasgos que importar"),
+                Ok(known) => match store.learn_traits(crafter, &known).and_then(|_| store.known_traits(crafter)) {
+                    Ok(all) => components::research_embed(crafter, &all)
+                        .description(format!("Importados {} rasgos", known.len())),
+                    Err(why) => {
+                        error!("Cannot import research for {}: {:?}", crafter, why);
+                        CreateEmbed::new().description("No se ha podido importar tu investigación")
+                    }
+                },
+                Err(line_error) => CreateEmbed::new().description(line_error)
+            }
+        }
+        _ => {
+            let user = options.iter()
+                .find_map(|o| if let ResolvedValue::User(u, _) = o.value {Some(u.id)} else {None})
+                .unwrap_or(crafter);
+            match store.known_traits(user) {
+                Ok(known) => components::research_embed(user, &known),
+                Err(why) => {
+                    error!("Cannot load research for {}: {:?}", user, why);
+                    CreateEmbed::new().description("No se ha podido cargar la investigación")
+                }
+            }
+        }
+    };
+
+    if let Err(why) = command.create_response(&ctx.http, CreateInteractionResponse::Message(
+        CreateInteractionResponseMessage::new()
+            .embed(embed)
+            .ephemeral(true)
+    )).await {
+        info!("Cannot respond to research command: {}", why);
+    }
+}
+
+pub async fn research_autocomplete(autocomplete: CommandInteraction, ctx: &Context) {
+    let Some(focused) = autocomplete.data.autocomplete() else { return };
+    let query = focused.value.to_lowercase();
+    let names: Vec<String> = if focused.name == "rasgo" {
+        let options = autocomplete.data.options();
+        let item = match options.first() {
+            Some(ResolvedOption { value: ResolvedValue::SubCommand(options), .. }) => option_str(options, "pieza"),
+            _ => ""
+        };
+        ResearchItem::from_str(item).map(|item| research::research_traits(&item)).unwrap_or_default()
+            .iter().map(|t| t.to_string()).collect()
+    } else {
+        research::research_items().iter().map(|i| i.to_string()).collect()
+    };
+
+    // Discord accepts at most 25 choices
+    let choices = names.into_iter()
+        .filter(|n| n.to_lowercase().contains(&query))
+        .take(25)
+        .fold(CreateAutocompleteResponse::new(), |r, n| r.add_string_choice(n.clone(), n));
+
+    if let Err(why) = autocomplete.create_response(&ctx.http, CreateInteractionResponse::Autocomplete(choices)).await {
+        info!("Cannot respond to research autocomplete: {}", why);
+    }
+}
+
+/// Crafters holding the crafter role in the guild that have researched every trait of the pieces
+pub async fn capable_crafters(ctx: &Context, guild_id: Option<GuildId>, crafters: RoleId, pieces: &[SetPiece], set: Option<&CraftableSet>) -> Vec<UserId> {
+    let Some(guild_id) = guild_id else { return vec![] };
+    let store = ctx.data.read().await.get::<ResearchStore>().cloned()
+        .expect("Research store not initialised");
+    let needed: Vec<KnownTrait> = pieces.iter().map(KnownTrait::from).collect();
+
+    let candidates = match store.crafters_knowing(&needed) {
+        Ok(candidates) => candidates,
+        Err(why) => {
+            error!("Cannot look up crafters for request: {:?}", why);
+            return vec![]
+        }
+    };
+
+    let mut capable = Vec::new();
+    for candidate in candidates {
+        let known = store.known_traits(candidate).unwrap_or_default();
+        if components::research_gap(&known, &needed, set).is_some() {
+            continue;
+        }
+        match guild_id.member(&ctx.http, candidate).await {
+            Ok(member) if member.roles.contains(&crafters) => capable.push(candidate),
+            Ok(_) => {}
+            Err(why) => info!("Cannot load member {}: {}", candidate, why)
+        }
+    }
+    capable
+}
+
+fn option_str<'a>(options: &[ResolvedOption<'a>], name: &str) -> &'a str {
+    options.iter()
+        .find(|o| o.name == name)
+        .and_then(|o| match o.value {
+            ResolvedValue::String(s) => Some(s),
+            ResolvedValue::Autocomplete { value, .. } => Some(value),
+            _ => None
+        })
+        .unwrap_or_default()
+}
@@ -0,0 +1,35 @@
+use serenity::all::{CommandDataOptionValue, CommandInteraction, Context, CreateAutocompleteResponse, CreateInteractionResponse, CreateInteractionResponseMessage};
+use tracing::info;
+use crate::components;
+use seht_core::entities::sets;
+
+pub async fn set_info(command: CommandInteraction, ctx: &Context) {
+    let name = command.data.options.first()
+        .and_then(|o| if let CommandDataOptionValue::String(name) = &o.value {Some(name.clone())} else {None})
+        .unwrap_or_default();
+
+    let message = match sets::find_set(&name) {
+        Some(set) => CreateInteractionResponseMessage::new().embed(components::craftable_set_embed(set)),
+        None => CreateInteractionResponseMessage::new().content(components::unknown_set_message(&name, &sets::suggest_sets(&name)))
+    };
+
+    if let Err(why) = command.create_response(&ctx.http, CreateInteractionResponse::Message(message.ephemeral(true))).await {
+        info!("Cannot respond to set command: {}", why);
+    }
+}
+
+pub async fn set_autocomplete(autocomplete: CommandInteraction, ctx: &Context) {
+    let query = autocomplete.data.autocomplete()
+        .map(|o| o.value.to_string())
+        .unwrap_or_default();
+
+    // Discord accepts at most 25 choices
+    let choices = sets::search_sets(&query).into_iter()
+        .take(25)
+        .fold(CreateAutocompleteResponse::new(), |r, set| r.add_string_choice(
+            format!("{} ({})", set.name_es, set.name_en), set.name_es));
+
+    if let Err(why) = autocomplete.create_response(&ctx.http, CreateInteractionResponse::Autocomplete(choices)).await {
+        info!("Cannot respond to set autocomplete: {}", why);
+    }
+}
@@ -0,0 +1,315 @@
+pub mod sqlite;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use serenity::all::{ChannelId, GuildId, MessageId, RoleId, UserId};
+use serenity::prelude::TypeMapKey;
+use strum_macros::{Display, EnumIter, EnumProperty, EnumString};
+use seht_core::build::BuildSpec;
+use seht_core::piece::SetPiece;
+use seht_core::entities::research::KnownTrait;
+use seht_core::entities::sets::CraftableSet;
+use seht_core::wizard::GearWizard;
+
+#[derive(Clone, Copy, Eq, PartialEq, Hash, EnumIter, Display, EnumString)]
+pub enum OrderKind {
+    #[strum(serialize = "Equipamiento")]
+    Gear,
+    #[strum(serialize = "Investigación")]
+    Research,
+    #[strum(serialize = "Consumibles")]
+    Consumables,
+    #[strum(serialize = "Encantamientos")]
+    Enchantments
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Display, EnumString, EnumProperty)]
+pub enum OrderStatus {
+    #[strum(serialize = "Abierta")]
+    #[strum(props(Emoji = "📬"))]
+    Open,
+    #[strum(serialize = "Reclamada")]
+    #[strum(props(Emoji = "🙋"))]
+    Claimed,
+    #[strum(serialize = "En progreso")]
+    #[strum(props(Emoji = "⚒️"))]
+    InProgress,
+    #[strum(serialize = "Entregada")]
+    #[strum(props(Emoji = "✅"))]
+    Delivered,
+    #[strum(serialize = "Cancelada")]
+    #[strum(props(Emoji = "❌"))]
+    Cancelled
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, EnumIter, Display, EnumString)]
+pub enum OrderAction {
+    #[strum(serialize = "order_claim")]
+    Claim,
+    #[strum(serialize = "order_start")]
+    Start,
+    #[strum(serialize = "order_deliver")]
+    Deliver,
+    #[strum(serialize = "order_cancel")]
+    Cancel
+}
+
+/// Where the steps of a gear request are shown
+#[derive(Clone, Copy, Default, Eq, PartialEq, EnumIter, Display, EnumString, EnumProperty)]
+pub enum Delivery {
+    #[default]
+    #[strum(serialize = "dm")]
+    #[strum(props(Label = "Mensajes directos"))]
+    Dm,
+    /// A single ephemeral message in the menu channel, edited on every step
+    #[strum(serialize = "efimero")]
+    #[strum(props(Label = "Mensaje efímero en el canal"))]
+    Ephemeral
+}
+
+/// Language of the set names shown in posted requests
+#[derive(Clone, Copy, Default, Eq, PartialEq, EnumIter, Display, EnumString, EnumProperty)]
+pub enum Language {
+    #[default]
+    #[strum(serialize = "es")]
+    #[strum(props(Label = "Español"))]
+    Spanish,
+    #[strum(serialize = "en")]
+    #[strum(props(Label = "Inglés"))]
+    English
+}
+
+impl Language {
+    pub fn set_name(&self, set: &CraftableSet) -> &'static str {
+        match self {
+            Language::Spanish => set.name_es,
+            Language::English => set.name_en
+        }
+    }
+}
+
+impl OrderStatus {
+    pub fn next(&self, action: &OrderAction) -> Option<OrderStatus> {
+        match (self, action) {
+            (OrderStatus::Open, OrderAction::Claim) => Some(OrderStatus::Claimed),
+            (OrderStatus::Claimed, OrderAction::Start) => Some(OrderStatus::InProgress),
+            (OrderStatus::InProgress, OrderAction::Deliver) => Some(OrderStatus::Delivered),
+            (OrderStatus::Open | OrderStatus::Claimed | OrderStatus::InProgress, OrderAction::Cancel) => Some(OrderStatus::Cancelled),
+            _ => None
+        }
+    }
+}
+
+pub struct NewOrder {
+    pub kind: OrderKind,
+    pub guild_id: Option<GuildId>,
+    pub channel_id: ChannelId,
+    pub message_id: MessageId,
+    pub requester: UserId,
+    pub crafters: RoleId,
+    pub price: Option<f64>,
+    pub set_name: Option<String>,
+    pub pieces: Vec<SetPiece>,
+    pub details: Option<String>,
+    /// Thread or forum post opened to discuss the order
+    pub thread: Option<ChannelId>
+}
+
+pub struct Order {
+    pub id: i64,
+    pub kind: OrderKind,
+    pub guild_id: Option<GuildId>,
+    pub channel_id: ChannelId,
+    pub message_id: MessageId,
+    pub requester: UserId,
+    pub crafters: RoleId,
+    pub price: Option<f64>,
+    pub set_name: Option<String>,
+    pub pieces: Vec<SetPiece>,
+    pub details: Option<String>,
+    pub thread: Option<ChannelId>,
+    pub status: OrderStatus,
+    pub assignee: Option<UserId>,
+    pub created_at: i64,
+    pub updated_at: i64
+}
+
+pub trait OrderRepository: Send + Sync {
+    fn create_order(&self, order: &NewOrder) -> anyhow::Result<i64>;
+    fn get_order(&self, id: i64) -> anyhow::Result<Option<Order>>;
+    fn get_order_by_message(&self, message_id: MessageId) -> anyhow::Result<Option<Order>>;
+    fn orders_by_requester(&self, requester: UserId) -> anyhow::Result<Vec<Order>>;
+    /// Moves the order to `status` only if it is still `expected`, returning whether it was.
+    /// Keeps two crafters acting on the same order at once from overwriting each other
+    fn update_order_status(&self, id: i64, expected: OrderStatus, status: OrderStatus, assignee: Option<UserId>) -> anyhow::Result<bool>;
+}
+
+pub trait ResearchRepository: Send + Sync {
+    fn known_traits(&self, crafter: UserId) -> anyhow::Result<Vec<KnownTrait>>;
+    fn learn_traits(&self, crafter: UserId, traits: &[KnownTrait]) -> anyhow::Result<()>;
+    fn forget_traits(&self, crafter: UserId, traits: &[KnownTrait]) -> anyhow::Result<()>;
+    /// Crafters that have researched every one of the given traits
+    fn crafters_knowing(&self, traits: &[KnownTrait]) -> anyhow::Result<Vec<UserId>>;
+}
+
+pub struct Preset {
+    pub name: String,
+    pub spec: BuildSpec,
+    pub created_at: i64,
+    pub updated_at: i64
+}
+
+/// Builds saved by a user in a guild, names being unique per user and guild
+pub trait PresetRepository: Send + Sync {
+    fn save_preset(&self, guild_id: GuildId, user: UserId, name: &str, spec: &BuildSpec) -> anyhow::Result<()>;
+    fn get_preset(&self, guild_id: GuildId, user: UserId, name: &str) -> anyhow::Result<Option<Preset>>;
+    fn presets(&self, guild_id: GuildId, user: UserId) -> anyhow::Result<Vec<Preset>>;
+    /// Returns whether a preset with the old name existed
+    fn rename_preset(&self, guild_id: GuildId, user: UserId, name: &str, new_name: &str) -> anyhow::Result<bool>;
+    /// Returns whether a preset with that name existed
+    fn delete_preset(&self, guild_id: GuildId, user: UserId, name: &str) -> anyhow::Result<bool>;
+}
+
+/// A gear request being filled in through DM, with the menu it was started from
+pub struct GearSession {
+    pub user: UserId,
+    pub guild_id: Option<GuildId>,
+    pub channel_id: ChannelId,
+    pub crafters: RoleId,
+    pub price: Option<f64>,
+    pub delivery: Delivery,
+    pub wizard: GearWizard,
+    /// Last time the user answered a step
+    pub updated_at: i64
+}
+
+/// Requests in progress, one per user
+pub trait SessionRepository: Send + Sync {
+    /// Stores the session, returning whether it was. A new session, without `expected_turn`, replaces
+    /// any the user had in progress; otherwise it is only written while the stored one is still on
+    /// `expected_turn`, so an answer sent twice is only applied once
+    fn save_session(&self, session: &GearSession, expected_turn: Option<u32>) -> anyhow::Result<bool>;
+    fn session(&self, user: UserId) -> anyhow::Result<Option<GearSession>>;
+    /// Removes and returns the session of the user if it is still on `turn`, so only one answer can end it
+    fn take_session(&self, user: UserId, turn: u32) -> anyhow::Result<Option<GearSession>>;
+    /// Deletes the sessions that waited longer than their guild allows, returning how many
+    fn prune_sessions(&self) -> anyhow::Result<usize>;
+}
+
+/// Configuration given to `/menu`, kept by the id of the message it was posted as
+pub struct Menu {
+    pub message_id: MessageId,
+    pub guild_id: Option<GuildId>,
+    pub channel_id: ChannelId,
+    pub crafters: RoleId,
+    pub price: f64
+}
+
+pub trait MenuRepository: Send + Sync {
+    /// Replaces the configuration of the menu posted as `menu.message_id`
+    fn save_menu(&self, menu: &Menu) -> anyhow::Result<()>;
+    fn menu(&self, message_id: MessageId) -> anyhow::Result<Option<Menu>>;
+}
+
+/// Minutes a gear request waits for an answer in guilds that never changed it, as long as the
+/// conversation used to wait before requests were stored
+pub const DEFAULT_TIMEOUT_MINUTES: u32 = 3;
+
+pub struct GuildSettings {
+    pub delivery: Delivery,
+    /// Role `/menu` mentions when none is given
+    pub crafters: Option<RoleId>,
+    /// Channel requests are posted to instead of the menu channel
+    pub channel: Option<ChannelId>,
+    /// Channels overriding `channel` for a category of requests
+    pub channels: HashMap<OrderKind, ChannelId>,
+    /// Whether a thread is opened for every request posted to a text channel
+    pub threads: bool,
+    pub prices: HashMap<OrderKind, f64>,
+    pub language: Language,
+    /// Minutes a gear request can wait for an answer, never expiring when unset
+    pub timeout: Option<u32>,
+    /// Whether requests show the bill of materials
+    pub show_costs: bool
+}
+
+impl Default for GuildSettings {
+    fn default() -> Self {
+        GuildSettings {
+            delivery: Delivery::default(),
+            crafters: None,
+            channel: None,
+            channels: HashMap::new(),
+            threads: false,
+            prices: HashMap::new(),
+            language: Language::default(),
+            timeout: Some(DEFAULT_TIMEOUT_MINUTES),
+            show_costs: true
+        }
+    }
+}
+
+impl GuildSettings {
+    /// Channel configured for the category, if any
+    pub fn channel(&self, kind: OrderKind) -> Option<ChannelId> {
+        self.channels.get(&kind).copied().or(self.channel)
+    }
+
+    pub fn price(&self, kind: OrderKind) -> Option<f64> {
+        self.prices.get(&kind).copied()
+    }
+
+    /// Whether a session last answered at `updated_at` has waited longer than allowed
+    pub fn expired(&self, updated_at: i64) -> bool {
+        self.timeout.is_some_and(|minutes| now() - updated_at > i64::from(minutes) * 60)
+    }
+}
+
+pub trait SettingsRepository: Send + Sync {
+    /// Settings of the guild, the defaults when never changed
+    fn guild_settings(&self, guild_id: GuildId) -> anyhow::Result<GuildSettings>;
+    fn save_guild_settings(&self, guild_id: GuildId, settings: &GuildSettings) -> anyhow::Result<()>;
+}
+
+pub struct OrderStore;
+
+impl TypeMapKey for OrderStore {
+    type Value = Arc<dyn OrderRepository>;
+}
+
+pub struct ResearchStore;
+
+impl TypeMapKey for ResearchStore {
+    type Value = Arc<dyn ResearchRepository>;
+}
+
+pub struct PresetStore;
+
+impl TypeMapKey for PresetStore {
+    type Value = Arc<dyn PresetRepository>;
+}
+
+pub struct SessionStore;
+
+impl TypeMapKey for SessionStore {
+    type Value = Arc<dyn SessionRepository>;
+}
+
+pub struct MenuStore;
+
+impl TypeMapKey for MenuStore {
+    type Value = Arc<dyn MenuRepository>;
+}
+
+pub struct SettingsStore;
+
+impl TypeMapKey for SettingsStore {
+    type Value = Arc<dyn SettingsRepository>;
+}
+
+pub fn now() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default()
+}
@@ -0,0 +1,935 @@
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Mutex;
+use anyhow::anyhow;
+use rusqlite::{Connection, OptionalExtension, params, params_from_iter, Row};
+use rusqlite::types::Type;
+use serenity::all::{ChannelId, GuildId, MessageId, RoleId, UserId};
+use seht_core::build::BuildSpec;
+use seht_core::piece::SetPiece;
+use seht_core::entities::armour::{Armour, ArmourEnchantments, ArmourParts, ArmourTraits, ArmourWeights};
+use seht_core::entities::GearQuality;
+use seht_core::entities::jewelry::{Jewelries, Jewelry, JewelryEnchantments, JewelryTraits};
+use seht_core::entities::level::CraftingLevel;
+use seht_core::entities::research::KnownTrait;
+use seht_core::entities::weapon::{Weapon, WeaponEnchantments, WeaponKind, WeaponTraits};
+use seht_core::wizard::GearWizard;
+use crate::store::{now, Delivery, DEFAULT_TIMEOUT_MINUTES, GearSession, GuildSettings, Menu, MenuRepository, NewOrder, Order, OrderRepository, OrderStatus, Preset, PresetRepository, ResearchRepository, SessionRepository, SettingsRepository};
+
+// Each entry upgrades the database by one version, tracked through `PRAGMA user_version`
+const MIGRATIONS: &[&str] = &[
+    "CREATE TABLE IF NOT EXISTS orders (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        kind TEXT NOT NULL,
+        guild_id INTEGER,
+        channel_id INTEGER NOT NULL,
+        message_id INTEGER NOT NULL,
+        requester_id INTEGER NOT NULL,
+        crafter_role_id INTEGER NOT NULL,
+        price REAL,
+        set_name TEXT,
+        details TEXT,
+        created_at INTEGER NOT NULL,
+        updated_at INTEGER NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS orders_requester ON orders (requester_id);
+    CREATE TABLE IF NOT EXISTS order_pieces (
+        order_id INTEGER NOT NULL REFERENCES orders (id) ON DELETE CASCADE,
+        position INTEGER NOT NULL,
+        category TEXT NOT NULL,
+        kind TEXT NOT NULL,
+        weight TEXT,
+        piece_trait TEXT NOT NULL,
+        enchantment TEXT,
+        quality TEXT NOT NULL,
+        PRIMARY KEY (order_id, position)
+    );",
+    "
+    ALTER TABLE orders ADD COLUMN status TEXT NOT NULL DEFAULT 'Abierta';
+    ALTER TABLE orders ADD COLUMN assignee_id INTEGER;
+    CREATE INDEX IF NOT EXISTS orders_message ON orders (message_id);
+    ",
+    "ALTER TABLE order_pieces ADD COLUMN level TEXT NOT NULL DEFAULT 'CP160';",
+    "CREATE TABLE IF NOT EXISTS research (
+        crafter_id INTEGER NOT NULL,
+        item TEXT NOT NULL,
+        research_trait TEXT NOT NULL,
+        updated_at INTEGER NOT NULL,
+        PRIMARY KEY (crafter_id, item, research_trait)
+    );",
+    "CREATE TABLE IF NOT EXISTS presets (
+        guild_id INTEGER NOT NULL,
+        user_id INTEGER NOT NULL,
+        name TEXT NOT NULL,
+        spec TEXT NOT NULL,
+        created_at INTEGER NOT NULL,
+        updated_at INTEGER NOT NULL,
+        PRIMARY KEY (guild_id, user_id, name)
+    );",
+    "CREATE TABLE IF NOT EXISTS gear_sessions (
+        user_id INTEGER PRIMARY KEY,
+        guild_id INTEGER,
+        channel_id INTEGER NOT NULL,
+        crafter_role_id INTEGER NOT NULL,
+        price REAL,
+        state TEXT NOT NULL,
+        updated_at INTEGER NOT NULL
+    );",
+    "CREATE TABLE IF NOT EXISTS guild_settings (
+        guild_id INTEGER PRIMARY KEY,
+        delivery TEXT NOT NULL,
+        updated_at INTEGER NOT NULL
+    );
+    ALTER TABLE gear_sessions ADD COLUMN delivery TEXT NOT NULL DEFAULT 'dm';",
+    "CREATE TABLE IF NOT EXISTS menus (
+        message_id INTEGER PRIMARY KEY,
+        guild_id INTEGER,
+        channel_id INTEGER NOT NULL,
+        crafter_role_id INTEGER NOT NULL,
+        price REAL NOT NULL,
+        created_at INTEGER NOT NULL,
+        updated_at INTEGER NOT NULL
+    );",
+    "ALTER TABLE guild_settings ADD COLUMN crafter_role_id INTEGER;
+    ALTER TABLE guild_settings ADD COLUMN channel_id INTEGER;
+    ALTER TABLE guild_settings ADD COLUMN language TEXT NOT NULL DEFAULT 'es';
+    ALTER TABLE guild_settings ADD COLUMN timeout_minutes INTEGER;
+    ALTER TABLE guild_settings ADD COLUMN show_costs INTEGER NOT NULL DEFAULT 1;
+    CREATE TABLE IF NOT EXISTS guild_prices (
+        guild_id INTEGER NOT NULL,
+        kind TEXT NOT NULL,
+        price REAL NOT NULL,
+        PRIMARY KEY (guild_id, kind)
+    );",
+    "ALTER TABLE orders ADD COLUMN thread_id INTEGER;
+    ALTER TABLE guild_settings ADD COLUMN threads INTEGER NOT NULL DEFAULT 0;
+    CREATE TABLE IF NOT EXISTS guild_channels (
+        guild_id INTEGER NOT NULL,
+        kind TEXT NOT NULL,
+        channel_id INTEGER NOT NULL,
+        PRIMARY KEY (guild_id, kind)
+    );",
+    // Armour is researched apart for each weight, empty for items without one. Armour rows from
+    // before did not say which weight they were for, so they are kept for all three and crafters
+    // can forget the ones they do not know
+    "CREATE TABLE research_by_weight (
+        crafter_id INTEGER NOT NULL,
+        item TEXT NOT NULL,
+        weight TEXT NOT NULL DEFAULT '',
+        research_trait TEXT NOT NULL,
+        updated_at INTEGER NOT NULL,
+        PRIMARY KEY (crafter_id, item, weight, research_trait)
+    );
+    INSERT INTO research_by_weight (crafter_id, item, research_trait, updated_at)
+        SELECT crafter_id, item, research_trait, updated_at FROM research
+        WHERE item NOT IN ('Cabeza', 'Hombros', 'Cuerpo', 'Manos', 'Cintura', 'Piernas', 'Pies');
+    INSERT INTO research_by_weight (crafter_id, item, weight, research_trait, updated_at)
+        SELECT crafter_id, item, weight, research_trait, updated_at FROM research
+        CROSS JOIN (SELECT 'Ligera' AS weight UNION ALL SELECT 'Media' UNION ALL SELECT 'Pesada')
+        WHERE item IN ('Cabeza', 'Hombros', 'Cuerpo', 'Manos', 'Cintura', 'Piernas', 'Pies');
+    DROP TABLE research;
+    ALTER TABLE research_by_weight RENAME TO research;",
+    // Pieces are kept as in a JSON build spec. The per-field columns are only read for pieces
+    // stored before, so they become optional
+    "CREATE TABLE order_pieces_spec (
+        order_id INTEGER NOT NULL REFERENCES orders (id) ON DELETE CASCADE,
+        position INTEGER NOT NULL,
+        spec TEXT,
+        category TEXT,
+        kind TEXT,
+        weight TEXT,
+        piece_trait TEXT,
+        enchantment TEXT,
+        quality TEXT,
+        level TEXT,
+        PRIMARY KEY (order_id, position)
+    );
+    INSERT INTO order_pieces_spec (order_id, position, category, kind, weight, piece_trait, enchantment, quality, level)
+        SELECT order_id, position, category, kind, weight, piece_trait, enchantment, quality, level FROM order_pieces;
+    DROP TABLE order_pieces;
+    ALTER TABLE order_pieces_spec RENAME TO order_pieces;",
+    // The turn is kept apart from the state so answers can be checked against it as they are written
+    "ALTER TABLE gear_sessions ADD COLUMN turn INTEGER NOT NULL DEFAULT 0;
+    UPDATE gear_sessions SET turn = COALESCE(json_extract(state, '$.turn'), 0);",
+    // Guilds saved before timeouts were added got none, which read as never expiring. They get the
+    // default, and never expiring is stored as 0 from now on
+    "UPDATE guild_settings SET timeout_minutes = 3 WHERE timeout_minutes IS NULL;"
+];
+
+const ORDER_COLUMNS: &str = "id, kind, guild_id, channel_id, message_id, requester_id, crafter_role_id, price, set_name, details, created_at, updated_at, status, assignee_id, thread_id";
+
+pub struct SqliteStore {
+    conn: Mutex<Connection>
+}
+
+impl SqliteStore {
+    pub fn open<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        let conn = Connection::open(path)?;
+        conn.execute_batch("PRAGMA foreign_keys = ON;")?;
+        migrate(&conn)?;
+        Ok(SqliteStore { conn: Mutex::new(conn) })
+    }
+
+    fn conn(&self) -> anyhow::Result<std::sync::MutexGuard<'_, Connection>> {
+        self.conn.lock().map_err(|_| anyhow!("sqlite connection poisoned"))
+    }
+}
+
+fn migrate(conn: &Connection) -> anyhow::Result<()> {
+    let version: usize = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    for (idx, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+        conn.execute_batch(&format!("BEGIN; {} PRAGMA user_version = {}; COMMIT;", migration, idx + 1))?;
+    }
+    Ok(())
+}
+
+impl OrderRepository for SqliteStore {
+    fn create_order(&self, order: &NewOrder) -> anyhow::Result<i64> {
+        let mut conn = self.conn()?;
+        let tx = conn.transaction()?;
+        let timestamp = now();
+        tx.execute(
+            "INSERT INTO orders (kind, guild_id, channel_id, message_id, requester_id, crafter_role_id, price, set_name, details, thread_id, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?11)",
+            params![
+                order.kind.to_string(),
+                order.guild_id.map(|g| g.get() as i64),
+                order.channel_id.get() as i64,
+                order.message_id.get() as i64,
+                order.requester.get() as i64,
+                order.crafters.get() as i64,
+                order.price,
+                order.set_name,
+                order.details,
+                order.thread.map(|t| t.get() as i64),
+                timestamp
+            ]
+        )?;
+        let id = tx.last_insert_rowid();
+        for (position, piece) in order.pieces.iter().enumerate() {
+            tx.execute(
+                "INSERT INTO order_pieces (order_id, position, spec) VALUES (?1, ?2, ?3)",
+                params![id, position as i64, piece.to_json()]
+            )?;
+        }
+        tx.commit()?;
+        Ok(id)
+    }
+
+    fn get_order(&self, id: i64) -> anyhow::Result<Option<Order>> {
+        let conn = self.conn()?;
+        let order = conn.query_row(
+            &format!("SELECT {} FROM orders WHERE id = ?1", ORDER_COLUMNS),
+            params![id],
+            order_from_row
+        ).optional()?;
+
+        match order {
+            Some(order) => Ok(Some(with_pieces(&conn, order)?)),
+            None => Ok(None)
+        }
+    }
+
+    fn get_order_by_message(&self, message_id: MessageId) -> anyhow::Result<Option<Order>> {
+        let conn = self.conn()?;
+        let order = conn.query_row(
+            &format!("SELECT {} FROM orders WHERE message_id = ?1", ORDER_COLUMNS),
+            params![message_id.get() as i64],
+            order_from_row
+        ).optional()?;
+
+        match order {
+            Some(order) => Ok(Some(with_pieces(&conn, order)?)),
+            None => Ok(None)
+        }
+    }
+
+    fn orders_by_requester(&self, requester: UserId) -> anyhow::Result<Vec<Order>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            &format!("SELECT {} FROM orders WHERE requester_id = ?1 ORDER BY created_at DESC", ORDER_COLUMNS)
+        )?;
+        let orders = stmt.query_map(params![requester.get() as i64], order_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        orders.into_iter()
+            .map(|order| with_pieces(&conn, order))
+            .collect()
+    }
+
+    fn update_order_status(&self, id: i64, expected: OrderStatus, status: OrderStatus, assignee: Option<UserId>) -> anyhow::Result<bool> {
+        let conn = self.conn()?;
+        let updated = conn.execute(
+            "UPDATE orders SET status = ?3, assignee_id = ?4, updated_at = ?5 WHERE id = ?1 AND status = ?2",
+            params![id, expected.to_string(), status.to_string(), assignee.map(|u| u.get() as i64), now()]
+        )?;
+        Ok(updated > 0)
+    }
+}
+
+impl ResearchRepository for SqliteStore {
+    fn known_traits(&self, crafter: UserId) -> anyhow::Result<Vec<KnownTrait>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT item, weight, research_trait FROM research WHERE crafter_id = ?1 ORDER BY item, weight, research_trait"
+        )?;
+        let rows = stmt.query_map(params![crafter.get() as i64], |row| Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, String>(2)?
+        )))?;
+
+        let mut known = Vec::new();
+        for row in rows {
+            let (item, weight, research_trait) = row?;
+            let item = if weight.is_empty() { item } else { format!("{} {}", item, weight) };
+            known.push(KnownTrait::parse(&item, &research_trait)?);
+        }
+        Ok(known)
+    }
+
+    fn learn_traits(&self, crafter: UserId, traits: &[KnownTrait]) -> anyhow::Result<()> {
+        let mut conn = self.conn()?;
+        let tx = conn.transaction()?;
+        let timestamp = now();
+        for known in traits {
+            let (item, weight, research_trait) = research_key(known);
+            tx.execute(
+                "INSERT INTO research (crafter_id, item, weight, research_trait, updated_at) VALUES (?1, ?2, ?3, ?4, ?5)
+                 ON CONFLICT (crafter_id, item, weight, research_trait) DO UPDATE SET updated_at = ?5",
+                params![crafter.get() as i64, item, weight, research_trait, timestamp]
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn forget_traits(&self, crafter: UserId, traits: &[KnownTrait]) -> anyhow::Result<()> {
+        let mut conn = self.conn()?;
+        let tx = conn.transaction()?;
+        for known in traits {
+            let (item, weight, research_trait) = research_key(known);
+            tx.execute(
+                "DELETE FROM research WHERE crafter_id = ?1 AND item = ?2 AND weight = ?3 AND research_trait = ?4",
+                params![crafter.get() as i64, item, weight, research_trait]
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn crafters_knowing(&self, traits: &[KnownTrait]) -> anyhow::Result<Vec<UserId>> {
+        let conn = self.conn()?;
+        let mut needed: Vec<(String, String, String)> = traits.iter().map(research_key).collect();
+        needed.sort();
+        needed.dedup();
+
+        let crafters = if needed.is_empty() {
+            let mut stmt = conn.prepare("SELECT DISTINCT crafter_id FROM research")?;
+            let rows = stmt.query_map([], |row| row.get::<_, i64>(0))?;
+            rows.collect::<Result<Vec<_>, _>>()?
+        } else {
+            let filter = vec!["(item = ? AND weight = ? AND research_trait = ?)"; needed.len()].join(" OR ");
+            let mut stmt = conn.prepare(&format!(
+                "SELECT crafter_id FROM research WHERE {} GROUP BY crafter_id HAVING COUNT(*) = {}",
+                filter, needed.len()
+            ))?;
+            let rows = stmt.query_map(
+                params_from_iter(needed.iter().flat_map(|(item, weight, research_trait)| [item, weight, research_trait])),
+                |row| row.get::<_, i64>(0)
+            )?;
+            rows.collect::<Result<Vec<_>, _>>()?
+        };
+
+        Ok(crafters.into_iter().map(|c| UserId::new(c as u64)).collect())
+    }
+}
+
+/// Item, weight (empty when the item has none) and trait a research row is keyed by
+fn research_key(known: &KnownTrait) -> (String, String, String) {
+    (
+        known.item.gear().to_string(),
+        known.item.weight().map(|w| w.to_string()).unwrap_or_default(),
+        known.gear_trait.to_string()
+    )
+}
+
+impl PresetRepository for SqliteStore {
+    fn save_preset(&self, guild_id: GuildId, user: UserId, name: &str, spec: &BuildSpec) -> anyhow::Result<()> {
+        let conn = self.conn()?;
+        conn.execute(
+            "INSERT INTO presets (guild_id, user_id, name, spec, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?5)
+             ON CONFLICT (guild_id, user_id, name) DO UPDATE SET spec = ?4, updated_at = ?5",
+            params![guild_id.get() as i64, user.get() as i64, name, spec.to_json(), now()]
+        )?;
+        Ok(())
+    }
+
+    fn get_preset(&self, guild_id: GuildId, user: UserId, name: &str) -> anyhow::Result<Option<Preset>> {
+        let conn = self.conn()?;
+        let row = conn.query_row(
+            "SELECT name, spec, created_at, updated_at FROM presets WHERE guild_id = ?1 AND user_id = ?2 AND name = ?3",
+            params![guild_id.get() as i64, user.get() as i64, name],
+            preset_row
+        ).optional()?;
+        row.map(preset_from_row).transpose()
+    }
+
+    fn presets(&self, guild_id: GuildId, user: UserId) -> anyhow::Result<Vec<Preset>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT name, spec, created_at, updated_at FROM presets WHERE guild_id = ?1 AND user_id = ?2 ORDER BY name"
+        )?;
+        let rows = stmt.query_map(params![guild_id.get() as i64, user.get() as i64], preset_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        rows.into_iter().map(preset_from_row).collect()
+    }
+
+    fn rename_preset(&self, guild_id: GuildId, user: UserId, name: &str, new_name: &str) -> anyhow::Result<bool> {
+        let conn = self.conn()?;
+        let updated = conn.execute(
+            "UPDATE presets SET name = ?4, updated_at = ?5 WHERE guild_id = ?1 AND user_id = ?2 AND name = ?3",
+            params![guild_id.get() as i64, user.get() as i64, name, new_name, now()]
+        )?;
+        Ok(updated > 0)
+    }
+
+    fn delete_preset(&self, guild_id: GuildId, user: UserId, name: &str) -> anyhow::Result<bool> {
+        let conn = self.conn()?;
+        let deleted = conn.execute(
+            "DELETE FROM presets WHERE guild_id = ?1 AND user_id = ?2 AND name = ?3",
+            params![guild_id.get() as i64, user.get() as i64, name]
+        )?;
+        Ok(deleted > 0)
+    }
+}
+
+impl SessionRepository for SqliteStore {
+    fn save_session(&self, session: &GearSession, expected_turn: Option<u32>) -> anyhow::Result<bool> {
+        let conn = self.conn()?;
+        let values = params![
+            session.user.get() as i64,
+            session.guild_id.map(|g| g.get() as i64),
+            session.channel_id.get() as i64,
+            session.crafters.get() as i64,
+            session.price,
+            session.delivery.to_string(),
+            session.wizard.to_json(),
+            session.wizard.turn,
+            now(),
+            expected_turn
+        ];
+        let saved = match expected_turn {
+            None => conn.execute(
+                "INSERT OR REPLACE INTO gear_sessions (user_id, guild_id, channel_id, crafter_role_id, price, delivery, state, turn, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                &values[..9]
+            )?,
+            Some(_) => conn.execute(
+                "UPDATE gear_sessions SET guild_id = ?2, channel_id = ?3, crafter_role_id = ?4, price = ?5, delivery = ?6, state = ?7, turn = ?8, updated_at = ?9
+                 WHERE user_id = ?1 AND turn = ?10",
+                values
+            )?
+        };
+        Ok(saved > 0)
+    }
+
+    fn session(&self, user: UserId) -> anyhow::Result<Option<GearSession>> {
+        let conn = self.conn()?;
+        let row = conn.query_row(
+            &format!("SELECT {} FROM gear_sessions WHERE user_id = ?1", SESSION_COLUMNS),
+            params![user.get() as i64],
+            session_row
+        ).optional()?;
+        row.map(|row| session_from_row(user, row)).transpose()
+    }
+
+    fn take_session(&self, user: UserId, turn: u32) -> anyhow::Result<Option<GearSession>> {
+        let conn = self.conn()?;
+        let row = conn.query_row(
+            &format!("DELETE FROM gear_sessions WHERE user_id = ?1 AND turn = ?2 RETURNING {}", SESSION_COLUMNS),
+            params![user.get() as i64, turn],
+            session_row
+        ).optional()?;
+        row.map(|row| session_from_row(user, row)).transpose()
+    }
+
+    fn prune_sessions(&self) -> anyhow::Result<usize> {
+        let conn = self.conn()?;
+        // Guilds without saved settings use the default, those that never expire have a timeout of 0
+        let pruned = conn.execute(
+            "DELETE FROM gear_sessions WHERE updated_at < ?1 - 60 * NULLIF(COALESCE(
+                (SELECT timeout_minutes FROM guild_settings g WHERE g.guild_id = gear_sessions.guild_id),
+                ?2
+            ), 0)",
+            params![now(), DEFAULT_TIMEOUT_MINUTES]
+        )?;
+        Ok(pruned)
+    }
+}
+
+impl MenuRepository for SqliteStore {
+    fn save_menu(&self, menu: &Menu) -> anyhow::Result<()> {
+        let conn = self.conn()?;
+        conn.execute(
+            "INSERT INTO menus (message_id, guild_id, channel_id, crafter_role_id, price, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?6)
+             ON CONFLICT (message_id) DO UPDATE SET crafter_role_id = ?4, price = ?5, updated_at = ?6",
+            params![
+                menu.message_id.get() as i64,
+                menu.guild_id.map(|g| g.get() as i64),
+                menu.channel_id.get() as i64,
+                menu.crafters.get() as i64,
+                menu.price,
+                now()
+            ]
+        )?;
+        Ok(())
+    }
+
+    fn menu(&self, message_id: MessageId) -> anyhow::Result<Option<Menu>> {
+        let conn = self.conn()?;
+        let menu = conn.query_row(
+            "SELECT guild_id, channel_id, crafter_role_id, price FROM menus WHERE message_id = ?1",
+            params![message_id.get() as i64],
+            |row| Ok(Menu {
+                message_id,
+                guild_id: row.get::<_, Option<i64>>(0)?.map(|g| GuildId::new(g as u64)),
+                channel_id: ChannelId::new(row.get::<_, i64>(1)? as u64),
+                crafters: RoleId::new(row.get::<_, i64>(2)? as u64),
+                price: row.get(3)?
+            })
+        ).optional()?;
+        Ok(menu)
+    }
+}
+
+impl SettingsRepository for SqliteStore {
+    fn guild_settings(&self, guild_id: GuildId) -> anyhow::Result<GuildSettings> {
+        let conn = self.conn()?;
+        let settings = conn.query_row(
+            "SELECT delivery, crafter_role_id, channel_id, language, timeout_minutes, show_costs, threads FROM guild_settings WHERE guild_id = ?1",
+            params![guild_id.get() as i64],
+            |row| Ok(GuildSettings {
+                delivery: parse_column(row, 0)?,
+                crafters: row.get::<_, Option<i64>>(1)?.map(|r| RoleId::new(r as u64)),
+                channel: row.get::<_, Option<i64>>(2)?.map(|c| ChannelId::new(c as u64)),
+                language: parse_column(row, 3)?,
+                timeout: timeout_from_column(row.get(4)?),
+                show_costs: row.get(5)?,
+                threads: row.get(6)?,
+                ..GuildSettings::default()
+            })
+        ).optional()?;
+        let Some(mut settings) = settings else { return Ok(GuildSettings::default()) };
+
+        let mut stmt = conn.prepare("SELECT kind, price FROM guild_prices WHERE guild_id = ?1")?;
+        settings.prices = stmt.query_map(params![guild_id.get() as i64], |row| Ok((parse_column(row, 0)?, row.get(1)?)))?
+            .collect::<Result<_, _>>()?;
+        let mut stmt = conn.prepare("SELECT kind, channel_id FROM guild_channels WHERE guild_id = ?1")?;
+        settings.channels = stmt.query_map(params![guild_id.get() as i64], |row| Ok((
+            parse_column(row, 0)?,
+            ChannelId::new(row.get::<_, i64>(1)? as u64)
+        )))?
+            .collect::<Result<_, _>>()?;
+        Ok(settings)
+    }
+
+    fn save_guild_settings(&self, guild_id: GuildId, settings: &GuildSettings) -> anyhow::Result<()> {
+        let mut conn = self.conn()?;
+        let tx = conn.transaction()?;
+        tx.execute(
+            "INSERT OR REPLACE INTO guild_settings (guild_id, delivery, crafter_role_id, channel_id, language, timeout_minutes, show_costs, threads, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                guild_id.get() as i64,
+                settings.delivery.to_string(),
+                settings.crafters.map(|r| r.get() as i64),
+                settings.channel.map(|c| c.get() as i64),
+                settings.language.to_string(),
+                settings.timeout.unwrap_or(0),
+                settings.show_costs,
+                settings.threads,
+                now()
+            ]
+        )?;
+        tx.execute("DELETE FROM guild_prices WHERE guild_id = ?1", params![guild_id.get() as i64])?;
+        for (kind, price) in &settings.prices {
+            tx.execute(
+                "INSERT INTO guild_prices (guild_id, kind, price) VALUES (?1, ?2, ?3)",
+                params![guild_id.get() as i64, kind.to_string(), price]
+            )?;
+        }
+        tx.execute("DELETE FROM guild_channels WHERE guild_id = ?1", params![guild_id.get() as i64])?;
+        for (kind, channel) in &settings.channels {
+            tx.execute(
+                "INSERT INTO guild_channels (guild_id, kind, channel_id) VALUES (?1, ?2, ?3)",
+                params![guild_id.get() as i64, kind.to_string(), channel.get() as i64]
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+}
+
+const SESSION_COLUMNS: &str = "guild_id, channel_id, crafter_role_id, price, delivery, state, updated_at";
+
+type SessionRow = (Option<i64>, i64, i64, Option<f64>, Delivery, String, i64);
+
+fn session_row(row: &Row) -> rusqlite::Result<SessionRow> {
+    Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, parse_column(row, 4)?, row.get(5)?, row.get(6)?))
+}
+
+fn session_from_row(user: UserId, (guild_id, channel_id, crafters, price, delivery, state, updated_at): SessionRow) -> anyhow::Result<GearSession> {
+    Ok(GearSession {
+        user,
+        guild_id: guild_id.map(|g| GuildId::new(g as u64)),
+        channel_id: ChannelId::new(channel_id as u64),
+        crafters: RoleId::new(crafters as u64),
+        price,
+        delivery,
+        wizard: GearWizard::from_json(&state).map_err(|e| anyhow!(e))?,
+        updated_at
+    })
+}
+
+fn preset_row(row: &Row) -> rusqlite::Result<(String, String, i64, i64)> {
+    Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+}
+
+fn preset_from_row((name, spec, created_at, updated_at): (String, String, i64, i64)) -> anyhow::Result<Preset> {
+    Ok(Preset {
+        name,
+        spec: BuildSpec::from_json(&spec).map_err(|e| anyhow!(e))?,
+        created_at,
+        updated_at
+    })
+}
+
+fn order_from_row(row: &Row) -> rusqlite::Result<Order> {
+    Ok(Order {
+        id: row.get(0)?,
+        kind: parse_column(row, 1)?,
+        guild_id: row.get::<_, Option<i64>>(2)?.map(|g| GuildId::new(g as u64)),
+        channel_id: ChannelId::new(row.get::<_, i64>(3)? as u64),
+        message_id: MessageId::new(row.get::<_, i64>(4)? as u64),
+        requester: UserId::new(row.get::<_, i64>(5)? as u64),
+        crafters: RoleId::new(row.get::<_, i64>(6)? as u64),
+        price: row.get(7)?,
+        set_name: row.get(8)?,
+        details: row.get(9)?,
+        pieces: vec![],
+        created_at: row.get(10)?,
+        updated_at: row.get(11)?,
+        status: parse_column(row, 12)?,
+        assignee: row.get::<_, Option<i64>>(13)?.map(|u| UserId::new(u as u64)),
+        thread: row.get::<_, Option<i64>>(14)?.map(|t| ChannelId::new(t as u64)),
+    })
+}
+
+/// Timeout stored in `guild_settings`, 0 when sessions never expire
+fn timeout_from_column(minutes: Option<u32>) -> Option<u32> {
+    match minutes {
+        Some(0) => None,
+        Some(minutes) => Some(minutes),
+        None => Some(DEFAULT_TIMEOUT_MINUTES)
+    }
+}
+
+fn parse_column<T: FromStr<Err = strum::ParseError>>(row: &Row, idx: usize) -> rusqlite::Result<T> {
+    let value: String = row.get(idx)?;
+    T::from_str(&value)
+        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(idx, Type::Text, Box::new(e)))
+}
+
+fn with_pieces(conn: &Connection, mut order: Order) -> anyhow::Result<Order> {
+    let mut stmt = conn.prepare(
+        "SELECT spec, category, kind, weight, piece_trait, enchantment, quality, level FROM order_pieces WHERE order_id = ?1 ORDER BY position"
+    )?;
+    let rows = stmt.query_map(params![order.id], |row| Ok((row.get::<_, Option<String>>(0)?, PieceRow {
+        category: row.get(1)?,
+        kind: row.get(2)?,
+        weight: row.get(3)?,
+        piece_trait: row.get(4)?,
+        enchantment: row.get(5)?,
+        quality: row.get(6)?,
+        level: row.get(7)?,
+    })))?;
+
+    for row in rows {
+        let piece = match row? {
+            (Some(spec), _) => SetPiece::from_json(&spec).map_err(|e| anyhow!(e))?,
+            (None, legacy) => legacy.try_into()?
+        };
+        order.pieces.push(piece);
+    }
+    Ok(order)
+}
+
+/// A piece stored field by field, as orders were before pieces were kept as JSON
+struct PieceRow {
+    category: Option<String>,
+    kind: Option<String>,
+    weight: Option<String>,
+    piece_trait: Option<String>,
+    enchantment: Option<String>,
+    quality: Option<String>,
+    level: Option<String>
+}
+
+impl TryFrom<PieceRow> for SetPiece {
+    type Error = anyhow::Error;
+
+    fn try_from(row: PieceRow) -> Result<Self, Self::Error> {
+        let field = |value: Option<String>, name: &str| value.ok_or_else(|| anyhow!("order piece without {}", name));
+        let quality = GearQuality::from_str(&field(row.quality, "quality")?)?;
+        let level = CraftingLevel::from_str(&field(row.level, "level")?)?;
+        let kind = field(row.kind, "kind")?;
+        let piece_trait = field(row.piece_trait, "trait")?;
+        match field(row.category, "category")?.as_str() {
+            "weapon" => Ok(SetPiece::Weapon(Weapon {
+                kind: WeaponKind::from_str(&kind)?,
+                weapon_trait: WeaponTraits::from_str(&piece_trait)?,
+                enchantment: row.enchantment.map(|e| WeaponEnchantments::from_str(&e)).transpose()?,
+                quality,
+                level,
+            })),
+            "armour" => Ok(SetPiece::Armour(Armour {
+                kind: ArmourParts::from_str(&kind)?,
+                weight: ArmourWeights::from_str(&field(row.weight, "weight")?)?,
+                armour_trait: ArmourTraits::from_str(&piece_trait)?,
+                enchantment: row.enchantment.map(|e| ArmourEnchantments::from_str(&e)).transpose()?,
+                quality,
+                level,
+            })),
+            "jewelry" => Ok(SetPiece::Jewelry(Jewelry {
+                kind: Jewelries::from_str(&kind)?,
+                jewelry_trait: JewelryTraits::from_str(&piece_trait)?,
+                enchantment: row.enchantment.map(|e| JewelryEnchantments::from_str(&e)).transpose()?,
+                quality,
+                level,
+            })),
+            category => Err(anyhow!("unknown piece category '{}'", category))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::OrderKind;
+
+    fn store() -> SqliteStore {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("PRAGMA foreign_keys = ON;").unwrap();
+        migrate(&conn).unwrap();
+        SqliteStore { conn: Mutex::new(conn) }
+    }
+
+    /// Database as it was at `version`, to be migrated from there
+    fn database_at(version: usize) -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        for (idx, migration) in MIGRATIONS[..version].iter().enumerate() {
+            conn.execute_batch(&format!("BEGIN; {} PRAGMA user_version = {}; COMMIT;", migration, idx + 1)).unwrap();
+        }
+        conn
+    }
+
+    fn session(user: u64, guild_id: Option<u64>) -> GearSession {
+        GearSession {
+            user: UserId::new(user),
+            guild_id: guild_id.map(GuildId::new),
+            channel_id: ChannelId::new(1),
+            crafters: RoleId::new(2),
+            price: Some(500.0),
+            delivery: Delivery::Dm,
+            wizard: GearWizard::set("Cólera de la orden"),
+            updated_at: now()
+        }
+    }
+
+    #[test]
+    fn stores_each_answer_to_a_turn_once() {
+        let store = store();
+        let mut started = session(10, Some(20));
+        assert!(store.save_session(&started, None).unwrap());
+
+        started.wizard.turn += 1;
+        assert!(store.save_session(&started, Some(0)).unwrap());
+        assert!(!store.save_session(&started, Some(0)).unwrap());
+        assert_eq!(store.session(started.user).unwrap().unwrap().wizard.turn, 1);
+    }
+
+    #[test]
+    fn takes_a_session_once() {
+        let store = store();
+        let started = session(10, Some(20));
+        store.save_session(&started, None).unwrap();
+
+        assert!(store.take_session(started.user, 1).unwrap().is_none());
+        let taken = store.take_session(started.user, 0).unwrap().unwrap();
+        assert_eq!(taken.wizard.set_name, "Cólera de la orden");
+        assert!(store.take_session(started.user, 0).unwrap().is_none());
+        assert!(store.session(started.user).unwrap().is_none());
+    }
+
+    #[test]
+    fn prunes_sessions_of_guilds_saved_before_timeouts() {
+        let conn = database_at(8);
+        conn.execute_batch(&format!(
+            "INSERT INTO guild_settings (guild_id, delivery, updated_at) VALUES (20, 'dm', 0);
+            INSERT INTO gear_sessions (user_id, guild_id, channel_id, crafter_role_id, state, updated_at) VALUES (10, 20, 1, 2, '{}', 0);",
+            session(10, Some(20)).wizard.to_json()
+        )).unwrap();
+        migrate(&conn).unwrap();
+        let store = SqliteStore { conn: Mutex::new(conn) };
+
+        assert!(store.guild_settings(GuildId::new(20)).unwrap().timeout == Some(DEFAULT_TIMEOUT_MINUTES));
+        assert_eq!(store.prune_sessions().unwrap(), 1);
+    }
+
+    #[test]
+    fn prunes_sessions_by_the_timeout_of_their_guild() {
+        let store = store();
+        let never = GuildSettings { timeout: None, ..GuildSettings::default() };
+        store.save_guild_settings(GuildId::new(21), &never).unwrap();
+        assert!(store.guild_settings(GuildId::new(21)).unwrap().timeout.is_none());
+        let long = GuildSettings { timeout: Some(60), ..GuildSettings::default() };
+        store.save_guild_settings(GuildId::new(22), &long).unwrap();
+
+        // Saving stamps the time of the answer, so how long each session waited is written afterwards
+        for (user, guild_id, minutes) in [(1, None, 10), (2, Some(20), 10), (3, Some(21), 1000), (4, Some(22), 10), (5, Some(22), 61), (6, Some(20), 1)] {
+            store.save_session(&session(user, guild_id), None).unwrap();
+            store.conn().unwrap().execute(
+                "UPDATE gear_sessions SET updated_at = ?2 WHERE user_id = ?1",
+                params![user as i64, now() - minutes * 60]
+            ).unwrap();
+        }
+
+        assert_eq!(store.prune_sessions().unwrap(), 3);
+        let left: Vec<u64> = (1..=6).filter(|user| store.session(UserId::new(*user)).unwrap().is_some()).collect();
+        assert_eq!(left, [3, 4, 6]);
+    }
+
+    #[test]
+    fn keeps_armour_research_for_every_weight() {
+        let conn = database_at(10);
+        conn.execute_batch(
+            "INSERT INTO research (crafter_id, item, research_trait, updated_at) VALUES
+                (10, 'Cabeza', 'Divinidad', 0), (10, 'Escudo', 'Divinidad', 0), (10, 'Anillo', 'Arcanidad', 0);"
+        ).unwrap();
+        migrate(&conn).unwrap();
+        let store = SqliteStore { conn: Mutex::new(conn) };
+
+        let known: Vec<String> = store.known_traits(UserId::new(10)).unwrap().iter()
+            .map(|known| format!("{}: {}", known.item, known.gear_trait))
+            .collect();
+        assert_eq!(known, ["Anillo: Arcanidad", "Cabeza Ligera: Divinidad", "Cabeza Media: Divinidad", "Cabeza Pesada: Divinidad", "Escudo: Divinidad"]);
+    }
+
+    fn new_order(pieces: Vec<SetPiece>) -> NewOrder {
+        NewOrder {
+            kind: OrderKind::Gear,
+            guild_id: Some(GuildId::new(20)),
+            channel_id: ChannelId::new(1),
+            message_id: MessageId::new(30),
+            requester: UserId::new(10),
+            crafters: RoleId::new(2),
+            price: Some(500.0),
+            set_name: Some("Cólera de la orden".to_string()),
+            pieces,
+            details: None,
+            thread: None
+        }
+    }
+
+    #[test]
+    fn migrates_an_empty_database_to_the_last_version() {
+        let conn = Connection::open_in_memory().unwrap();
+        migrate(&conn).unwrap();
+        let version: usize = conn.query_row("PRAGMA user_version", [], |row| row.get(0)).unwrap();
+        assert_eq!(version, MIGRATIONS.len());
+        // Migrating again leaves it as it is
+        migrate(&conn).unwrap();
+    }
+
+    #[test]
+    fn migrates_from_the_version_before_the_last() {
+        let conn = database_at(MIGRATIONS.len() - 1);
+        conn.execute_batch(
+            "INSERT INTO guild_settings (guild_id, delivery, timeout_minutes, updated_at) VALUES (20, 'dm', NULL, 0), (21, 'dm', 15, 0);"
+        ).unwrap();
+        migrate(&conn).unwrap();
+        let version: usize = conn.query_row("PRAGMA user_version", [], |row| row.get(0)).unwrap();
+        assert_eq!(version, MIGRATIONS.len());
+        let store = SqliteStore { conn: Mutex::new(conn) };
+        assert!(store.guild_settings(GuildId::new(20)).unwrap().timeout == Some(DEFAULT_TIMEOUT_MINUTES));
+        assert!(store.guild_settings(GuildId::new(21)).unwrap().timeout == Some(15));
+    }
+
+    #[test]
+    fn reads_pieces_stored_field_by_field() {
+        let conn = database_at(11);
+        conn.execute_batch(
+            "INSERT INTO orders (id, kind, guild_id, channel_id, message_id, requester_id, crafter_role_id, price, set_name, created_at, updated_at)
+                VALUES (1, 'Equipamiento', 20, 1, 30, 10, 2, 500, 'Cólera de la orden', 0, 0);
+            INSERT INTO order_pieces (order_id, position, category, kind, weight, piece_trait, enchantment, quality, level) VALUES
+                (1, 0, 'armour', 'Cabeza', 'Pesada', 'Divinidad', 'Glifo de salud', 'Amarilla', 'CP150'),
+                (1, 1, 'weapon', 'Mandoble', NULL, 'Precisión', NULL, 'Morada', 'CP160'),
+                (1, 2, 'jewelry', 'Anillo', NULL, 'Arcanidad', NULL, 'Azul', 'CP160');"
+        ).unwrap();
+        migrate(&conn).unwrap();
+        let store = SqliteStore { conn: Mutex::new(conn) };
+
+        let order = store.get_order(1).unwrap().unwrap();
+        let pieces: Vec<String> = order.pieces.iter().map(SetPiece::to_json).collect();
+        let expected: Vec<String> = [
+            "Cabeza:Pesada:Divinidad:Amarilla:Glifo de salud:CP150",
+            "Mandoble:Precisión:Morada",
+            "Anillo:Arcanidad:Azul"
+        ].iter().map(|spec| seht_core::piece::parse_piece(spec, CraftingLevel::CP160).unwrap().to_json()).collect();
+        assert_eq!(pieces, expected);
+        assert!(order.status == OrderStatus::Open);
+    }
+
+    #[test]
+    fn stores_orders_with_their_pieces() {
+        let store = store();
+        let pieces = vec![seht_core::piece::parse_piece("Cabeza:Pesada:Divinidad:Amarilla:Glifo de salud:CP150", CraftingLevel::CP160).unwrap()];
+        let id = store.create_order(&new_order(pieces.clone())).unwrap();
+
+        let order = store.get_order_by_message(MessageId::new(30)).unwrap().unwrap();
+        assert_eq!(order.id, id);
+        assert_eq!(order.pieces.iter().map(SetPiece::to_json).collect::<Vec<_>>(), [pieces[0].to_json()]);
+        assert_eq!(store.orders_by_requester(UserId::new(10)).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn only_the_first_of_two_crafters_claims_an_order() {
+        let store = store();
+        let id = store.create_order(&new_order(vec![])).unwrap();
+
+        assert!(store.update_order_status(id, OrderStatus::Open, OrderStatus::Claimed, Some(UserId::new(11))).unwrap());
+        assert!(!store.update_order_status(id, OrderStatus::Open, OrderStatus::Claimed, Some(UserId::new(12))).unwrap());
+        let order = store.get_order(id).unwrap().unwrap();
+        assert!(order.status == OrderStatus::Claimed);
+        assert_eq!(order.assignee, Some(UserId::new(11)));
+    }
+
+    #[test]
+    fn finds_the_crafters_knowing_every_trait() {
+        let store = store();
+        let research = |text| seht_core::entities::research::parse_research(text).unwrap();
+        store.learn_traits(UserId::new(11), &research("Cabeza Pesada: Divinidad; Anillo: Arcanidad")).unwrap();
+        store.learn_traits(UserId::new(12), &research("Cabeza Ligera: Divinidad; Anillo: Arcanidad")).unwrap();
+        store.learn_traits(UserId::new(13), &research("Cabeza Pesada: Divinidad")).unwrap();
+
+        assert_eq!(store.crafters_knowing(&research("Cabeza Pesada: Divinidad; Anillo: Arcanidad")).unwrap(), [UserId::new(11)]);
+        let mut anyone = store.crafters_knowing(&[]).unwrap();
+        anyone.sort();
+        assert_eq!(anyone, [UserId::new(11), UserId::new(12), UserId::new(13)]);
+
+        store.forget_traits(UserId::new(11), &research("Anillo: Arcanidad")).unwrap();
+        assert!(store.crafters_knowing(&research("Cabeza Pesada: Divinidad; Anillo: Arcanidad")).unwrap().is_empty());
+    }
+}